@@ -1,62 +1,428 @@
-use crate::domain::{Asset, RiskCalculationResult};
+use crate::domain::{
+    apply_incremental_risk_reduction, find_alternative_conflicts, normalize_priority_scores, Asset,
+    CurrencyRates, RiskCalculationResult, RiskLevelConfig, SafetyWeights,
+};
+use crate::error::CapAllocError;
 use crate::repository::{AssetRepository, FormulaRepository};
 use crate::services::{OptimizationSolution, PortfolioOptimizer, RiskCalculationService};
 use rayon::prelude::*;
-use std::error::Error;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
 use std::time::{Duration, Instant};
 
+/// How often the progress reporter prints while calculating
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Outcome of `CapitalAllocationApp::validate`: dataset counts and every problem found
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub assets: usize,
+    pub alternatives: usize,
+    pub issues: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Successful results, failed assets with their error messages, and elapsed time
+pub type Calculated = (Vec<RiskCalculationResult>, Vec<(Asset, String)>, Duration);
+
 /// Application service orchestrating the capital allocation workflow
 pub struct CapitalAllocationApp {
-    repository: Box<dyn AssetRepository + Send + Sync>,
+    repository: Box<dyn AssetRepository>,
     calculator: RiskCalculationService,
     optimizer: PortfolioOptimizer,
+    currency_rates: Option<CurrencyRates>,
+    cost_inflation: Option<f64>,
+    pof_scale: Option<f64>,
+    cancel: Option<&'static AtomicBool>,
 }
 
 impl CapitalAllocationApp {
     pub fn new(
-        repository: Box<dyn AssetRepository + Send + Sync>,
+        repository: Box<dyn AssetRepository>,
         formula_repository: Box<dyn FormulaRepository>,
     ) -> Self {
         Self {
             repository,
             calculator: RiskCalculationService::new(formula_repository),
             optimizer: PortfolioOptimizer::new(),
+            currency_rates: None,
+            cost_inflation: None,
+            pof_scale: None,
+            cancel: None,
+        }
+    }
+
+    /// Convert money columns of loaded assets to the rates' base currency
+    pub fn with_currency_rates(mut self, rates: CurrencyRates) -> Self {
+        self.currency_rates = Some(rates);
+        self
+    }
+
+    /// Raise every loaded cost by `percent` (10.0 is 10%) before calculation,
+    /// to model a uniform cost increase
+    pub fn with_cost_inflation(mut self, percent: f64) -> Self {
+        self.cost_inflation = Some(percent);
+        self
+    }
+
+    /// Multiply every loaded post-action PoF by `factor` (0.8 is 20% more reliable)
+    /// before calculation, clamped to [0, 1]
+    pub fn with_pof_scale(mut self, factor: f64) -> Self {
+        self.pof_scale = Some(factor);
+        self
+    }
+
+    /// Stop calculations early once `cancel` is set, such as from a Ctrl-C handler
+    pub fn with_cancel_flag(mut self, cancel: &'static AtomicBool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Classify safety levels with custom high-risk and critical labels
+    pub fn with_risk_levels(mut self, risk_levels: RiskLevelConfig) -> Self {
+        self.calculator = self.calculator.with_risk_levels(risk_levels);
+        self
+    }
+
+    /// Weight the priority score by safety level
+    pub fn with_safety_weights(mut self, safety_weights: SafetyWeights) -> Self {
+        self.calculator = self.calculator.with_safety_weights(safety_weights);
+        self
+    }
+
+    /// Set the annual discount rate used by the time value formulas
+    pub fn with_discount_rate(mut self, annual_rate: f64) -> Self {
+        self.calculator = self.calculator.with_discount_rate(annual_rate);
+        self
+    }
+
+    /// Calculate a risk reduction band with post-action PoF perturbed by ±`percent`
+    pub fn with_pof_uncertainty(mut self, percent: f64) -> Self {
+        self.calculator = self.calculator.with_pof_uncertainty(percent);
+        self
+    }
+
+    /// Set the probability of failure assumed for doing nothing (default 1.0)
+    pub fn with_baseline_pof(mut self, baseline_pof: f64) -> Self {
+        self.calculator = self.calculator.with_baseline_pof(baseline_pof);
+        self
+    }
+
+    /// Set the decimal places used to round money outputs, ROI/priority scores and ratings
+    pub fn with_precision(mut self, money_dp: u32, score_dp: u32, rating_dp: u32) -> Self {
+        self.calculator = self
+            .calculator
+            .with_precision(money_dp, score_dp, rating_dp);
+        self
+    }
+
+    /// Optimize with `optimizer`, which carries the do-nothing, category cap and
+    /// timeout settings (see `PortfolioOptimizer`)
+    pub fn with_optimizer(mut self, optimizer: PortfolioOptimizer) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+
+    /// Load assets from repository, normalized to the base currency when rates are set
+    /// and with costs inflated and PoF scaled when those are set
+    pub fn load_assets(&self) -> Result<Vec<Asset>, CapAllocError> {
+        let mut assets = self.repository.load_all()?;
+        for asset in &mut assets {
+            self.normalize_asset(asset)?;
         }
+        Ok(assets)
     }
 
-    /// Load assets from repository
-    pub fn load_assets(&self) -> Result<Vec<Asset>, Box<dyn Error>> {
-        self.repository.load_all()
+    /// Convert `asset` to the base currency, inflate its cost and scale its PoF,
+    /// when those are set
+    fn normalize_asset(&self, asset: &mut Asset) -> Result<(), CapAllocError> {
+        if let Some(rates) = &self.currency_rates {
+            rates
+                .normalize(std::slice::from_mut(asset))
+                .map_err(CapAllocError::Validation)?;
+        }
+        if let Some(percent) = self.cost_inflation {
+            asset.cost_usd *= 1.0 + percent / 100.0;
+        }
+        if let Some(factor) = self.pof_scale {
+            asset.pof_post_action = (asset.pof_post_action * factor).clamp(0.0, 1.0);
+        }
+        Ok(())
+    }
+
+    /// Load assets and check them without calculating: row-level validation,
+    /// conflicting duplicate alternatives, and optimizer settings
+    /// Input that cannot be read or parsed at all is returned as an error
+    pub fn validate(&self) -> Result<ValidationReport, CapAllocError> {
+        let assets = self.load_assets()?;
+        let mut issues: Vec<String> = assets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, asset)| {
+                asset.validate().err().map(|problems| {
+                    format!(
+                        "row {} ({} / {}): {}",
+                        i + 1,
+                        asset.asset_id,
+                        asset.alternative_id,
+                        problems
+                    )
+                })
+            })
+            .collect();
+
+        for conflict in find_alternative_conflicts(&assets) {
+            issues.push(format!(
+                "{} / {}: listed {} times with different cost or PoF",
+                conflict.asset_id,
+                conflict.alternative_id,
+                conflict.variants.len()
+            ));
+        }
+        if let Err(e) = self.optimizer.validate_category_caps() {
+            issues.push(e.to_string());
+        }
+
+        let mut asset_ids: Vec<&str> = assets.iter().map(|a| a.asset_id.as_str()).collect();
+        asset_ids.sort_unstable();
+        asset_ids.dedup();
+
+        Ok(ValidationReport {
+            assets: asset_ids.len(),
+            alternatives: assets.len(),
+            issues,
+        })
     }
 
     /// Calculate risk metrics for all assets in parallel
-    pub fn calculate_all_risks(
+    /// Returns successful results, failed assets with their error messages, and elapsed time
+    pub fn calculate_all_risks(&self, assets: Vec<Asset>) -> Result<Calculated, CapAllocError> {
+        self.calculate_all_risks_with_progress(assets, false)
+    }
+
+    /// Calculate risk metrics for all assets in parallel, optionally printing
+    /// "processed N/total" to stderr while the calculation runs
+    /// Returns `Cancelled` if the cancel flag is set before every asset is processed
+    pub fn calculate_all_risks_with_progress(
         &self,
         assets: Vec<Asset>,
-    ) -> (Vec<RiskCalculationResult>, Duration) {
-        let start = Instant::now();
+        show_progress: bool,
+    ) -> Result<Calculated, CapAllocError> {
+        let ((outcomes, processed), duration) =
+            Self::with_progress(assets.len(), show_progress, |processed| {
+                let outcomes = assets
+                    .par_iter()
+                    .map(|asset| {
+                        if self.is_cancelled() {
+                            return None;
+                        }
+                        let outcome = self.calculator.calculate(asset).map_err(|e| e.to_string());
+                        processed.fetch_add(1, Ordering::Relaxed);
+                        Some(outcome)
+                    })
+                    .collect::<Vec<Option<Result<RiskCalculationResult, String>>>>();
+                (outcomes, processed.load(Ordering::Relaxed))
+            });
+        self.check_cancelled(processed, assets.len())?;
 
-        let results: Vec<RiskCalculationResult> = assets
-            .par_iter()
-            .filter_map(|asset| self.calculator.calculate(asset).ok())
+        // Outcomes are in input order, so failures pair back up with their asset
+        let mut results = Vec::with_capacity(outcomes.len());
+        let mut failures = Vec::new();
+        for (outcome, asset) in outcomes.into_iter().zip(assets) {
+            match outcome {
+                Some(Ok(result)) => results.push(result),
+                Some(Err(reason)) => failures.push((asset, reason)),
+                None => {}
+            }
+        }
+
+        apply_incremental_risk_reduction(&mut results);
+
+        Ok((results, failures, duration))
+    }
+
+    /// Calculate risk metrics in parallel but keep only the `k` highest-priority
+    /// results, best first, without materializing the full result set
+    /// Each worker keeps a bounded min-heap; one do-nothing baseline per asset is
+    /// retained separately so incremental risk reduction stays relative to Defer
+    pub fn calculate_top_by_priority(
+        &self,
+        assets: Vec<Asset>,
+        k: usize,
+        show_progress: bool,
+    ) -> Result<Calculated, CapAllocError> {
+        let ((partial, processed), duration) =
+            Self::with_progress(assets.len(), show_progress, |processed| {
+                let partial = assets
+                    .par_iter()
+                    .enumerate()
+                    .fold(TopK::new, |mut top, (index, asset)| {
+                        if self.is_cancelled() {
+                            return top;
+                        }
+                        match self.calculator.calculate(asset) {
+                            Ok(result) => top.push(index, result, k),
+                            Err(e) => top.failures.push((asset.clone(), e.to_string())),
+                        }
+                        processed.fetch_add(1, Ordering::Relaxed);
+                        top
+                    })
+                    .reduce(TopK::new, |a, b| a.merge(b, k));
+                (partial, processed.load(Ordering::Relaxed))
+            });
+        self.check_cancelled(processed, assets.len())?;
+
+        let mut ranked: Vec<RiskCalculationResult> = partial
+            .heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ByPriority(result))| result)
             .collect();
+        let kept = ranked.len();
+        ranked.extend(partial.do_nothing.into_values().map(|(_, result)| result));
+        apply_incremental_risk_reduction(&mut ranked);
+        ranked.truncate(kept);
+
+        Ok((ranked, partial.failures, duration))
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+
+    /// `Cancelled` if the cancel flag stopped the calculation short of `total`
+    fn check_cancelled(&self, processed: usize, total: usize) -> Result<(), CapAllocError> {
+        if processed < total && self.is_cancelled() {
+            Err(CapAllocError::Cancelled { processed, total })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Run `work` while an optional reporter thread prints how many of `total`
+    /// items have been counted in the shared counter
+    fn with_progress<T: Send>(
+        total: usize,
+        show_progress: bool,
+        work: impl FnOnce(&AtomicUsize) -> T + Send,
+    ) -> (T, Duration) {
+        let processed = AtomicUsize::new(0);
+        let done = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            if show_progress {
+                scope.spawn(|| {
+                    let mut reported = false;
+                    while !done.load(Ordering::Relaxed) {
+                        thread::sleep(PROGRESS_INTERVAL);
+                        if done.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        eprint!(
+                            "\rprocessed {}/{}",
+                            processed.load(Ordering::Relaxed),
+                            total
+                        );
+                        reported = true;
+                    }
+                    if reported {
+                        eprintln!(
+                            "\rprocessed {}/{}",
+                            processed.load(Ordering::Relaxed),
+                            total
+                        );
+                    }
+                });
+            }
+
+            let start = Instant::now();
+            let output = work(&processed);
+            let duration = start.elapsed();
+
+            done.store(true, Ordering::Relaxed);
+            (output, duration)
+        })
+    }
+
+    /// The `n` highest-priority results, best first
+    /// Works on a sorted copy so the caller's ordering is left untouched
+    pub fn top_by_priority(
+        results: &[RiskCalculationResult],
+        n: usize,
+    ) -> Vec<RiskCalculationResult> {
+        let mut sorted = results.to_vec();
+        sorted.sort_by(|a, b| b.effective_priority().total_cmp(&a.effective_priority()));
+        sorted.truncate(n);
+        sorted
+    }
+
+    /// Drop results whose priority score is below `min_priority`
+    /// Returns the remaining results, in their original order, and the number removed
+    pub fn filter_by_min_priority(
+        results: Vec<RiskCalculationResult>,
+        min_priority: f64,
+    ) -> (Vec<RiskCalculationResult>, usize) {
+        let original_len = results.len();
+        let kept: Vec<RiskCalculationResult> = results
+            .into_iter()
+            .filter(|r| r.effective_priority() >= min_priority)
+            .collect();
+        let removed = original_len - kept.len();
+        (kept, removed)
+    }
 
-        let duration = start.elapsed();
-        (results, duration)
+    /// Give every result a dataset-relative `normalized_priority_score`, scaled by the
+    /// critical, safety-weight and remaining-life factors of the priority formula
+    pub fn normalize_priority_scores(&self, results: &mut [RiskCalculationResult]) {
+        normalize_priority_scores(results, |asset| self.calculator.priority_multiplier(asset));
     }
 
     /// Calculate risk metrics for a single asset
     #[allow(dead_code)]
-    pub fn calculate_risk(&self, asset: &Asset) -> Result<RiskCalculationResult, Box<dyn Error>> {
+    pub fn calculate_risk(&self, asset: &Asset) -> Result<RiskCalculationResult, CapAllocError> {
         self.calculator.calculate(asset)
     }
 
+    /// Recalculate one edited asset in place and return its new result
+    /// The asset is converted, inflated and PoF-scaled like `load_assets`, and
+    /// incremental risk reduction is refreshed for every row, since an edited
+    /// do-nothing row shifts its siblings
+    #[allow(dead_code)]
+    pub fn recalculate_one<'a>(
+        &self,
+        results: &'a mut [RiskCalculationResult],
+        index: usize,
+        mut new_asset: Asset,
+    ) -> Result<&'a RiskCalculationResult, CapAllocError> {
+        if index >= results.len() {
+            return Err(CapAllocError::Validation(format!(
+                "Result index {} is out of range for {} results",
+                index,
+                results.len()
+            )));
+        }
+        self.normalize_asset(&mut new_asset)?;
+
+        results[index] = self.calculator.calculate(&new_asset)?;
+        apply_incremental_risk_reduction(results);
+        Ok(&results[index])
+    }
+
     /// Optimize portfolio by maximizing risk reduction
     pub fn optimize_by_risk_reduction(
         &self,
         results: &[RiskCalculationResult],
         budget: f64,
-    ) -> Result<OptimizationSolution, Box<dyn Error>> {
+    ) -> Result<OptimizationSolution, CapAllocError> {
         self.optimizer.optimize_by_risk_reduction(results, budget)
     }
 
@@ -65,7 +431,7 @@ impl CapitalAllocationApp {
         &self,
         results: &[RiskCalculationResult],
         budget: f64,
-    ) -> Result<OptimizationSolution, Box<dyn Error>> {
+    ) -> Result<OptimizationSolution, CapAllocError> {
         self.optimizer.optimize_by_priority(results, budget)
     }
 
@@ -76,48 +442,199 @@ impl CapitalAllocationApp {
         budget: f64,
         risk_weight: f64,
         priority_weight: f64,
-    ) -> Result<OptimizationSolution, Box<dyn Error>> {
+    ) -> Result<OptimizationSolution, CapAllocError> {
         self.optimizer
             .optimize_combined(results, budget, risk_weight, priority_weight)
     }
+
+    /// Maximize risk reduction at each of the given budget levels
+    pub fn budget_sweep(
+        &self,
+        results: &[RiskCalculationResult],
+        budgets: &[f64],
+    ) -> Result<Vec<(f64, OptimizationSolution)>, CapAllocError> {
+        self.optimizer.budget_sweep(results, budgets)
+    }
+
+    /// Non-dominated (cost, risk reduction) solutions for budgets up to `budget`
+    pub fn pareto_frontier(
+        &self,
+        results: &[RiskCalculationResult],
+        budget: f64,
+        steps: usize,
+    ) -> Result<Vec<OptimizationSolution>, CapAllocError> {
+        self.optimizer.pareto_frontier(results, budget, steps)
+    }
+
+    /// Maximize risk reduction across several budget periods, funding each asset at most once
+    pub fn optimize_multi_period(
+        &self,
+        results: &[RiskCalculationResult],
+        budgets: &[f64],
+    ) -> Result<Vec<OptimizationSolution>, CapAllocError> {
+        self.optimizer.optimize_multi_period(results, budgets)
+    }
+
+    /// Cheapest portfolio reaching at least `min_reduction` total risk reduction
+    pub fn optimize_min_cost(
+        &self,
+        results: &[RiskCalculationResult],
+        min_reduction: f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
+        self.optimizer.optimize_min_cost(results, min_reduction)
+    }
+
+    /// Maximize risk reduction, allowing overspend at `overspend_penalty` per dollar
+    pub fn optimize_soft_budget(
+        &self,
+        results: &[RiskCalculationResult],
+        budget: f64,
+        overspend_penalty: f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
+        self.optimizer
+            .optimize_soft_budget(results, budget, overspend_penalty)
+    }
+
+    /// Greedy baseline: fund alternatives by cost effectiveness until the budget runs out
+    pub fn optimize_greedy(
+        &self,
+        results: &[RiskCalculationResult],
+        budget: f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
+        self.optimizer.optimize_greedy(results, budget)
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::domain::Asset;
-    use crate::repository::InMemoryFormulaRepository;
-    use std::error::Error;
+/// Orders results by priority score, ties broken by asset then alternative ID
+struct ByPriority(RiskCalculationResult);
 
-    struct MockRepository {
-        assets: Vec<Asset>,
+impl ByPriority {
+    fn key(&self) -> (&str, &str) {
+        (&self.0.asset.asset_id, &self.0.asset.alternative_id)
+    }
+}
+
+impl PartialEq for ByPriority {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for ByPriority {}
+
+impl PartialOrd for ByPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    impl MockRepository {
-        fn new(assets: Vec<Asset>) -> Self {
-            Self { assets }
+impl Ord for ByPriority {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0
+            .priority_score
+            .total_cmp(&other.0.priority_score)
+            .then_with(|| other.key().cmp(&self.key()))
+    }
+}
+
+/// Per-worker state for the bounded top-K calculation
+struct TopK {
+    heap: BinaryHeap<Reverse<ByPriority>>,
+    /// Baseline per asset ID with its preference key (see `keep_baseline`)
+    do_nothing: HashMap<String, ((bool, usize), RiskCalculationResult)>,
+    failures: Vec<(Asset, String)>,
+}
+
+impl TopK {
+    fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            do_nothing: HashMap::new(),
+            failures: Vec::new(),
         }
     }
 
-    impl AssetRepository for MockRepository {
-        fn load_all(&self) -> Result<Vec<Asset>, Box<dyn Error>> {
-            Ok(self.assets.clone())
+    /// Record the result for input row `index`, evicting the current worst once `k` are held
+    fn push(&mut self, index: usize, result: RiskCalculationResult, k: usize) {
+        if result.is_do_nothing() {
+            let named = result.asset.alternative_id.eq_ignore_ascii_case("Defer");
+            self.keep_baseline((!named, index), result.clone());
         }
+        self.offer(Reverse(ByPriority(result)), k);
     }
 
-    fn create_test_asset(id: &str, alternative: &str, cost: f64) -> Asset {
-        Asset {
-            asset_id: id.to_string(),
-            alternative_id: alternative.to_string(),
-            cost_usd: cost,
-            pof_post_action: 0.05,
-            cof_total_usd: 500000.0,
-            safety_risk_level: "Low".to_string(),
+    /// Keep `result` as its asset's baseline if its key is lower: a row named Defer
+    /// beats other zero-cost rows, then the earlier input row wins, matching the
+    /// baseline `apply_incremental_risk_reduction` picks from the full set
+    fn keep_baseline(&mut self, key: (bool, usize), result: RiskCalculationResult) {
+        match self.do_nothing.entry(result.asset.asset_id.clone()) {
+            Entry::Occupied(mut held) => {
+                if key < held.get().0 {
+                    held.insert((key, result));
+                }
+            }
+            Entry::Vacant(slot) => {
+                slot.insert((key, result));
+            }
         }
     }
 
+    fn offer(&mut self, candidate: Reverse<ByPriority>, k: usize) {
+        if self.heap.len() < k {
+            self.heap.push(candidate);
+        } else if self.heap.peek().is_some_and(|worst| candidate < *worst) {
+            self.heap.pop();
+            self.heap.push(candidate);
+        }
+    }
+
+    fn merge(mut self, other: Self, k: usize) -> Self {
+        for candidate in other.heap {
+            self.offer(candidate, k);
+        }
+        for (key, result) in other.do_nothing.into_values() {
+            self.keep_baseline(key, result);
+        }
+        self.failures.extend(other.failures);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Asset;
+    use crate::repository::{InMemoryAssetRepository, InMemoryFormulaRepository};
+    use formcalc::{Formula, FormulaT};
+    use std::collections::BTreeMap;
+
+    /// Formula set whose ROI formula fails for alternatives costing more than $100K
+    struct CostLimitedFormulaRepository;
+
+    impl FormulaRepository for CostLimitedFormulaRepository {
+        fn load_all(&self) -> Result<Vec<Formula>, CapAllocError> {
+            let mut formulas = InMemoryFormulaRepository::new().load_all()?;
+            formulas.retain(|f| f.name() != "roi");
+            formulas.push(Formula::new(
+                "roi",
+                r#"
+                if (cost > 100000) then
+                    return missing_variable
+                else
+                    return 1.0
+                end
+            "#,
+            ));
+            Ok(formulas)
+        }
+    }
+
+    fn create_test_asset(id: &str, alternative: &str, cost: f64) -> Asset {
+        Asset::fixture(id, alternative, cost)
+    }
+
     fn create_app(assets: Vec<Asset>) -> CapitalAllocationApp {
-        let repository = Box::new(MockRepository::new(assets));
+        let repository = Box::new(InMemoryAssetRepository::new(assets));
         let formula_repository = Box::new(InMemoryFormulaRepository::new());
         CapitalAllocationApp::new(repository, formula_repository)
     }
@@ -134,6 +651,100 @@ mod tests {
         assert_eq!(loaded.len(), 2);
     }
 
+    #[test]
+    fn test_cost_inflation_scales_cost_and_roi() {
+        let assets = vec![create_test_asset("IT_SYSTEM_001", "Pilot_Program", 1000.0)];
+        let calculate = |app: CapitalAllocationApp| {
+            let assets = app.load_assets().unwrap();
+            app.calculate_risk(&assets[0]).unwrap()
+        };
+
+        let base = calculate(create_app(assets.clone()));
+        let inflated = calculate(create_app(assets).with_cost_inflation(10.0));
+
+        let adjusted_cost = |r: &RiskCalculationResult| r.risk_reduction / r.roi.unwrap();
+        assert!((inflated.asset.cost_usd - 1100.0).abs() < 1e-9);
+        assert_eq!(inflated.risk_reduction, base.risk_reduction);
+        assert!((adjusted_cost(&inflated) / adjusted_cost(&base) - 1.1).abs() < 1e-3);
+        assert!((inflated.roi.unwrap() / base.roi.unwrap() - 1.0 / 1.1).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_recalculate_one_normalizes_like_load_assets() {
+        let assets = vec![create_test_asset("IT_SYSTEM_001", "Pilot_Program", 1000.0)];
+        let app = create_app(assets)
+            .with_cost_inflation(10.0)
+            .with_pof_scale(0.5);
+        let loaded = app.load_assets().unwrap();
+        let (mut results, _, _) = app.calculate_all_risks(loaded).unwrap();
+
+        // The edit is in input terms, so it is inflated and scaled just like a loaded row
+        let edited = create_test_asset("IT_SYSTEM_001", "Pilot_Program", 2000.0);
+        let mut normalized = edited.clone();
+        normalized.cost_usd = 2200.0;
+        normalized.pof_post_action = 0.025;
+        let expected = app.calculate_risk(&normalized).unwrap();
+        let updated = app.recalculate_one(&mut results, 0, edited).unwrap();
+        assert!((updated.asset.cost_usd - 2200.0).abs() < 1e-9);
+        assert!((updated.asset.pof_post_action - 0.025).abs() < 1e-12);
+        assert_eq!(updated.post_action_risk, expected.post_action_risk);
+    }
+
+    #[test]
+    fn test_pof_scale_lowers_post_action_risk_and_clamps() {
+        let assets = vec![create_test_asset("IT_SYSTEM_001", "Pilot_Program", 1000.0)];
+        let calculate = |app: CapitalAllocationApp| {
+            let assets = app.load_assets().unwrap();
+            app.calculate_risk(&assets[0]).unwrap()
+        };
+
+        let base = calculate(create_app(assets.clone()));
+        let scaled = calculate(create_app(assets.clone()).with_pof_scale(0.8));
+        assert!((scaled.asset.pof_post_action - 0.04).abs() < 1e-12);
+        assert!(scaled.post_action_risk < base.post_action_risk);
+
+        let loaded = |factor: f64| {
+            create_app(assets.clone())
+                .with_pof_scale(factor)
+                .load_assets()
+                .unwrap()[0]
+                .pof_post_action
+        };
+        assert_eq!(loaded(100.0), 1.0);
+        assert_eq!(loaded(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_validate_reports_counts_and_issues() {
+        let valid = create_app(vec![
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0),
+            create_test_asset("IT_SYSTEM_001", "Full_Implementation", 120000.0),
+            create_test_asset("DATACENTER_002", "Pilot_Program", 80000.0),
+        ]);
+        let report = valid.validate().unwrap();
+        assert!(report.is_valid(), "{:?}", report.issues);
+        assert_eq!(report.assets, 2);
+        assert_eq!(report.alternatives, 3);
+
+        let mut bad_pof = create_test_asset("IT_SYSTEM_001", "Full_Implementation", 120000.0);
+        bad_pof.pof_post_action = 2.0;
+        let invalid = create_app(vec![
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0),
+            bad_pof,
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program", 50000.0),
+        ])
+        .with_optimizer(
+            PortfolioOptimizer::new()
+                .with_category_caps(BTreeMap::from([("IT".to_string(), -1.0)])),
+        );
+        let report = invalid.validate().unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.issues.len(), 3, "{:?}", report.issues);
+        assert!(report.issues[0].starts_with("row 2 (IT_SYSTEM_001 / Full_Implementation)"));
+        assert!(report.issues[1].contains("IT_SYSTEM_001 / Pilot_Program"));
+        assert!(report.issues[2].contains("category IT"));
+    }
+
     #[test]
     fn test_calculate_risk_single() {
         let assets = vec![create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0)];
@@ -143,6 +754,32 @@ mod tests {
         assert!(result.risk_reduction > 0.0);
     }
 
+    #[test]
+    fn test_recalculate_one_updates_only_that_entry() {
+        let assets = vec![
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0),
+            create_test_asset("DATACENTER_002", "Partial_Implementation", 15000.0),
+        ];
+        let app = create_app(assets.clone());
+        let (mut results, _, _) = app.calculate_all_risks(assets.clone()).unwrap();
+        let before = results.clone();
+
+        let mut edited = assets[1].clone();
+        edited.cost_usd = 30000.0;
+        let updated = app.recalculate_one(&mut results, 1, edited).unwrap();
+
+        assert_eq!(updated.asset.cost_usd, 30000.0);
+        assert!(updated.roi.unwrap() < before[1].roi.unwrap());
+        assert_eq!(results[0].roi, before[0].roi);
+        assert_eq!(results[0].priority_score, before[0].priority_score);
+        assert_eq!(results[1].risk_reduction, before[1].risk_reduction);
+
+        assert!(matches!(
+            app.recalculate_one(&mut results, 2, assets[0].clone()),
+            Err(CapAllocError::Validation(_))
+        ));
+    }
+
     #[test]
     fn test_calculate_all_risks() {
         let assets = vec![
@@ -152,9 +789,10 @@ mod tests {
         ];
         let app = create_app(assets.clone());
 
-        let (results, duration) = app.calculate_all_risks(assets);
+        let (results, failures, duration) = app.calculate_all_risks(assets).unwrap();
 
         assert_eq!(results.len(), 3);
+        assert!(failures.is_empty());
         assert!(duration.as_nanos() > 0);
     }
 
@@ -166,7 +804,310 @@ mod tests {
 
         let app = create_app(assets.clone());
 
-        let (results, _duration) = app.calculate_all_risks(assets);
+        let (results, _failures, _duration) = app.calculate_all_risks(assets).unwrap();
         assert_eq!(results.len(), 100);
     }
+
+    #[test]
+    fn test_calculate_all_risks_collects_failures() {
+        let assets = vec![
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0),
+            create_test_asset("IT_SYSTEM_001", "Full_Implementation", 120000.0),
+            create_test_asset("DATACENTER_002", "Partial_Implementation", 15000.0),
+            create_test_asset("CLOUD_MIGRATION_003", "Full_Implementation", 200000.0),
+        ];
+        let repository = Box::new(InMemoryAssetRepository::new(assets.clone()));
+        let app = CapitalAllocationApp::new(repository, Box::new(CostLimitedFormulaRepository));
+
+        let (results, failures, _duration) = app.calculate_all_risks(assets).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(failures.len(), 2);
+        for (asset, message) in &failures {
+            assert!(asset.cost_usd > 100000.0);
+            assert!(message.contains(&asset.asset_id));
+            assert!(message.contains("roi"));
+        }
+    }
+
+    #[test]
+    fn test_cancel_flag_stops_calculation() {
+        static CANCEL: AtomicBool = AtomicBool::new(false);
+        let assets: Vec<Asset> = (1..=20)
+            .map(|id| create_test_asset(&format!("ASSET_{:03}", id), "Optimize", 10000.0))
+            .collect();
+        let app = create_app(assets.clone()).with_cancel_flag(&CANCEL);
+
+        // An unset flag changes nothing
+        let (results, _, _) = app.calculate_all_risks(assets.clone()).unwrap();
+        assert_eq!(results.len(), 20);
+
+        CANCEL.store(true, Ordering::Relaxed);
+        let cancelled = app.calculate_all_risks(assets.clone()).unwrap_err();
+        assert!(matches!(
+            cancelled,
+            CapAllocError::Cancelled {
+                processed: 0,
+                total: 20
+            }
+        ));
+        assert_eq!(
+            cancelled.to_string(),
+            "Calculation cancelled after 0 of 20 alternatives"
+        );
+        assert!(matches!(
+            app.calculate_top_by_priority(assets, 5, false),
+            Err(CapAllocError::Cancelled { total: 20, .. })
+        ));
+        // Nothing left to cancel
+        assert!(app.calculate_all_risks(Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn test_calculate_with_progress_matches_plain() {
+        let assets: Vec<Asset> = (1..=50)
+            .map(|id| create_test_asset(&format!("ASSET_{:03}", id), "Optimize", 10000.0))
+            .collect();
+        let app = create_app(assets.clone());
+
+        let (with_progress, failures, _) = app
+            .calculate_all_risks_with_progress(assets.clone(), true)
+            .unwrap();
+        let (plain, _, _) = app.calculate_all_risks(assets).unwrap();
+
+        assert!(failures.is_empty());
+        assert_eq!(with_progress.len(), plain.len());
+        for (a, b) in with_progress.iter().zip(&plain) {
+            assert_eq!(a.asset.asset_id, b.asset.asset_id);
+            assert_eq!(a.priority_score, b.priority_score);
+        }
+    }
+
+    #[test]
+    fn test_top_by_priority() {
+        let assets = vec![
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0),
+            create_test_asset("DATACENTER_002", "Partial_Implementation", 15000.0),
+            create_test_asset("CLOUD_MIGRATION_003", "Full_Implementation", 200000.0),
+            create_test_asset("WAREHOUSE_004", "Full_Implementation", 5000.0),
+        ];
+        let app = create_app(assets.clone());
+        let (results, _failures, _duration) = app.calculate_all_risks(assets).unwrap();
+
+        let top = CapitalAllocationApp::top_by_priority(&results, 2);
+
+        assert_eq!(top.len(), 2);
+        let mut scores: Vec<f64> = results.iter().map(|r| r.priority_score).collect();
+        scores.sort_by(|a, b| b.total_cmp(a));
+        assert_eq!(top[0].priority_score, scores[0]);
+        assert_eq!(top[1].priority_score, scores[1]);
+        assert_eq!(results.len(), 4);
+    }
+
+    #[test]
+    fn test_filter_by_min_priority() {
+        let assets = vec![
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0),
+            create_test_asset("DATACENTER_002", "Partial_Implementation", 15000.0),
+            create_test_asset("CLOUD_MIGRATION_003", "Full_Implementation", 200000.0),
+            create_test_asset("WAREHOUSE_004", "Full_Implementation", 5000.0),
+        ];
+        let app = create_app(assets.clone());
+        let (results, _failures, _duration) = app.calculate_all_risks(assets).unwrap();
+
+        let mut scores: Vec<f64> = results.iter().map(|r| r.priority_score).collect();
+        scores.sort_by(|a, b| b.total_cmp(a));
+        let threshold = scores[1];
+
+        let (kept, removed) = CapitalAllocationApp::filter_by_min_priority(results, threshold);
+
+        // The threshold itself is kept
+        assert_eq!(kept.len() + removed, 4);
+        assert!(kept.len() >= 2);
+        assert!(kept.iter().all(|r| r.priority_score >= threshold));
+        assert!(kept.iter().any(|r| r.priority_score == threshold));
+
+        let (none_kept, removed) =
+            CapitalAllocationApp::filter_by_min_priority(kept, f64::INFINITY);
+        assert!(none_kept.is_empty());
+        assert!(removed >= 2);
+    }
+
+    #[test]
+    fn test_calculate_top_by_priority_matches_full_sort() {
+        let assets: Vec<Asset> = (1..=60)
+            .map(|id| {
+                let mut asset = create_test_asset(
+                    &format!("ASSET_{:03}", id % 25),
+                    &format!("ALT_{}", id),
+                    1000.0 + (id * 7919 % 60) as f64 * 1000.0,
+                );
+                asset.pof_post_action = (id % 13) as f64 / 100.0;
+                asset
+            })
+            .collect();
+        let app = create_app(assets.clone());
+
+        let (all, _, _) = app.calculate_all_risks(assets.clone()).unwrap();
+        let mut expected: Vec<f64> = all.iter().map(|r| r.priority_score).collect();
+        expected.sort_by(|a, b| b.total_cmp(a));
+
+        for k in [0, 1, 10, 60, 100] {
+            let (top, failures, _) = app
+                .calculate_top_by_priority(assets.clone(), k, false)
+                .unwrap();
+            let scores: Vec<f64> = top.iter().map(|r| r.priority_score).collect();
+            assert!(failures.is_empty());
+            assert_eq!(scores, expected[..k.min(expected.len())]);
+        }
+    }
+
+    #[test]
+    fn test_calculate_top_by_priority_keeps_incremental_and_failures() {
+        let mut defer = create_test_asset("IT_SYSTEM_001", "Defer", 0.0);
+        defer.pof_post_action = 0.2;
+        let assets = vec![
+            defer,
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0),
+            create_test_asset("IT_SYSTEM_001", "Full_Implementation", 120000.0),
+            create_test_asset("DATACENTER_002", "Partial_Implementation", 15000.0),
+        ];
+        let repository = Box::new(InMemoryAssetRepository::new(assets.clone()));
+        let app = CapitalAllocationApp::new(repository, Box::new(CostLimitedFormulaRepository));
+
+        let (all, _, _) = app.calculate_all_risks(assets.clone()).unwrap();
+        let (top, failures, _) = app.calculate_top_by_priority(assets, 2, false).unwrap();
+
+        assert_eq!(top.len(), 2);
+        assert_eq!(failures.len(), 1);
+        for result in &top {
+            let full = all
+                .iter()
+                .find(|r| r.asset.alternative_id == result.asset.alternative_id)
+                .unwrap();
+            assert_eq!(
+                result.incremental_risk_reduction,
+                full.incremental_risk_reduction
+            );
+        }
+    }
+
+    #[test]
+    fn test_top_k_keeps_one_baseline_per_asset() {
+        let app = create_app(Vec::new());
+        let mut top = TopK::new();
+        for index in 0..100 {
+            let asset_id = format!("ASSET_{}", index % 5);
+            let alternative = if index % 2 == 0 {
+                "Defer"
+            } else {
+                "Do_Nothing"
+            };
+            let asset = create_test_asset(&asset_id, alternative, 0.0);
+            top.push(index, app.calculator.calculate(&asset).unwrap(), 3);
+        }
+
+        assert_eq!(top.heap.len(), 3);
+        assert_eq!(top.do_nothing.len(), 5);
+        for (key, result) in top.do_nothing.values() {
+            // The earliest row named Defer for each asset is the one kept
+            assert_eq!(result.asset.alternative_id, "Defer");
+            assert!(key.1 < 10);
+        }
+    }
+
+    #[test]
+    fn test_zero_assets() {
+        let app = create_app(Vec::new());
+
+        let assets = app.load_assets().unwrap();
+        assert!(assets.is_empty());
+
+        let (results, failures, duration) = app.calculate_all_risks(assets.clone()).unwrap();
+        assert!(results.is_empty());
+        assert!(failures.is_empty());
+        assert!(duration.as_secs_f64().is_finite());
+
+        let (top, failures, _) = app.calculate_top_by_priority(assets, 10, false).unwrap();
+        assert!(top.is_empty());
+        assert!(failures.is_empty());
+        assert!(CapitalAllocationApp::top_by_priority(&results, 10).is_empty());
+    }
+
+    #[test]
+    fn test_single_thread_pool_matches_default() {
+        let assets: Vec<Asset> = (1..=20)
+            .map(|id| {
+                create_test_asset(&format!("ASSET_{:03}", id), "Optimize", 5000.0 * id as f64)
+            })
+            .collect();
+        let app = create_app(assets.clone());
+
+        let (default_results, _, _) = app.calculate_all_risks(assets.clone()).unwrap();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let (pooled_results, failures, _) =
+            pool.install(|| app.calculate_all_risks(assets)).unwrap();
+
+        assert!(failures.is_empty());
+        assert_eq!(pooled_results.len(), default_results.len());
+        for (a, b) in pooled_results.iter().zip(&default_results) {
+            assert_eq!(a.asset.asset_id, b.asset.asset_id);
+            assert_eq!(a.risk_reduction, b.risk_reduction);
+            assert_eq!(a.priority_score, b.priority_score);
+        }
+    }
+
+    #[test]
+    fn test_app_from_plain_trait_objects_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let repository: Box<dyn AssetRepository> =
+            Box::new(InMemoryAssetRepository::new(vec![create_test_asset(
+                "IT_SYSTEM_001",
+                "Pilot_Program",
+                45000.0,
+            )]));
+        let formula_repository: Box<dyn FormulaRepository> =
+            Box::new(InMemoryFormulaRepository::new());
+        let app = CapitalAllocationApp::new(repository, formula_repository);
+        assert_send_sync(&app);
+
+        // The app can be moved to and shared with other threads
+        let loaded = std::thread::spawn(move || app.load_assets().unwrap().len())
+            .join()
+            .unwrap();
+        assert_eq!(loaded, 1);
+    }
+
+    #[test]
+    fn test_load_assets_normalizes_currency() {
+        let mut local = create_test_asset("IT_SYSTEM_001", "Pilot_Program", 180000.0);
+        local.cof_total_usd = 2000000.0;
+        local.currency = Some("BRL".to_string());
+        let base = create_test_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0);
+
+        let app = create_app(vec![local, base.clone()])
+            .with_currency_rates(CurrencyRates::new("USD").with_rate("BRL", 0.25));
+        let assets = app.load_assets().unwrap();
+
+        assert_eq!(assets[0].cost_usd, 45000.0);
+        assert_eq!(assets[0].cof_total_usd, 500000.0);
+
+        // Once normalized, both rows calculate identically
+        let (results, _, _) = app.calculate_all_risks(assets).unwrap();
+        assert_eq!(results[0].risk_reduction, results[1].risk_reduction);
+        assert_eq!(results[0].priority_score, results[1].priority_score);
+
+        let mut unknown = base;
+        unknown.currency = Some("EUR".to_string());
+        let app = create_app(vec![unknown])
+            .with_currency_rates(CurrencyRates::new("USD").with_rate("BRL", 0.25));
+        assert!(matches!(
+            app.load_assets(),
+            Err(CapAllocError::Validation(_))
+        ));
+    }
 }
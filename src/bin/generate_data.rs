@@ -1,105 +1,137 @@
 use csv::Writer;
+use serde::Serialize;
 use std::error::Error;
 use std::fs::File;
+use std::io::Write;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    println!("Generating large dataset for capital allocation optimizer...");
+const INVESTMENT_TYPES: [&str; 20] = [
+    "IT_SYSTEM",
+    "DATACENTER",
+    "CLOUD_MIGRATION",
+    "SOFTWARE_LICENSE",
+    "BUILDING_RENOVATION",
+    "FACILITY_EXPANSION",
+    "OFFICE_UPGRADE",
+    "WAREHOUSE",
+    "MARKET_EXPANSION",
+    "PRODUCT_LAUNCH",
+    "RND_PROJECT",
+    "MANUFACTURING_LINE",
+    "ROAD_UPGRADE",
+    "BRIDGE_REPAIR",
+    "POWER_GRID",
+    "WATER_SYSTEM",
+    "TRAINING_PROGRAM",
+    "CYBERSECURITY",
+    "ERP_SYSTEM",
+    "CRM_PLATFORM",
+];
 
-    let num_investments = 1000;
-    let alternatives_per_investment = 4;
-    let total_alternatives = num_investments * alternatives_per_investment;
+const ALTERNATIVES: [&str; 4] = [
+    "Defer",
+    "Pilot_Program",
+    "Partial_Implementation",
+    "Full_Implementation",
+];
 
-    let file = File::create("assets_large.csv")?;
-    let mut writer = Writer::from_writer(file);
+const SAFETY_LEVELS: [&str; 5] = ["Negligible", "Low", "Medium", "High", "Critical"];
 
-    writer.write_record([
-        "Asset_ID",
-        "Alternative_ID",
-        "Cost_USD",
-        "PoF_Post_Action",
-        "CoF_Total_USD",
-        "Safety_Risk_Level",
-    ])?;
+const DEFAULT_SEED: u64 = 42;
+const DEFAULT_INVESTMENTS: usize = 1000;
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../domain/mod.rs"]
+mod domain;
+
+/// Output file format
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Csv,
+    Json,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Result<Self, Box<dyn Error>> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Invalid --format '{}': expected csv or json", value).into()),
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Csv => "assets_large.csv",
+            Self::Json => "assets_large.json",
+        }
+    }
+}
+
+/// Linear congruential generator so datasets are reproducible from a seed
+struct SeededRng {
+    state: u64,
+}
 
-    let investment_types = vec![
-        "IT_SYSTEM",
-        "DATACENTER",
-        "CLOUD_MIGRATION",
-        "SOFTWARE_LICENSE",
-        "BUILDING_RENOVATION",
-        "FACILITY_EXPANSION",
-        "OFFICE_UPGRADE",
-        "WAREHOUSE",
-        "MARKET_EXPANSION",
-        "PRODUCT_LAUNCH",
-        "RND_PROJECT",
-        "MANUFACTURING_LINE",
-        "ROAD_UPGRADE",
-        "BRIDGE_REPAIR",
-        "POWER_GRID",
-        "WATER_SYSTEM",
-        "TRAINING_PROGRAM",
-        "CYBERSECURITY",
-        "ERP_SYSTEM",
-        "CRM_PLATFORM",
-    ];
-
-    let alternatives = [
-        "Defer",
-        "Pilot_Program",
-        "Partial_Implementation",
-        "Full_Implementation",
-    ];
-
-    let safety_levels = ["Negligible", "Low", "Medium", "High", "Critical"];
-
-    let mut seed = 42u64;
+impl SeededRng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Advance the generator and return the next value in [0, 2^31)
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_mul(1103515245).wrapping_add(12345) & 0x7fffffff;
+        self.state
+    }
+
+    /// Next value reduced to [0, modulus)
+    fn below(&mut self, modulus: u64) -> u64 {
+        self.next() % modulus
+    }
+}
+
+/// One generated row, serialized with the same column names as the CSV schema
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct GeneratedAsset {
+    #[serde(rename = "Asset_ID")]
+    asset_id: String,
+    #[serde(rename = "Alternative_ID")]
+    alternative_id: &'static str,
+    #[serde(rename = "Cost_USD")]
+    cost_usd: f64,
+    #[serde(rename = "PoF_Post_Action")]
+    pof_post_action: f64,
+    #[serde(rename = "CoF_Total_USD")]
+    cof_total_usd: f64,
+    #[serde(rename = "Safety_Risk_Level")]
+    safety_risk_level: &'static str,
+}
+
+/// Generate every alternative for `num_investments` investments from `seed`
+fn generate(num_investments: usize, seed: u64) -> Vec<GeneratedAsset> {
+    let mut rng = SeededRng::new(seed);
+    let mut assets = Vec::with_capacity(num_investments * ALTERNATIVES.len());
 
     for inv_num in 0..num_investments {
-        let investment_type = &investment_types[inv_num % investment_types.len()];
+        let investment_type = INVESTMENT_TYPES[inv_num % INVESTMENT_TYPES.len()];
         let asset_id = format!("{}_{:04}", investment_type, inv_num + 1);
 
-        seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-        let base_cof = 100000.0 + (seed % 5000000) as f64;
+        let base_cof = 100000.0 + rng.below(5000000) as f64;
+        let base_safety_idx = rng.below(5) as usize;
 
-        seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-        let base_safety_idx = (seed % 5) as usize;
-
-        for (alt_idx, alternative) in alternatives.iter().enumerate() {
+        for (alt_idx, alternative) in ALTERNATIVES.iter().enumerate() {
             let cost = match alt_idx {
-                0 => 0.0,
-                1 => {
-                    seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-                    5000.0 + (seed % 15000) as f64
-                }
-                2 => {
-                    seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-                    20000.0 + (seed % 80000) as f64
-                }
-                3 => {
-                    seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-                    100000.0 + (seed % 400000) as f64
-                }
+                1 => 5000.0 + rng.below(15000) as f64,
+                2 => 20000.0 + rng.below(80000) as f64,
+                3 => 100000.0 + rng.below(400000) as f64,
                 _ => 0.0,
             };
 
             let pof = match alt_idx {
-                0 => {
-                    seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-                    0.15 + (seed % 30) as f64 / 100.0
-                }
-                1 => {
-                    seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-                    0.10 + (seed % 20) as f64 / 100.0
-                }
-                2 => {
-                    seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-                    0.04 + (seed % 12) as f64 / 100.0
-                }
-                3 => {
-                    seed = (seed * 1103515245 + 12345) & 0x7fffffff;
-                    0.01 + (seed % 5) as f64 / 100.0
-                }
+                0 => 0.15 + rng.below(30) as f64 / 100.0,
+                1 => 0.10 + rng.below(20) as f64 / 100.0,
+                2 => 0.04 + rng.below(12) as f64 / 100.0,
+                3 => 0.01 + rng.below(5) as f64 / 100.0,
                 _ => 0.5,
             };
 
@@ -108,38 +140,192 @@ fn main() -> Result<(), Box<dyn Error>> {
             } else {
                 (base_safety_idx as i32 - alt_idx as i32).max(0) as usize
             };
-            let safety_level = &safety_levels[safety_idx];
-
-            writer.write_record([
-                &asset_id,
-                *alternative,
-                &format!("{:.2}", cost),
-                &format!("{:.4}", pof),
-                &format!("{:.2}", base_cof),
-                safety_level,
-            ])?;
-        }
 
-        if (inv_num + 1) % 100 == 0 {
-            println!(
-                "Generated {} investments ({} alternatives)...",
-                inv_num + 1,
-                (inv_num + 1) * alternatives_per_investment
-            );
+            assets.push(GeneratedAsset {
+                asset_id: asset_id.clone(),
+                alternative_id: alternative,
+                cost_usd: cost,
+                // Match the 4 decimal places written to CSV
+                pof_post_action: (pof * 10000.0).round() / 10000.0,
+                cof_total_usd: base_cof,
+                safety_risk_level: SAFETY_LEVELS[safety_idx],
+            });
         }
     }
 
+    assets
+}
+
+/// Write generated rows as CSV using the schema expected by `CsvAssetRepository`
+fn write_csv<W: Write>(writer: W, assets: &[GeneratedAsset]) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_writer(writer);
+
+    writer.write_record([
+        "Asset_ID",
+        "Alternative_ID",
+        "Cost_USD",
+        "PoF_Post_Action",
+        "CoF_Total_USD",
+        "Safety_Risk_Level",
+    ])?;
+
+    for asset in assets {
+        writer.write_record([
+            &asset.asset_id,
+            asset.alternative_id,
+            &format!("{:.2}", asset.cost_usd),
+            &format!("{:.4}", asset.pof_post_action),
+            &format!("{:.2}", asset.cof_total_usd),
+            asset.safety_risk_level,
+        ])?;
+    }
+
     writer.flush()?;
+    Ok(())
+}
+
+/// Write generated rows as a pretty-printed JSON array
+fn write_json<W: Write>(writer: W, assets: &[GeneratedAsset]) -> Result<(), Box<dyn Error>> {
+    serde_json::to_writer_pretty(writer, assets)?;
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    let seed = match args
+        .iter()
+        .position(|a| a == "--seed")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid --seed '{}': expected an unsigned integer", value))?,
+        None => DEFAULT_SEED,
+    };
+
+    let num_investments = match args
+        .iter()
+        .position(|a| a == "--count")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid --count '{}': expected an unsigned integer", value))?,
+        None => DEFAULT_INVESTMENTS,
+    };
+
+    let format = match args
+        .iter()
+        .position(|a| a == "--format")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => OutputFormat::parse(value)?,
+        None => OutputFormat::Csv,
+    };
+    let file_name = format.file_name();
+
+    println!("Generating large dataset for capital allocation optimizer...");
+    println!(
+        "Seed: {}, investments: {} ({} alternatives each)",
+        seed,
+        num_investments,
+        ALTERNATIVES.len()
+    );
+
+    let assets = generate(num_investments, seed);
+    let file = File::create(file_name)?;
+    match format {
+        OutputFormat::Csv => write_csv(file, &assets)?,
+        OutputFormat::Json => write_json(file, &assets)?,
+    }
 
     println!(
         "\n✓ Successfully generated {} alternatives for {} investments",
-        total_alternatives, num_investments
+        assets.len(),
+        num_investments
     );
-    println!("✓ File saved as: assets_large.csv");
+    println!("✓ File saved as: {}", file_name);
 
-    let metadata = std::fs::metadata("assets_large.csv")?;
+    let metadata = std::fs::metadata(file_name)?;
     let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
     println!("✓ File size: {:.2} MB", size_mb);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_csv(assets: &[GeneratedAsset]) -> String {
+        let mut buffer = Vec::new();
+        write_csv(&mut buffer, assets).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_same_seed_is_reproducible() {
+        let first = generate(50, 7);
+        let second = generate(50, 7);
+
+        assert_eq!(first, second);
+        assert_eq!(to_csv(&first), to_csv(&second));
+    }
+
+    #[test]
+    fn test_different_seeds_diverge() {
+        let first = to_csv(&generate(50, 7));
+        let second = to_csv(&generate(50, 8));
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_default_seed_matches_original_sequence() {
+        let mut rng = SeededRng::new(DEFAULT_SEED);
+        assert_eq!(rng.next(), (42 * 1103515245 + 12345) & 0x7fffffff);
+    }
+
+    #[test]
+    fn test_large_seed_does_not_overflow() {
+        let mut rng = SeededRng::new(u64::MAX);
+        assert!(rng.next() < 1 << 31);
+    }
+
+    #[test]
+    fn test_count_controls_size() {
+        let assets = generate(3, DEFAULT_SEED);
+
+        assert_eq!(assets.len(), 3 * ALTERNATIVES.len());
+        assert_eq!(to_csv(&assets).lines().count(), 3 * ALTERNATIVES.len() + 1);
+        assert!(assets
+            .iter()
+            .all(|a| a.alternative_id != "Defer" || a.cost_usd == 0.0));
+    }
+
+    #[test]
+    fn test_json_parses_back_into_assets() {
+        let generated = generate(5, DEFAULT_SEED);
+        let mut buffer = Vec::new();
+        write_json(&mut buffer, &generated).unwrap();
+
+        let parsed: Vec<domain::Asset> = serde_json::from_slice(&buffer).unwrap();
+
+        assert_eq!(parsed.len(), generated.len());
+        for (asset, expected) in parsed.iter().zip(&generated) {
+            assert_eq!(asset.asset_id, expected.asset_id);
+            assert_eq!(asset.alternative_id, expected.alternative_id);
+            assert_eq!(asset.cost_usd, expected.cost_usd);
+            assert_eq!(asset.pof_post_action, expected.pof_post_action);
+            assert_eq!(asset.safety_risk_level, expected.safety_risk_level);
+        }
+    }
+
+    #[test]
+    fn test_output_format_parse() {
+        assert_eq!(OutputFormat::parse("json").unwrap(), OutputFormat::Json);
+        assert_eq!(OutputFormat::parse("CSV").unwrap(), OutputFormat::Csv);
+        assert!(OutputFormat::parse("xml").is_err());
+    }
+}
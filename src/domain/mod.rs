@@ -1,7 +1,9 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
 
 /// Domain model representing a capital investment alternative
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Asset {
     #[serde(rename = "Asset_ID")]
     pub asset_id: String,
@@ -15,51 +17,542 @@ pub struct Asset {
     pub cof_total_usd: f64,
     #[serde(rename = "Safety_Risk_Level")]
     pub safety_risk_level: String,
+    /// Currency of the money columns; `None` (or a missing column) means the base currency
+    #[serde(rename = "Currency", default)]
+    pub currency: Option<String>,
+    /// Years until end of life; `None` (or a missing column) adds no urgency to priority
+    #[serde(rename = "Remaining_Life_Years", default)]
+    pub remaining_life_years: Option<f64>,
 }
 
 impl Asset {
-    pub fn is_high_risk(&self) -> bool {
-        self.safety_risk_level == "High" || self.safety_risk_level == "Critical"
+    pub fn is_high_risk(&self, levels: &RiskLevelConfig) -> bool {
+        levels.is_high_risk(&self.safety_risk_level)
     }
 
-    pub fn is_critical(&self) -> bool {
-        self.safety_risk_level == "Critical"
+    pub fn is_critical(&self, levels: &RiskLevelConfig) -> bool {
+        levels.is_critical(&self.safety_risk_level)
+    }
+
+    /// Category of this asset, see `asset_category`
+    pub fn category(&self) -> &str {
+        asset_category(&self.asset_id)
+    }
+
+    /// Test fixture: a Low safety level alternative with PoF 0.05 and $500K CoF
+    /// in the base currency, with no remaining life; override fields as needed
+    #[cfg(test)]
+    pub fn fixture(asset_id: &str, alternative_id: &str, cost_usd: f64) -> Self {
+        Self {
+            asset_id: asset_id.to_string(),
+            alternative_id: alternative_id.to_string(),
+            cost_usd,
+            pof_post_action: 0.05,
+            cof_total_usd: 500000.0,
+            safety_risk_level: "Low".to_string(),
+            currency: None,
+            remaining_life_years: None,
+        }
+    }
+
+    /// Check the row can be calculated: IDs and safety level present, cost and
+    /// CoF finite and non-negative, PoF within 0..=1. Every problem is listed
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        if self.asset_id.trim().is_empty() {
+            problems.push("missing Asset_ID".to_string());
+        }
+        if self.alternative_id.trim().is_empty() {
+            problems.push("missing Alternative_ID".to_string());
+        }
+        if !self.cost_usd.is_finite() || self.cost_usd < 0.0 {
+            problems.push(format!(
+                "Cost_USD {} is not a non-negative amount",
+                self.cost_usd
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.pof_post_action) {
+            problems.push(format!(
+                "PoF_Post_Action {} is outside 0 to 1",
+                self.pof_post_action
+            ));
+        }
+        if !self.cof_total_usd.is_finite() || self.cof_total_usd < 0.0 {
+            problems.push(format!(
+                "CoF_Total_USD {} is not a non-negative amount",
+                self.cof_total_usd
+            ));
+        }
+        if self.safety_risk_level.trim().is_empty() {
+            problems.push("missing Safety_Risk_Level".to_string());
+        }
+        if let Some(years) = self.remaining_life_years {
+            if !years.is_finite() || years < 0.0 {
+                problems.push(format!(
+                    "Remaining_Life_Years {} is not a non-negative number",
+                    years
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+}
+
+/// Category prefix of an asset ID: everything before a trailing `_<digits>`
+/// ("IT_SYSTEM_0001" -> "IT_SYSTEM"); IDs without a trailing number are their own category
+pub fn asset_category(asset_id: &str) -> &str {
+    match asset_id.rsplit_once('_') {
+        Some((prefix, number))
+            if !prefix.is_empty()
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            prefix
+        }
+        _ => asset_id,
+    }
+}
+
+/// Which safety level labels count as high risk and critical
+/// Critical labels are always high risk as well. Matching ignores case and
+/// surrounding whitespace, so "high" and " CRITICAL " classify like the defaults
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskLevelConfig {
+    pub high_risk_labels: Vec<String>,
+    pub critical_labels: Vec<String>,
+}
+
+impl RiskLevelConfig {
+    pub fn new(high_risk_labels: Vec<String>, critical_labels: Vec<String>) -> Self {
+        Self {
+            high_risk_labels,
+            critical_labels,
+        }
+    }
+
+    pub fn is_high_risk(&self, level: &str) -> bool {
+        Self::matches(&self.high_risk_labels, level) || self.is_critical(level)
+    }
+
+    pub fn is_critical(&self, level: &str) -> bool {
+        Self::matches(&self.critical_labels, level)
+    }
+
+    fn matches(labels: &[String], level: &str) -> bool {
+        let level = level.trim().to_lowercase();
+        labels
+            .iter()
+            .any(|label| label.trim().to_lowercase() == level)
+    }
+}
+
+impl Default for RiskLevelConfig {
+    fn default() -> Self {
+        Self::new(vec!["High".to_string()], vec!["Critical".to_string()])
     }
 }
 
+/// Safety levels with a priority weight of 1.0 by default
+pub const SAFETY_LEVELS: [&str; 5] = ["Negligible", "Low", "Medium", "High", "Critical"];
+
+/// Multiplier applied to the priority score per safety level
+/// Keys are lowercase so lookups ignore case and whitespace like `RiskLevelConfig`;
+/// levels without a weight use 1.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetyWeights {
+    pub weights: HashMap<String, f64>,
+}
+
+impl SafetyWeights {
+    pub fn with_weight(mut self, level: &str, weight: f64) -> Self {
+        self.weights.insert(level.trim().to_lowercase(), weight);
+        self
+    }
+
+    pub fn weight(&self, level: &str) -> f64 {
+        self.weights
+            .get(&level.trim().to_lowercase())
+            .copied()
+            .unwrap_or(1.0)
+    }
+}
+
+impl Default for SafetyWeights {
+    fn default() -> Self {
+        SAFETY_LEVELS.iter().fold(
+            Self {
+                weights: HashMap::new(),
+            },
+            |weights, level| weights.with_weight(level, 1.0),
+        )
+    }
+}
+
+/// Exchange rates used to express every asset's money columns in one base currency
+/// Each rate is the number of base-currency units per unit of the other currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyRates {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+}
+
+impl CurrencyRates {
+    pub fn new(base: &str) -> Self {
+        Self {
+            base: base.to_uppercase(),
+            rates: HashMap::new(),
+        }
+    }
+
+    pub fn with_rate(mut self, currency: &str, rate: f64) -> Self {
+        self.rates.insert(currency.to_uppercase(), rate);
+        self
+    }
+
+    /// Convert `cost_usd` and `cof_total_usd` to the base currency in place
+    /// Assets without a currency are already in the base; unknown currencies are an error
+    pub fn normalize(&self, assets: &mut [Asset]) -> Result<(), String> {
+        for asset in assets.iter_mut() {
+            let currency = match asset.currency.as_deref().map(str::trim) {
+                None | Some("") => continue,
+                Some(currency) => currency.to_uppercase(),
+            };
+            if currency != self.base {
+                let rate = self.rates.get(&currency).ok_or_else(|| {
+                    format!(
+                        "{} ({}): no exchange rate from {} to {}",
+                        asset.asset_id, asset.alternative_id, currency, self.base
+                    )
+                })?;
+                asset.cost_usd *= rate;
+                asset.cof_total_usd *= rate;
+            }
+            asset.currency = Some(self.base.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Drop rows repeating an earlier (asset_id, alternative_id) pair, keeping the first
+/// Returns the remaining assets and the number of rows removed
+pub fn deduplicate_assets(assets: Vec<Asset>) -> (Vec<Asset>, usize) {
+    let original_len = assets.len();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    let unique: Vec<Asset> = assets
+        .into_iter()
+        .filter(|asset| seen.insert((asset.asset_id.clone(), asset.alternative_id.clone())))
+        .collect();
+    let removed = original_len - unique.len();
+    (unique, removed)
+}
+
+/// Number of rows `deduplicate_assets` would remove, without copying any asset
+pub fn count_duplicate_assets(assets: &[Asset]) -> usize {
+    let mut seen: HashSet<(&str, &str)> = HashSet::new();
+    assets
+        .iter()
+        .filter(|asset| !seen.insert((asset.asset_id.as_str(), asset.alternative_id.as_str())))
+        .count()
+}
+
+/// Rows sharing an asset and alternative ID but disagreeing on cost or PoF
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlternativeConflict {
+    pub asset_id: String,
+    pub alternative_id: String,
+    /// Distinct (cost_usd, pof_post_action) pairs, in file order
+    pub variants: Vec<(f64, f64)>,
+}
+
+/// Find alternative IDs that appear more than once under an asset with differing cost/PoF
+/// Exact duplicates are not reported here; see `deduplicate_assets`
+pub fn find_alternative_conflicts(assets: &[Asset]) -> Vec<AlternativeConflict> {
+    let mut order: Vec<(&str, &str)> = Vec::new();
+    let mut variants: HashMap<(&str, &str), Vec<(f64, f64)>> = HashMap::new();
+
+    for asset in assets {
+        let key = (asset.asset_id.as_str(), asset.alternative_id.as_str());
+        let entry = variants.entry(key).or_insert_with(|| {
+            order.push(key);
+            Vec::new()
+        });
+        let variant = (asset.cost_usd, asset.pof_post_action);
+        if !entry.contains(&variant) {
+            entry.push(variant);
+        }
+    }
+
+    order
+        .into_iter()
+        .filter(|key| variants[key].len() > 1)
+        .map(|key| AlternativeConflict {
+            asset_id: key.0.to_string(),
+            alternative_id: key.1.to_string(),
+            variants: variants[&key].clone(),
+        })
+        .collect()
+}
+
+/// Totals over the results of one asset category
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategorySummary {
+    pub category: String,
+    pub assets: usize,
+    pub alternatives: usize,
+    pub total_cost: f64,
+    pub total_risk_reduction: f64,
+}
+
+/// Aggregate results by asset category, sorted by category name
+/// Totals sum every given result, so pass one alternative per asset (e.g. a
+/// solution's selections) for spend and risk reduction figures that add up
+pub fn summarize_by_category<'a>(
+    results: impl IntoIterator<Item = &'a RiskCalculationResult>,
+) -> Vec<CategorySummary> {
+    let mut categories: BTreeMap<&str, (HashSet<&str>, CategorySummary)> = BTreeMap::new();
+    for result in results {
+        let category = result.asset.category();
+        let (assets, summary) = categories.entry(category).or_insert_with(|| {
+            (
+                HashSet::new(),
+                CategorySummary {
+                    category: category.to_string(),
+                    assets: 0,
+                    alternatives: 0,
+                    total_cost: 0.0,
+                    total_risk_reduction: 0.0,
+                },
+            )
+        });
+        assets.insert(&result.asset.asset_id);
+        summary.assets = assets.len();
+        summary.alternatives += 1;
+        summary.total_cost += result.asset.cost_usd;
+        summary.total_risk_reduction += result.risk_reduction;
+    }
+
+    categories
+        .into_values()
+        .map(|(_, summary)| summary)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn create_test_asset() -> Asset {
-        Asset {
-            asset_id: "IT_SYSTEM_001".to_string(),
-            alternative_id: "Pilot_Program".to_string(),
-            cost_usd: 45000.0,
-            pof_post_action: 0.05,
-            cof_total_usd: 500000.0,
-            safety_risk_level: "Low".to_string(),
-        }
+        Asset::fixture("IT_SYSTEM_001", "Pilot_Program", 45000.0)
     }
 
     #[test]
     fn test_is_high_risk_false() {
         let asset = create_test_asset();
-        assert!(!asset.is_high_risk());
+        assert!(!asset.is_high_risk(&RiskLevelConfig::default()));
     }
 
     #[test]
     fn test_is_high_risk_true() {
         let mut asset = create_test_asset();
         asset.safety_risk_level = "High".to_string();
-        assert!(asset.is_high_risk());
+        assert!(asset.is_high_risk(&RiskLevelConfig::default()));
     }
 
     #[test]
     fn test_is_critical() {
+        let levels = RiskLevelConfig::default();
+        let mut asset = create_test_asset();
+        asset.safety_risk_level = "Critical".to_string();
+        assert!(asset.is_critical(&levels));
+        assert!(asset.is_high_risk(&levels));
+    }
+
+    #[test]
+    fn test_risk_levels_ignore_case_and_whitespace() {
+        let levels = RiskLevelConfig::default();
+        let mut asset = create_test_asset();
+
+        for label in ["high", "HIGH", " High ", "\tHiGh\n"] {
+            asset.safety_risk_level = label.to_string();
+            assert!(asset.is_high_risk(&levels), "{:?}", label);
+            assert!(!asset.is_critical(&levels), "{:?}", label);
+        }
+        for label in ["critical", "CRITICAL", "  Critical"] {
+            asset.safety_risk_level = label.to_string();
+            assert!(asset.is_critical(&levels), "{:?}", label);
+            assert!(asset.is_high_risk(&levels), "{:?}", label);
+        }
+        for label in ["low", " Medium ", "Highest", ""] {
+            asset.safety_risk_level = label.to_string();
+            assert!(!asset.is_high_risk(&levels), "{:?}", label);
+        }
+
+        let custom = RiskLevelConfig::new(vec![" Alto".to_string()], vec!["Crítico".to_string()]);
+        asset.safety_risk_level = "CRÍTICO ".to_string();
+        assert!(custom.is_critical(&asset.safety_risk_level));
+        asset.safety_risk_level = "alto".to_string();
+        assert!(custom.is_high_risk(&asset.safety_risk_level));
+    }
+
+    #[test]
+    fn test_custom_risk_labels() {
+        let levels = RiskLevelConfig::new(vec!["Alto".to_string()], vec!["Crítico".to_string()]);
         let mut asset = create_test_asset();
+
+        asset.safety_risk_level = "Alto".to_string();
+        assert!(asset.is_high_risk(&levels));
+        assert!(!asset.is_critical(&levels));
+
+        asset.safety_risk_level = "Crítico".to_string();
+        assert!(asset.is_high_risk(&levels));
+        assert!(asset.is_critical(&levels));
+
+        // The English defaults no longer apply
         asset.safety_risk_level = "Critical".to_string();
-        assert!(asset.is_critical());
+        assert!(!asset.is_high_risk(&levels));
+        assert!(!asset.is_critical(&levels));
+    }
+
+    #[test]
+    fn test_normalize_mixed_currencies() {
+        let rates = CurrencyRates::new("USD")
+            .with_rate("BRL", 0.25)
+            .with_rate("mxn", 0.5);
+        let mut assets = vec![
+            create_test_asset(),
+            create_test_asset(),
+            create_test_asset(),
+        ];
+        assets[1].currency = Some("BRL".to_string());
+        assets[2].currency = Some(" MXN".to_string());
+
+        rates.normalize(&mut assets).unwrap();
+
+        assert_eq!(assets[0].cost_usd, 45000.0);
+        assert_eq!(assets[0].cof_total_usd, 500000.0);
+        assert_eq!(assets[1].cost_usd, 11250.0);
+        assert_eq!(assets[1].cof_total_usd, 125000.0);
+        assert_eq!(assets[2].cost_usd, 22500.0);
+        assert_eq!(assets[2].cof_total_usd, 250000.0);
+        assert_eq!(assets[0].currency, None);
+        assert!(assets[1..]
+            .iter()
+            .all(|a| a.currency.as_deref() == Some("USD")));
+    }
+
+    #[test]
+    fn test_normalize_base_and_unknown_currency() {
+        let rates = CurrencyRates::new("usd");
+        let mut assets = vec![create_test_asset()];
+        assets[0].currency = Some("usd".to_string());
+        rates.normalize(&mut assets).unwrap();
+        assert_eq!(assets[0].cost_usd, 45000.0);
+
+        assets[0].currency = Some("EUR".to_string());
+        let error = rates.normalize(&mut assets).unwrap_err();
+        assert!(error.contains("EUR"));
+        assert!(error.contains("IT_SYSTEM_001"));
+    }
+
+    #[test]
+    fn test_deduplicate_assets() {
+        let first = create_test_asset();
+        let duplicate = create_test_asset();
+        let mut other = create_test_asset();
+        other.alternative_id = "Full_Implementation".to_string();
+
+        let all = vec![first, duplicate, other];
+        assert_eq!(count_duplicate_assets(&all), 1);
+        let (assets, removed) = deduplicate_assets(all);
+
+        assert_eq!(removed, 1);
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].alternative_id, "Pilot_Program");
+        assert_eq!(assets[1].alternative_id, "Full_Implementation");
+    }
+
+    #[test]
+    fn test_find_alternative_conflicts() {
+        let first = create_test_asset();
+        let exact_duplicate = create_test_asset();
+        let mut conflicting = create_test_asset();
+        conflicting.cost_usd = 60000.0;
+        let mut other_asset = create_test_asset();
+        other_asset.asset_id = "DATACENTER_002".to_string();
+
+        let conflicts =
+            find_alternative_conflicts(&[first, exact_duplicate, conflicting, other_asset]);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].asset_id, "IT_SYSTEM_001");
+        assert_eq!(conflicts[0].alternative_id, "Pilot_Program");
+        assert_eq!(
+            conflicts[0].variants,
+            vec![(45000.0, 0.05), (60000.0, 0.05)]
+        );
+    }
+
+    #[test]
+    fn test_no_conflicts_for_exact_duplicates() {
+        let conflicts = find_alternative_conflicts(&[create_test_asset(), create_test_asset()]);
+        assert!(conflicts.is_empty());
+    }
+
+    fn create_result(alternative: &str, cost: f64, post_action_risk: f64) -> RiskCalculationResult {
+        let mut asset = create_test_asset();
+        asset.alternative_id = alternative.to_string();
+        asset.cost_usd = cost;
+        RiskCalculationResult::new(
+            asset,
+            500000.0,
+            post_action_risk,
+            500000.0 - post_action_risk,
+            Some(1.0),
+            1.0,
+            1.0,
+            50.0,
+            Some(12.0),
+            0.5,
+        )
+    }
+
+    #[test]
+    fn test_incremental_risk_reduction_with_defer() {
+        let mut results = vec![
+            create_result("Defer", 0.0, 125000.0),
+            create_result("Pilot_Program", 45000.0, 25000.0),
+            create_result("Full_Implementation", 120000.0, 5000.0),
+        ];
+
+        apply_incremental_risk_reduction(&mut results);
+
+        assert_eq!(results[0].incremental_risk_reduction, 0.0);
+        assert_eq!(results[1].incremental_risk_reduction, 100000.0);
+        assert_eq!(results[2].incremental_risk_reduction, 120000.0);
+    }
+
+    #[test]
+    fn test_incremental_risk_reduction_without_defer() {
+        let mut results = vec![
+            create_result("Pilot_Program", 45000.0, 25000.0),
+            create_result("Full_Implementation", 120000.0, 5000.0),
+        ];
+
+        apply_incremental_risk_reduction(&mut results);
+
+        assert_eq!(
+            results[0].incremental_risk_reduction,
+            results[0].risk_reduction
+        );
+        assert_eq!(
+            results[1].incremental_risk_reduction,
+            results[1].risk_reduction
+        );
     }
 
     #[test]
@@ -70,37 +563,448 @@ mod tests {
             25000.0,
             250000.0,
             225000.0,
-            0.9,
+            Some(0.9),
             5.5,
             0.75,
             85.0,
-            24.0,
+            Some(24.0),
             0.5,
         );
 
         assert_eq!(result.baseline_risk, 25000.0);
         assert_eq!(result.post_action_risk, 250000.0);
         assert_eq!(result.risk_reduction, 225000.0);
-        assert_eq!(result.roi, 0.9);
+        assert_eq!(result.roi, Some(0.9));
         assert_eq!(result.criticality_score, 5.5);
         assert_eq!(result.priority_score, 0.75);
         assert_eq!(result.calculation_time_ms, 0.5);
     }
+
+    #[test]
+    fn test_average_time_ms() {
+        assert_eq!(average_time_ms(Duration::from_millis(30), 3), 10.0);
+    }
+
+    #[test]
+    fn test_average_time_ms_zero_count() {
+        assert_eq!(average_time_ms(Duration::from_millis(30), 0), 0.0);
+        assert_eq!(average_time_ms(Duration::ZERO, 0), 0.0);
+    }
+
+    #[test]
+    fn test_throughput_per_sec() {
+        assert_eq!(throughput_per_sec(Duration::from_millis(500), 100), 200.0);
+        assert_eq!(throughput_per_sec(Duration::from_secs(2), 0), 0.0);
+    }
+
+    #[test]
+    fn test_throughput_per_sec_zero_duration() {
+        assert_eq!(throughput_per_sec(Duration::ZERO, 100), 0.0);
+        assert_eq!(throughput_per_sec(Duration::ZERO, 0), 0.0);
+    }
+
+    #[test]
+    fn test_histogram_bucket_boundaries() {
+        let buckets = histogram(&[0.0, 0.1, 0.25, 0.5, 0.75, 0.99, 1.0], 4);
+
+        assert_eq!(
+            buckets,
+            vec![
+                (0.0, 0.25, 2),
+                (0.25, 0.5, 1),
+                (0.5, 0.75, 1),
+                // The maximum lands in the last bucket rather than a fifth
+                (0.75, 1.0, 3),
+            ]
+        );
+        assert_eq!(buckets.iter().map(|(_, _, count)| count).sum::<usize>(), 7);
+    }
+
+    #[test]
+    fn test_histogram_empty_and_degenerate() {
+        assert!(histogram(&[], 5).is_empty());
+        assert!(histogram(&[f64::NAN], 5).is_empty());
+        assert!(histogram(&[0.5, 1.0], 0).is_empty());
+        assert_eq!(histogram(&[0.3, 0.3, 0.3], 5), vec![(0.3, 0.3, 3)]);
+    }
+
+    #[test]
+    fn test_benchmark_stats_percentiles() {
+        // Times 1..=100 ms in reverse order, so the nearest-rank percentile p is p ms
+        let results: Vec<RiskCalculationResult> = (1..=100)
+            .rev()
+            .map(|ms| {
+                RiskCalculationResult::new(
+                    create_test_asset(),
+                    500000.0,
+                    25000.0,
+                    475000.0,
+                    Some(10.5),
+                    1.0,
+                    0.75,
+                    85.0,
+                    Some(1.2),
+                    ms as f64,
+                )
+            })
+            .collect();
+
+        let stats = benchmark_stats(&results);
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.max_ms, 100.0);
+        assert_eq!(stats.p50_ms, 50.0);
+        assert_eq!(stats.p95_ms, 95.0);
+        assert_eq!(stats.p99_ms, 99.0);
+
+        let single = benchmark_stats(&results[..1]);
+        assert_eq!(single.p50_ms, 100.0);
+        assert_eq!(single.p99_ms, 100.0);
+        assert_eq!(benchmark_stats(&[]), BenchmarkStats::default());
+    }
+
+    #[test]
+    fn test_benchmark_summary_json_keys() {
+        let results: Vec<RiskCalculationResult> = (1..=4)
+            .map(|ms| {
+                let mut result = create_result("Pilot", 1000.0, 25000.0);
+                result.calculation_time_ms = ms as f64;
+                result
+            })
+            .collect();
+
+        let summary = BenchmarkSummary::new(&results, Duration::from_millis(10), 5, 1);
+        let json = serde_json::to_value(summary).unwrap();
+
+        for key in [
+            "asset_count",
+            "failed_count",
+            "total_time_ms",
+            "avg_time_ms",
+            "throughput_per_sec",
+        ] {
+            assert!(json[key].is_number(), "missing {}: {}", key, json);
+        }
+        for key in ["count", "min_ms", "max_ms", "p50_ms", "p95_ms", "p99_ms"] {
+            assert!(
+                json["calculation_time"][key].is_number(),
+                "missing calculation_time.{}: {}",
+                key,
+                json
+            );
+        }
+        assert_eq!(json["asset_count"], 5);
+        assert_eq!(json["avg_time_ms"], 2.5);
+        assert_eq!(json["throughput_per_sec"], 400.0);
+        assert_eq!(json["calculation_time"]["max_ms"], 4.0);
+    }
+
+    #[test]
+    fn test_phase_timings_accumulate_and_report() {
+        let mut timings = PhaseTimings::default();
+        timings.record(Phase::Load, Duration::from_millis(5));
+        timings.record(Phase::Calculate, Duration::from_millis(20));
+        timings.record(Phase::Optimize, Duration::from_millis(10));
+        timings.record(Phase::Optimize, Duration::from_millis(15));
+
+        assert_eq!(timings.optimize, Duration::from_millis(25));
+        assert_eq!(timings.total(), Duration::from_millis(50));
+
+        let report = timings.report();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Load") && lines[0].ends_with(" 5.00 ms"));
+        assert!(lines[1].starts_with("Calculate") && lines[1].ends_with(" 20.00 ms"));
+        assert!(lines[2].starts_with("Optimize") && lines[2].ends_with(" 25.00 ms"));
+        assert!(lines[3].starts_with("Total") && lines[3].ends_with(" 50.00 ms"));
+    }
+
+    #[test]
+    fn test_min_max_normalize() {
+        assert_eq!(min_max_normalize(&[5.0, 1.0, 3.0]), vec![1.0, 0.0, 0.5]);
+        assert_eq!(min_max_normalize(&[2.0, 2.0]), vec![0.0, 0.0]);
+        assert!(min_max_normalize(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_normalize_priority_scores_in_unit_range_and_ordered() {
+        // Risk reduction spans very different magnitudes; ROI and criticality are equal
+        let mut results: Vec<RiskCalculationResult> = [3_000_000.0, 12_000.0, 450_000.0, 80.0]
+            .into_iter()
+            .map(|post_action_risk| create_result("Pilot", 1000.0, 5_000_000.0 - post_action_risk))
+            .collect();
+        let risk_order = |results: &[RiskCalculationResult]| {
+            let mut order: Vec<usize> = (0..results.len()).collect();
+            order.sort_by(|&a, &b| {
+                results[a]
+                    .risk_reduction
+                    .total_cmp(&results[b].risk_reduction)
+            });
+            order
+        };
+        let before = risk_order(&results);
+
+        normalize_priority_scores(&mut results, |_| 1.0);
+
+        let normalized = |result: &RiskCalculationResult| result.normalized_priority_score.unwrap();
+        for result in &results {
+            assert!(
+                (0.0..=1.0).contains(&normalized(result)),
+                "{}",
+                normalized(result)
+            );
+        }
+        let mut by_score: Vec<usize> = (0..results.len()).collect();
+        by_score.sort_by(|&a, &b| normalized(&results[a]).total_cmp(&normalized(&results[b])));
+        assert_eq!(by_score, before);
+        assert_eq!(normalized(&results[before[0]]), 0.0);
+        assert!((normalized(&results[before[3]]) - 0.4).abs() < 1e-12);
+
+        // Every component at its maximum gives 1.0
+        results[0].roi = None;
+        results[0].criticality_score = 9.0;
+        normalize_priority_scores(&mut results, |_| 1.0);
+        assert!((normalized(&results[0]) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_normalize_priority_scores_keeps_formula_score() {
+        let mut results = vec![
+            create_result("Pilot", 1000.0, 400_000.0),
+            create_result("Full", 5000.0, 100_000.0),
+        ];
+        assert_eq!(results[1].effective_priority(), 1.0);
+
+        // The multiplier scales the normalized score, as the formula's factors do
+        normalize_priority_scores(&mut results, |asset| {
+            if asset.alternative_id == "Full" {
+                2.0
+            } else {
+                1.0
+            }
+        });
+
+        for result in &results {
+            assert_eq!(result.priority_score, 1.0);
+        }
+        assert_eq!(results[0].normalized_priority_score, Some(0.0));
+        assert_eq!(results[1].normalized_priority_score, Some(0.8));
+        assert_eq!(results[1].effective_priority(), 0.8);
+        assert_eq!(results[1].to_row()[8].1, "1");
+        assert_eq!(results[1].to_row()[12].1, "0.8");
+    }
+
+    #[test]
+    fn test_cost_benefit_ratio() {
+        let result = RiskCalculationResult::new(
+            create_test_asset(),
+            500000.0,
+            25000.0,
+            450000.0,
+            Some(10.5),
+            1.0,
+            0.75,
+            85.0,
+            Some(1.2),
+            0.5,
+        );
+        assert_eq!(result.cost_benefit_ratio(), 10.0);
+    }
+
+    #[test]
+    fn test_asset_validate() {
+        let valid = create_result("Pilot", 1000.0, 10.0).asset;
+        assert!(valid.validate().is_ok());
+
+        let mut invalid = valid.clone();
+        invalid.cost_usd = -5.0;
+        invalid.pof_post_action = 1.5;
+        invalid.safety_risk_level = " ".to_string();
+        let problems = invalid.validate().unwrap_err();
+        assert!(problems.contains("Cost_USD -5"), "{}", problems);
+        assert!(problems.contains("PoF_Post_Action 1.5"), "{}", problems);
+        assert!(problems.contains("Safety_Risk_Level"), "{}", problems);
+
+        let mut negative_life = valid.clone();
+        negative_life.remaining_life_years = Some(-1.0);
+        assert!(negative_life
+            .validate()
+            .unwrap_err()
+            .contains("Remaining_Life_Years -1"));
+
+        let mut nan = valid;
+        nan.cof_total_usd = f64::NAN;
+        assert!(nan.validate().unwrap_err().contains("CoF_Total_USD"));
+    }
+
+    #[test]
+    fn test_safety_weights_lookup() {
+        let weights = SafetyWeights::default().with_weight("Critical", 1.5);
+        assert_eq!(weights.weight(" critical "), 1.5);
+        assert_eq!(weights.weight("High"), 1.0);
+        assert_eq!(weights.weight("Unlisted"), 1.0);
+    }
+
+    #[test]
+    fn test_asset_category() {
+        assert_eq!(asset_category("IT_SYSTEM_0001"), "IT_SYSTEM");
+        assert_eq!(asset_category("DATACENTER_0002"), "DATACENTER");
+        assert_eq!(asset_category("PUMP_STATION_7"), "PUMP_STATION");
+
+        // No trailing number: the whole ID is the category
+        assert_eq!(asset_category("HEADQUARTERS"), "HEADQUARTERS");
+        assert_eq!(asset_category("ROOF_NORTH"), "ROOF_NORTH");
+        assert_eq!(asset_category("SITE_12A"), "SITE_12A");
+        assert_eq!(asset_category("SITE42"), "SITE42");
+        assert_eq!(asset_category("BRIDGE_"), "BRIDGE_");
+        assert_eq!(asset_category("_001"), "_001");
+        assert_eq!(asset_category(""), "");
+    }
+
+    #[test]
+    fn test_summarize_by_category() {
+        let result = |asset_id: &str, cost: f64, risk_reduction: f64| {
+            let mut asset = create_test_asset();
+            asset.asset_id = asset_id.to_string();
+            asset.cost_usd = cost;
+            RiskCalculationResult::new(
+                asset,
+                500000.0,
+                25000.0,
+                risk_reduction,
+                None,
+                1.0,
+                0.5,
+                50.0,
+                None,
+                0.5,
+            )
+        };
+        let results = vec![
+            result("IT_SYSTEM_001", 10000.0, 50000.0),
+            result("DATACENTER_001", 20000.0, 70000.0),
+            result("IT_SYSTEM_002", 15000.0, 30000.0),
+            result("IT_SYSTEM_002", 5000.0, 10000.0),
+        ];
+
+        let summaries = summarize_by_category(&results);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].category, "DATACENTER");
+        assert_eq!(summaries[0].assets, 1);
+        assert_eq!(summaries[1].category, "IT_SYSTEM");
+        assert_eq!(summaries[1].assets, 2);
+        assert_eq!(summaries[1].alternatives, 3);
+        assert_eq!(summaries[1].total_cost, 30000.0);
+        assert_eq!(summaries[1].total_risk_reduction, 90000.0);
+    }
+
+    #[test]
+    fn test_to_row_labels_and_values() {
+        let result = RiskCalculationResult::new(
+            create_test_asset(),
+            500000.0,
+            25000.0,
+            475000.0,
+            Some(10.5),
+            1.0,
+            0.75,
+            85.0,
+            Some(1.2),
+            0.5,
+        );
+        let row = result.to_row();
+
+        let labels: Vec<&str> = row.iter().map(|(label, _)| *label).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "asset_id",
+                "alternative_id",
+                "cost",
+                "baseline_risk",
+                "post_action_risk",
+                "risk_reduction",
+                "roi",
+                "criticality_score",
+                "priority_score",
+                "cost_effectiveness",
+                "payback_period",
+                "cost_benefit_ratio",
+                "normalized_priority_score",
+            ]
+        );
+        assert_eq!(row[0].1, "IT_SYSTEM_001");
+        assert_eq!(row[5].1, "475000");
+        assert_eq!(row[10].1, "1.2");
+
+        // Every label has its own title
+        let titles: HashSet<&str> = labels
+            .iter()
+            .map(|label| RiskCalculationResult::row_column(label).0)
+            .collect();
+        assert_eq!(titles.len(), labels.len());
+        assert!(!titles.iter().any(|title| title.contains('_')));
+    }
+
+    #[test]
+    fn test_to_row_leaves_undefined_values_empty() {
+        let mut asset = create_test_asset();
+        asset.cost_usd = 0.0;
+        let result = RiskCalculationResult::new(
+            asset, 500000.0, 500000.0, 0.0, None, 1.0, 0.1, 0.0, None, 0.5,
+        );
+        let row = result.to_row();
+        assert_eq!(row[6], ("roi", String::new()));
+        assert_eq!(row[10], ("payback_period", String::new()));
+        assert_eq!(row[12], ("normalized_priority_score", String::new()));
+    }
+
+    #[test]
+    fn test_cost_benefit_ratio_zero_cost_defer() {
+        let mut asset = create_test_asset();
+        asset.alternative_id = "Defer".to_string();
+        asset.cost_usd = 0.0;
+        let result = RiskCalculationResult::new(
+            asset,
+            500000.0,
+            500000.0,
+            0.0,
+            Some(0.0),
+            1.0,
+            0.1,
+            0.0,
+            Some(0.0),
+            0.5,
+        );
+        assert_eq!(result.cost_benefit_ratio(), 0.0);
+    }
 }
 
 /// Value object representing the optimization result for a capital investment alternative
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RiskCalculationResult {
     pub asset: Asset,
     pub baseline_risk: f64,
     pub post_action_risk: f64,
     pub risk_reduction: f64,
-    pub roi: f64,
+    /// None when the adjusted cost is zero and ROI is undefined
+    pub roi: Option<f64>,
     pub criticality_score: f64,
     pub priority_score: f64,
     pub cost_effectiveness: f64,
-    pub payback_period: f64,
+    /// Months to pay back; None when there is no risk reduction to pay it back
+    pub payback_period: Option<f64>,
     pub calculation_time_ms: f64,
+    /// Risk reduction relative to the asset's do-nothing (Defer) alternative;
+    /// equals `risk_reduction` when the asset has no such alternative
+    pub incremental_risk_reduction: f64,
+    /// Risk reduction range under the configured PoF uncertainty; None when not calculated
+    pub risk_reduction_low: Option<f64>,
+    pub risk_reduction_high: Option<f64>,
+    /// Dataset-relative priority from `normalize_priority_scores`; None when not normalized
+    #[serde(default)]
+    pub normalized_priority_score: Option<f64>,
 }
 
 impl RiskCalculationResult {
@@ -110,11 +1014,11 @@ impl RiskCalculationResult {
         baseline_risk: f64,
         post_action_risk: f64,
         risk_reduction: f64,
-        roi: f64,
+        roi: Option<f64>,
         criticality_score: f64,
         priority_score: f64,
         cost_effectiveness: f64,
-        payback_period: f64,
+        payback_period: Option<f64>,
         calculation_time_ms: f64,
     ) -> Self {
         Self {
@@ -128,10 +1032,40 @@ impl RiskCalculationResult {
             cost_effectiveness,
             payback_period,
             calculation_time_ms,
+            incremental_risk_reduction: risk_reduction,
+            risk_reduction_low: None,
+            risk_reduction_high: None,
+            normalized_priority_score: None,
         }
     }
 
-    #[allow(dead_code)]
+    /// Attach the risk reduction range from a PoF uncertainty calculation
+    pub fn with_risk_reduction_band(mut self, low: f64, high: f64) -> Self {
+        self.risk_reduction_low = Some(low);
+        self.risk_reduction_high = Some(high);
+        self
+    }
+
+    /// Key naming this alternative in `selected_alternatives` and selection sets,
+    /// "Asset_ID (Alternative_ID)"
+    pub fn selection_key(&self) -> String {
+        format!("{} ({})", self.asset.asset_id, self.asset.alternative_id)
+    }
+
+    /// Priority used for ranking, filtering and the priority strategy: the
+    /// normalized score when one was computed, otherwise the formula score
+    pub fn effective_priority(&self) -> f64 {
+        self.normalized_priority_score
+            .unwrap_or(self.priority_score)
+    }
+
+    /// Whether this alternative represents doing nothing for its asset
+    pub fn is_do_nothing(&self) -> bool {
+        self.asset.alternative_id.eq_ignore_ascii_case("Defer") || self.asset.cost_usd == 0.0
+    }
+
+    /// Raw risk reduction per dollar, independent of the formula adjustments baked into `roi`
+    /// Zero-cost alternatives return 0.0
     pub fn cost_benefit_ratio(&self) -> f64 {
         if self.asset.cost_usd > 0.0 {
             self.risk_reduction / self.asset.cost_usd
@@ -139,4 +1073,315 @@ impl RiskCalculationResult {
             0.0
         }
     }
+
+    /// Labels of the fields returned by `to_row`, in column order
+    pub const ROW_LABELS: [&'static str; 13] = [
+        "asset_id",
+        "alternative_id",
+        "cost",
+        "baseline_risk",
+        "post_action_risk",
+        "risk_reduction",
+        "roi",
+        "criticality_score",
+        "priority_score",
+        "cost_effectiveness",
+        "payback_period",
+        "cost_benefit_ratio",
+        "normalized_priority_score",
+    ];
+
+    /// Exported fields in column order, as (label, value) pairs
+    /// Undefined ROI and payback, and priorities that were not normalized, are left empty
+    pub fn to_row(&self) -> Vec<(&'static str, String)> {
+        let values = [
+            self.asset.asset_id.clone(),
+            self.asset.alternative_id.clone(),
+            self.asset.cost_usd.to_string(),
+            self.baseline_risk.to_string(),
+            self.post_action_risk.to_string(),
+            self.risk_reduction.to_string(),
+            self.roi.map(|roi| roi.to_string()).unwrap_or_default(),
+            self.criticality_score.to_string(),
+            self.priority_score.to_string(),
+            self.cost_effectiveness.to_string(),
+            self.payback_period
+                .map(|months| months.to_string())
+                .unwrap_or_default(),
+            self.cost_benefit_ratio().to_string(),
+            self.normalized_priority_score
+                .map(|score| score.to_string())
+                .unwrap_or_default(),
+        ];
+        Self::ROW_LABELS.into_iter().zip(values).collect()
+    }
+
+    /// Display title and kind of the `to_row` field labelled `label`
+    pub fn row_column(label: &'static str) -> (&'static str, RowKind) {
+        match label {
+            "asset_id" => ("Asset ID", RowKind::Text),
+            "alternative_id" => ("Alternative ID", RowKind::Text),
+            "cost" => ("Cost", RowKind::Money),
+            "baseline_risk" => ("Baseline Risk", RowKind::Money),
+            "post_action_risk" => ("Post-Action Risk", RowKind::Money),
+            "risk_reduction" => ("Risk Reduction", RowKind::Money),
+            "roi" => ("ROI", RowKind::Score),
+            "criticality_score" => ("Criticality Score", RowKind::Rating),
+            "priority_score" => ("Priority Score", RowKind::Score),
+            "cost_effectiveness" => ("Cost Effectiveness", RowKind::Rating),
+            "payback_period" => ("Payback (months)", RowKind::Months),
+            "cost_benefit_ratio" => ("Risk Reduction / Cost", RowKind::Score),
+            "normalized_priority_score" => ("Normalized Priority", RowKind::Score),
+            _ => (label, RowKind::Text),
+        }
+    }
+}
+
+/// How a `to_row` value is presented where it is not written as plain text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    /// Identifiers, shown as is
+    Text,
+    /// Amounts in the base currency
+    Money,
+    /// ROI, priority and other ratios, at score precision (4 places by default)
+    Score,
+    /// Criticality and cost effectiveness, at rating precision (2 places by default)
+    Rating,
+    /// Payback in months, one place fewer than ratings
+    Months,
+}
+
+/// Recompute `incremental_risk_reduction` relative to each asset's do-nothing alternative
+/// A row named "Defer" is preferred over other zero-cost rows; assets without either
+/// keep the absolute risk reduction
+pub fn apply_incremental_risk_reduction(results: &mut [RiskCalculationResult]) {
+    let mut do_nothing_risk: HashMap<String, (bool, f64)> = HashMap::new();
+    for result in results.iter().filter(|r| r.is_do_nothing()) {
+        let named = result.asset.alternative_id.eq_ignore_ascii_case("Defer");
+        let entry = do_nothing_risk
+            .entry(result.asset.asset_id.clone())
+            .or_insert((named, result.post_action_risk));
+        if named && !entry.0 {
+            *entry = (true, result.post_action_risk);
+        }
+    }
+
+    for result in results.iter_mut() {
+        result.incremental_risk_reduction = match do_nothing_risk.get(&result.asset.asset_id) {
+            Some(&(_, defer_risk)) => defer_risk - result.post_action_risk,
+            None => result.risk_reduction,
+        };
+    }
+}
+
+/// Scale `values` to 0..=1 by their minimum and maximum; all 0.0 when they are equal
+pub fn min_max_normalize(values: &[f64]) -> Vec<f64> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    values
+        .iter()
+        .map(|v| if range > 0.0 { (v - min) / range } else { 0.0 })
+        .collect()
+}
+
+/// Set each `normalized_priority_score` to a dataset-relative score, leaving the
+/// formula `priority_score` as is: risk reduction, ROI and criticality are min-max
+/// normalized across `results`, then weighted 40/35/25 like the priority formula.
+/// ROI is capped at 10 as in the formula, with undefined ROI (zero cost) at the cap.
+/// The weighted sum lies in 0..=1 and is scaled by `multiplier`, which carries the
+/// formula's critical, safety-weight and remaining-life factors
+pub fn normalize_priority_scores(
+    results: &mut [RiskCalculationResult],
+    multiplier: impl Fn(&Asset) -> f64,
+) {
+    let component = |value: fn(&RiskCalculationResult) -> f64| -> Vec<f64> {
+        min_max_normalize(&results.iter().map(value).collect::<Vec<f64>>())
+    };
+    let risk_reduction = component(|r| r.risk_reduction);
+    let roi = component(|r| r.roi.map_or(10.0, |roi| roi.min(10.0)));
+    let criticality = component(|r| r.criticality_score);
+
+    for (i, result) in results.iter_mut().enumerate() {
+        let weighted = risk_reduction[i] * 0.4 + roi[i] * 0.35 + criticality[i] * 0.25;
+        result.normalized_priority_score = Some(weighted * multiplier(&result.asset));
+    }
+}
+
+/// Average time per calculation in milliseconds; 0.0 when nothing was calculated
+pub fn average_time_ms(total_time: Duration, count: usize) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total_time.as_secs_f64() * 1000.0 / count as f64
+    }
+}
+
+/// Calculations per second over `total_time`; 0.0 when no time was measured
+pub fn throughput_per_sec(total_time: Duration, count: usize) -> f64 {
+    let seconds = total_time.as_secs_f64();
+    if seconds > 0.0 {
+        count as f64 / seconds
+    } else {
+        0.0
+    }
+}
+
+/// Buckets in the priority score histograms of the report and TUI
+pub const HISTOGRAM_BUCKETS: usize = 10;
+
+/// Count `values` in `buckets` equal-width buckets spanning their range, as
+/// (start, end, count); a value on a boundary falls in the upper bucket, and
+/// the maximum in the last one. Non-finite values are skipped, and empty input
+/// or zero buckets give no buckets. If all values are equal there is one bucket
+pub fn histogram(values: &[f64], buckets: usize) -> Vec<(f64, f64, usize)> {
+    let finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if finite.is_empty() || buckets == 0 {
+        return Vec::new();
+    }
+    let min = finite.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = finite.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if min == max {
+        return vec![(min, max, finite.len())];
+    }
+
+    let width = (max - min) / buckets as f64;
+    let mut counts = vec![0; buckets];
+    for value in finite {
+        let index = (((value - min) / width) as usize).min(buckets - 1);
+        counts[index] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let start = min + width * i as f64;
+            let end = if i + 1 == buckets {
+                max
+            } else {
+                min + width * (i + 1) as f64
+            };
+            (start, end, count)
+        })
+        .collect()
+}
+
+/// Distribution of per-asset calculation times in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct BenchmarkStats {
+    pub count: usize,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// Summarize `calculation_time_ms` across results using nearest-rank percentiles
+/// All fields are zero when there are no results
+pub fn benchmark_stats(results: &[RiskCalculationResult]) -> BenchmarkStats {
+    let mut times: Vec<f64> = results.iter().map(|r| r.calculation_time_ms).collect();
+    if times.is_empty() {
+        return BenchmarkStats::default();
+    }
+    times.sort_by(|a, b| a.total_cmp(b));
+
+    let percentile = |p: f64| {
+        let rank = (p / 100.0 * times.len() as f64).ceil() as usize;
+        times[rank.clamp(1, times.len()) - 1]
+    };
+
+    BenchmarkStats {
+        count: times.len(),
+        min_ms: times[0],
+        max_ms: times[times.len() - 1],
+        p50_ms: percentile(50.0),
+        p95_ms: percentile(95.0),
+        p99_ms: percentile(99.0),
+    }
+}
+
+/// Machine-readable benchmark figures, as printed by `--benchmark --json`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BenchmarkSummary {
+    /// Alternatives loaded for calculation
+    pub asset_count: usize,
+    /// Alternatives whose calculation failed
+    pub failed_count: usize,
+    pub total_time_ms: f64,
+    /// Average over successful calculations, as in the human-readable output
+    pub avg_time_ms: f64,
+    pub throughput_per_sec: f64,
+    /// Per-calculation time percentiles
+    pub calculation_time: BenchmarkStats,
+}
+
+impl BenchmarkSummary {
+    pub fn new(
+        results: &[RiskCalculationResult],
+        total_time: Duration,
+        asset_count: usize,
+        failed_count: usize,
+    ) -> Self {
+        let calculated = asset_count.saturating_sub(failed_count);
+        Self {
+            asset_count,
+            failed_count,
+            total_time_ms: total_time.as_secs_f64() * 1000.0,
+            avg_time_ms: average_time_ms(total_time, calculated),
+            throughput_per_sec: throughput_per_sec(total_time, calculated),
+            calculation_time: benchmark_stats(results),
+        }
+    }
+}
+
+/// Stage of a run timed by `--profile`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading assets (or saved results) from the input
+    Load,
+    /// Evaluating the risk formulas
+    Calculate,
+    /// Budget strategies, sweeps, multi-period plans and cost minimization
+    Optimize,
+}
+
+/// Wall-clock time spent in each phase of a run
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PhaseTimings {
+    pub load: Duration,
+    pub calculate: Duration,
+    pub optimize: Duration,
+}
+
+impl PhaseTimings {
+    /// Add `elapsed` to `phase`; a phase timed more than once accumulates
+    pub fn record(&mut self, phase: Phase, elapsed: Duration) {
+        match phase {
+            Phase::Load => self.load += elapsed,
+            Phase::Calculate => self.calculate += elapsed,
+            Phase::Optimize => self.optimize += elapsed,
+        }
+    }
+
+    /// Sum of the timed phases
+    pub fn total(&self) -> Duration {
+        self.load + self.calculate + self.optimize
+    }
+
+    /// One line per phase plus the total, in milliseconds
+    pub fn report(&self) -> String {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        [
+            ("Load", self.load),
+            ("Calculate", self.calculate),
+            ("Optimize", self.optimize),
+            ("Total", self.total()),
+        ]
+        .iter()
+        .map(|(label, elapsed)| format!("{:<10} {:>12.2} ms", label, ms(*elapsed)))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
 }
@@ -0,0 +1,143 @@
+use std::fmt;
+
+/// Errors returned by the repositories, calculator and optimizer
+#[derive(Debug)]
+pub enum CapAllocError {
+    /// Reading or writing a file or stream failed
+    Io(std::io::Error),
+    /// CSV input could not be parsed, or CSV output could not be written
+    Csv(csv::Error),
+    /// JSON output could not be written
+    Json(serde_json::Error),
+    /// Excel output could not be written
+    #[cfg(feature = "xlsx")]
+    Xlsx(rust_xlsxwriter::XlsxError),
+    /// A formula failed, is missing, or returned a non-numeric value
+    FormulaEval(String),
+    /// The optimization problem has no solution (e.g. an unreachable target)
+    Infeasible(String),
+    /// Input or configuration was rejected before any work was done
+    Validation(String),
+    /// The LP solver did not finish within the configured timeout
+    TimedOut(String),
+    /// The calculation was stopped by its cancel flag (Ctrl-C) before finishing
+    Cancelled { processed: usize, total: usize },
+}
+
+impl CapAllocError {
+    /// Prefix the message of a message-carrying variant with `context`
+    pub fn with_context(self, context: impl fmt::Display) -> Self {
+        match self {
+            CapAllocError::FormulaEval(message) => {
+                CapAllocError::FormulaEval(format!("{}: {}", context, message))
+            }
+            CapAllocError::Infeasible(message) => {
+                CapAllocError::Infeasible(format!("{}: {}", context, message))
+            }
+            CapAllocError::Validation(message) => {
+                CapAllocError::Validation(format!("{}: {}", context, message))
+            }
+            CapAllocError::TimedOut(message) => {
+                CapAllocError::TimedOut(format!("{}: {}", context, message))
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for CapAllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapAllocError::Io(e) => write!(f, "I/O error: {}", e),
+            CapAllocError::Csv(e) => write!(f, "CSV error: {}", e),
+            CapAllocError::Json(e) => write!(f, "JSON error: {}", e),
+            #[cfg(feature = "xlsx")]
+            CapAllocError::Xlsx(e) => write!(f, "Excel error: {}", e),
+            CapAllocError::FormulaEval(message)
+            | CapAllocError::Infeasible(message)
+            | CapAllocError::Validation(message)
+            | CapAllocError::TimedOut(message) => write!(f, "{}", message),
+            CapAllocError::Cancelled { processed, total } => write!(
+                f,
+                "Calculation cancelled after {} of {} alternatives",
+                processed, total
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CapAllocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CapAllocError::Io(e) => Some(e),
+            CapAllocError::Csv(e) => Some(e),
+            CapAllocError::Json(e) => Some(e),
+            #[cfg(feature = "xlsx")]
+            CapAllocError::Xlsx(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CapAllocError {
+    fn from(e: std::io::Error) -> Self {
+        CapAllocError::Io(e)
+    }
+}
+
+impl From<csv::Error> for CapAllocError {
+    fn from(e: csv::Error) -> Self {
+        CapAllocError::Csv(e)
+    }
+}
+
+impl From<serde_json::Error> for CapAllocError {
+    fn from(e: serde_json::Error) -> Self {
+        CapAllocError::Json(e)
+    }
+}
+
+#[cfg(feature = "xlsx")]
+impl From<rust_xlsxwriter::XlsxError> for CapAllocError {
+    fn from(e: rust_xlsxwriter::XlsxError) -> Self {
+        CapAllocError::Xlsx(e)
+    }
+}
+
+impl From<minilp::Error> for CapAllocError {
+    fn from(e: minilp::Error) -> Self {
+        match e {
+            minilp::Error::Infeasible => CapAllocError::Infeasible(e.to_string()),
+            minilp::Error::Unbounded => CapAllocError::Validation(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_with_context_prefixes_messages_only() {
+        let err = CapAllocError::Validation("bad cap".to_string()).with_context("Budget 10");
+        assert!(matches!(&err, CapAllocError::Validation(m) if m == "Budget 10: bad cap"));
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let err = CapAllocError::from(io).with_context("ignored");
+        assert!(matches!(err, CapAllocError::Io(_)));
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn test_solver_errors_map_to_variants() {
+        assert!(matches!(
+            CapAllocError::from(minilp::Error::Infeasible),
+            CapAllocError::Infeasible(_)
+        ));
+        assert!(matches!(
+            CapAllocError::from(minilp::Error::Unbounded),
+            CapAllocError::Validation(_)
+        ));
+    }
+}
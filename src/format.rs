@@ -0,0 +1,161 @@
+/// Separators and currency symbol used when rendering money amounts
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoneyFormat {
+    pub symbol: String,
+    pub symbol_after: bool,
+    pub thousands_separator: char,
+    pub decimal_separator: char,
+}
+
+impl MoneyFormat {
+    /// `$1,234.56`
+    pub fn us() -> Self {
+        Self {
+            symbol: "$".to_string(),
+            symbol_after: false,
+            thousands_separator: ',',
+            decimal_separator: '.',
+        }
+    }
+
+    /// `1.234,56 $`
+    pub fn european() -> Self {
+        Self {
+            symbol: "$".to_string(),
+            symbol_after: true,
+            thousands_separator: '.',
+            decimal_separator: ',',
+        }
+    }
+
+    /// `1 234,56 $`
+    pub fn french() -> Self {
+        Self {
+            thousands_separator: ' ',
+            ..Self::european()
+        }
+    }
+
+    /// Look up a preset by locale name (e.g. `en-US`, `de-DE`, `fr-FR`)
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        match locale.to_ascii_lowercase().replace('_', "-").as_str() {
+            "us" | "en" | "en-us" | "en-gb" => Some(Self::us()),
+            "eu" | "de" | "de-de" | "es" | "es-es" | "it" | "it-it" | "nl" | "nl-nl" => {
+                Some(Self::european())
+            }
+            "fr" | "fr-fr" => Some(Self::french()),
+            _ => None,
+        }
+    }
+
+    pub fn with_symbol(mut self, symbol: &str) -> Self {
+        self.symbol = symbol.to_string();
+        self
+    }
+
+    /// Format the amount with two decimals and grouped thousands, without a symbol
+    pub fn amount(&self, value: f64) -> String {
+        let abs_value = value.abs();
+        let formatted = format!("{:.2}", abs_value);
+
+        let parts: Vec<&str> = formatted.split('.').collect();
+        let integer_part = parts[0];
+        let decimal_part = if parts.len() > 1 { parts[1] } else { "00" };
+
+        let mut result = String::new();
+        for (i, ch) in integer_part.chars().rev().enumerate() {
+            if i > 0 && i % 3 == 0 {
+                result.push(self.thousands_separator);
+            }
+            result.push(ch);
+        }
+
+        let formatted_integer: String = result.chars().rev().collect();
+        // Values that round to zero are shown unsigned
+        let rounds_to_zero = formatted.bytes().all(|b| b == b'0' || b == b'.');
+        let sign = if value < 0.0 && !rounds_to_zero {
+            "-"
+        } else {
+            ""
+        };
+        format!(
+            "{}{}{}{}",
+            sign, formatted_integer, self.decimal_separator, decimal_part
+        )
+    }
+
+    /// Format the amount with the currency symbol in its locale position
+    pub fn currency(&self, value: f64) -> String {
+        let amount = self.amount(value);
+        if self.symbol_after {
+            format!("{} {}", amount, self.symbol)
+        } else if let Some(unsigned) = amount.strip_prefix('-') {
+            format!("-{}{}", self.symbol, unsigned)
+        } else {
+            format!("{}{}", self.symbol, amount)
+        }
+    }
+}
+
+impl Default for MoneyFormat {
+    fn default() -> Self {
+        Self::us()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_us_style() {
+        let format = MoneyFormat::us();
+        assert_eq!(format.amount(1234567.891), "1,234,567.89");
+        assert_eq!(format.currency(1234.5), "$1,234.50");
+        assert_eq!(format.currency(-1234.5), "-$1,234.50");
+    }
+
+    #[test]
+    fn test_european_style() {
+        let format = MoneyFormat::european();
+        assert_eq!(format.amount(1234.56), "1.234,56");
+        assert_eq!(format.currency(1234567.0), "1.234.567,00 $");
+
+        let euro = MoneyFormat::from_locale("de_DE").unwrap().with_symbol("€");
+        assert_eq!(euro.currency(-1234.56), "-1.234,56 €");
+    }
+
+    #[test]
+    fn test_from_locale() {
+        assert_eq!(MoneyFormat::from_locale("en-US"), Some(MoneyFormat::us()));
+        assert_eq!(
+            MoneyFormat::from_locale("FR-fr"),
+            Some(MoneyFormat::french())
+        );
+        assert_eq!(MoneyFormat::from_locale("xx-YY"), None);
+    }
+
+    #[test]
+    fn test_zero_and_negative_zero() {
+        let format = MoneyFormat::us();
+        assert_eq!(format.currency(0.0), "$0.00");
+        assert_eq!(format.currency(-0.0), "$0.00");
+        assert_eq!(format.currency(-0.004), "$0.00");
+    }
+
+    #[test]
+    fn test_negative_values() {
+        let format = MoneyFormat::us();
+        assert_eq!(format.currency(-0.5), "-$0.50");
+        assert_eq!(format.currency(-999.0), "-$999.00");
+        assert_eq!(format.currency(-1000.0), "-$1,000.00");
+    }
+
+    #[test]
+    fn test_large_values_and_rounding() {
+        let format = MoneyFormat::us();
+        assert_eq!(format.currency(999.999), "$1,000.00");
+        assert_eq!(format.currency(1_000_000.0), "$1,000,000.00");
+        assert_eq!(format.currency(123_456_789_012.34), "$123,456,789,012.34");
+    }
+}
@@ -7,275 +7,1488 @@
 
 mod application;
 mod domain;
+mod error;
+mod format;
 mod repository;
 mod services;
 mod ui;
 
 use application::CapitalAllocationApp;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    cursor,
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use domain::{
+    histogram, CurrencyRates, Phase, PhaseTimings, RiskCalculationResult, RiskLevelConfig,
+    SafetyWeights, HISTOGRAM_BUCKETS,
+};
+use error::CapAllocError;
+use format::MoneyFormat;
 use ratatui::{backend::CrosstermBackend, Terminal};
-use repository::{CsvAssetRepository, InMemoryFormulaRepository};
-use std::{error::Error, io, time::Duration};
-use ui::AppState;
+use repository::{
+    AssetRepository, CsvAssetRepository, CsvFrontierExporter, CsvResultExporter, FormulaRepository,
+    InMemoryAssetRepository, InMemoryFormulaRepository, JsonResultStore, JsonSolutionExporter,
+    LayeredFormulaRepository, MergedAssetRepository,
+};
+use services::{
+    selection_overlap, Contribution, HeuristicComparison, OptimizationSolution, PortfolioOptimizer,
+    SelectionOverlap, SolutionDiff, DEFAULT_BASELINE_POF, DEFAULT_DISCOUNT_RATE, DEFAULT_MONEY_DP,
+    DEFAULT_RATING_DP, DEFAULT_SCORE_DP,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    error::Error,
+    io,
+    time::Duration,
+};
+use ui::{AppState, Strategy, Theme, THEME_NAMES};
+
+/// Process exit codes returned by `run`
+const EXIT_SUCCESS: i32 = 0;
+/// Input could not be loaded or an argument was invalid (errors returned from `run`)
+const EXIT_ERROR: i32 = 1;
+/// `--check` found invalid rows or settings
+const EXIT_VALIDATION_FAILED: i32 = 2;
+/// A budget was given but every optimization strategy failed
+const EXIT_OPTIMIZATION_FAILED: i32 = 3;
+/// Interrupted with Ctrl-C (128 + SIGINT, as shells report it)
+const EXIT_CANCELLED: i32 = 130;
+
+/// Budget strategy keys, as used by `--export-solution` and `--compare-strategies`
+const STRATEGY_KEYS: [&str; 5] = [
+    "risk_reduction",
+    "priority",
+    "combined",
+    "greedy",
+    "soft_budget",
+];
+
+/// Set by `--quiet`; silences informational output but never warnings or errors
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Set by the first Ctrl-C during the calculation, which then stops early
+static CANCELLED: AtomicBool = AtomicBool::new(false);
 
-fn format_money(value: f64) -> String {
-    let abs_value = value.abs();
-    let formatted = format!("{:.2}", abs_value);
+/// Set while the calculation runs; Ctrl-C at any other time exits at once
+static CALCULATING: AtomicBool = AtomicBool::new(false);
 
-    let parts: Vec<&str> = formatted.split('.').collect();
-    let integer_part = parts[0];
-    let decimal_part = if parts.len() > 1 { parts[1] } else { "00" };
+/// `println!` for informational messages, suppressed by `--quiet`
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if !QUIET.load(Ordering::Relaxed) {
+            println!($($arg)*);
+        }
+    };
+}
 
-    let mut result = String::new();
-    for (i, ch) in integer_part.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
+fn main() {
+    // Ctrl-C cancels the calculation cleanly; a second Ctrl-C, or one outside
+    // the calculation, exits immediately. In the TUI raw mode turns Ctrl-C into
+    // a key press instead, so the terminal is always restored
+    let handler = ctrlc::set_handler(|| {
+        if !CALCULATING.load(Ordering::Relaxed) || CANCELLED.swap(true, Ordering::Relaxed) {
+            std::process::exit(EXIT_CANCELLED);
         }
-        result.push(ch);
+        eprintln!("\nCancelling... press Ctrl-C again to exit immediately");
+    });
+    if let Err(e) = handler {
+        eprintln!("Warning: Ctrl-C handler not installed: {}", e);
     }
 
-    let formatted_integer: String = result.chars().rev().collect();
-    let sign = if value < 0.0 { "-" } else { "" };
-    format!("{}{}.{}", sign, formatted_integer, decimal_part)
+    let args: Vec<String> = std::env::args().collect();
+    let code = match run_cli(&args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            match e.downcast_ref::<CapAllocError>() {
+                Some(CapAllocError::Cancelled { .. }) => EXIT_CANCELLED,
+                _ => EXIT_ERROR,
+            }
+        }
+    };
+    std::process::exit(code);
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // Check for benchmark mode and budget
-    let args: Vec<String> = std::env::args().collect();
-    let benchmark_mode =
-        args.contains(&"--benchmark".to_string()) || args.contains(&"-b".to_string());
+/// Options parsed from the command line
+struct AppConfig {
+    /// Input CSV paths, merged in order; "-" is stdin
+    inputs: Vec<String>,
+    delimiter: u8,
+    header_aliases: HashMap<String, String>,
+    benchmark_mode: bool,
+    benchmark_json: bool,
+    dedup_mode: bool,
+    exclude_defer: bool,
+    report_mode: bool,
+    quiet: bool,
+    check_mode: bool,
+    budget: Option<f64>,
+    /// Normalized so the two weights add up to 1
+    risk_weight: f64,
+    priority_weight: f64,
+    threads: Option<usize>,
+    opt_timeout: Option<Duration>,
+    discount_rate: f64,
+    baseline_pof: f64,
+    /// Percentage by which post-action PoF is perturbed for the risk reduction band
+    pof_uncertainty: Option<f64>,
+    /// Percentage added to every cost before calculation
+    cost_inflation: Option<f64>,
+    /// Factor applied to every post-action PoF before calculation
+    pof_scale: Option<f64>,
+    /// Formulas replaced or added by name, on top of the built-in set
+    formula_overrides: BTreeMap<String, String>,
+    money_dp: u32,
+    score_dp: u32,
+    rating_dp: u32,
+    pareto_steps: Option<usize>,
+    period_budgets: Option<Vec<f64>>,
+    min_reduction: Option<f64>,
+    /// Risk reduction given up per dollar over budget in the soft-budget strategy
+    overspend_penalty: Option<f64>,
+    sweep_budgets: Option<Vec<f64>>,
+    money: MoneyFormat,
+    theme: Theme,
+    top_n: Option<usize>,
+    rank_by: Contribution,
+    min_priority: Option<f64>,
+    /// Replace formula priority scores with dataset-relative ones
+    normalize_priority: bool,
+    min_utilization: f64,
+    risk_levels: RiskLevelConfig,
+    safety_weights: SafetyWeights,
+    currency_rates: Option<CurrencyRates>,
+    category_caps: BTreeMap<String, f64>,
+    export_results_path: Option<String>,
+    export_xlsx_path: Option<String>,
+    export_solution_path: Option<String>,
+    save_results_path: Option<String>,
+    /// Skip loading assets and calculating, and use results saved with `--save-results`
+    load_results_path: Option<String>,
+    /// Two strategy keys whose budget solutions are diffed
+    compare_strategies: Option<(String, String)>,
+    /// Print the LP risk reduction portfolio side by side with the greedy one
+    compare_heuristic: bool,
+    /// Print how long loading, calculation and optimization took
+    profile: bool,
+    export_pareto_path: Option<String>,
+}
 
-    // Parse budget if provided
-    let budget = args
-        .iter()
-        .position(|a| a == "--budget" || a == "-B")
-        .and_then(|i| args.get(i + 1))
-        .and_then(|b| b.parse::<f64>().ok());
+impl AppConfig {
+    /// Parse `args`, including the program name
+    fn from_args(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        // Check for benchmark mode and budget
+        let benchmark_mode =
+            args.contains(&"--benchmark".to_string()) || args.contains(&"-b".to_string());
+        // Benchmark figures as one JSON object on stdout; implies --quiet
+        let benchmark_json = args.contains(&"--json".to_string());
+        if benchmark_json && !benchmark_mode {
+            return Err("--json requires --benchmark".into());
+        }
+        let dedup_mode = args.contains(&"--dedup".to_string());
+        let exclude_defer = args.contains(&"--exclude-defer".to_string());
+        let report_mode =
+            args.contains(&"--report".to_string()) || args.contains(&"--no-ui".to_string());
+        let quiet = args.contains(&"--quiet".to_string())
+            || args.contains(&"-q".to_string())
+            || benchmark_json;
+        let check_mode =
+            args.contains(&"--check".to_string()) || args.contains(&"--validate".to_string());
 
-    // Initialize application with CSV repository and formula repository
-    let applicant_repository = Box::new(CsvAssetRepository::new("assets.csv".to_string()));
-    let formula_repository = Box::new(InMemoryFormulaRepository::new());
-    let app = CapitalAllocationApp::new(applicant_repository, formula_repository);
+        // Parse budget if provided
+        let budget = args
+            .iter()
+            .position(|a| a == "--budget" || a == "-B")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|b| b.parse::<f64>().ok());
 
-    // Load assets from repository
-    let assets = app.load_assets()?;
-    let asset_count = assets.len();
+        // Parse combined strategy weights if provided
+        let parse_weight = |flag: &str, default: f64| -> Result<f64, Box<dyn Error>> {
+            match args
+                .iter()
+                .position(|a| a == flag)
+                .and_then(|i| args.get(i + 1))
+            {
+                Some(value) => match value.parse::<f64>() {
+                    Ok(weight) if weight.is_finite() => Ok(weight),
+                    _ => Err(format!("Invalid {} '{}', expected a number", flag, value).into()),
+                },
+                None => Ok(default),
+            }
+        };
+        let risk_weight = parse_weight("--risk-weight", 0.6)?;
+        let priority_weight = parse_weight("--priority-weight", 0.4)?;
+        if risk_weight < 0.0 || priority_weight < 0.0 {
+            return Err("--risk-weight and --priority-weight must be non-negative".into());
+        }
+        if risk_weight + priority_weight <= 0.0 {
+            return Err(
+                "At least one of --risk-weight and --priority-weight must be positive".into(),
+            );
+        }
+        // Normalize so percentages shown alongside the combined strategy add up to 100%
+        let (risk_weight, priority_weight) = (
+            risk_weight / (risk_weight + priority_weight),
+            priority_weight / (risk_weight + priority_weight),
+        );
 
-    println!("Loaded {} asset alternatives", asset_count);
+        // Parse worker thread cap if provided (default: all cores)
+        let threads = match args
+            .iter()
+            .position(|a| a == "--threads")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<usize>() {
+                Ok(n) if n >= 1 => Some(n),
+                _ => return Err(format!("Invalid --threads '{}', expected N >= 1", value).into()),
+            },
+            None => None,
+        };
 
-    // Warn about large datasets in debug mode
-    #[cfg(debug_assertions)]
-    if asset_count > 1000 {
-        eprintln!(
-            "\n⚠️  WARNING: Running {} assets in DEBUG mode will be very slow!",
-            asset_count
+        // Parse LP solve timeout in milliseconds if provided
+        let opt_timeout = match args
+            .iter()
+            .position(|a| a == "--opt-timeout-ms")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<u64>() {
+                Ok(ms) if ms >= 1 => Some(Duration::from_millis(ms)),
+                _ => {
+                    return Err(
+                        format!("Invalid --opt-timeout-ms '{}', expected N >= 1", value).into(),
+                    )
+                }
+            },
+            None => None,
+        };
+
+        // Parse annual discount rate if provided (default 8%)
+        let discount_rate = match args
+            .iter()
+            .position(|a| a == "--discount-rate")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(rate) if rate.is_finite() && rate >= 0.0 => rate,
+                _ => {
+                    return Err(format!(
+                        "Invalid --discount-rate '{}', expected an annual rate >= 0 such as 0.08",
+                        value
+                    )
+                    .into())
+                }
+            },
+            None => DEFAULT_DISCOUNT_RATE,
+        };
+
+        // Parse the do-nothing probability of failure if provided (default 1.0)
+        let baseline_pof = match args
+            .iter()
+            .position(|a| a == "--baseline-pof")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(pof) if pof > 0.0 && pof <= 1.0 => pof,
+                _ => {
+                    return Err(format!(
+                        "Invalid --baseline-pof '{}', expected a probability in (0, 1]",
+                        value
+                    )
+                    .into())
+                }
+            },
+            None => DEFAULT_BASELINE_POF,
+        };
+
+        // Parse the post-action PoF uncertainty in percent if provided
+        let pof_uncertainty = match args
+            .iter()
+            .position(|a| a == "--pof-uncertainty")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(percent) if percent > 0.0 && percent <= 100.0 => Some(percent),
+                _ => {
+                    return Err(format!(
+                        "Invalid --pof-uncertainty '{}', expected a percentage in (0, 100]",
+                        value
+                    )
+                    .into())
+                }
+            },
+            None => None,
+        };
+
+        // Parse uniform cost inflation in percent if provided
+        let cost_inflation = match args
+            .iter()
+            .position(|a| a == "--cost-inflation")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(percent) if percent.is_finite() && percent > -100.0 => Some(percent),
+                _ => {
+                    return Err(format!(
+                    "Invalid --cost-inflation '{}', expected a percentage above -100 such as 10",
+                    value
+                )
+                    .into())
+                }
+            },
+            None => None,
+        };
+
+        // Parse a uniform PoF scale factor if provided
+        let pof_scale = match args
+            .iter()
+            .position(|a| a == "--pof-scale")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(factor) if factor.is_finite() && factor >= 0.0 => Some(factor),
+                _ => {
+                    return Err(format!(
+                        "Invalid --pof-scale '{}', expected a non-negative factor such as 0.8",
+                        value
+                    )
+                    .into())
+                }
+            },
+            None => None,
+        };
+
+        // Parse formula overrides (name=expression); the flag may be repeated
+        let formula_overrides = parse_formula_overrides(args)?;
+
+        // Parse rounding precision for money outputs, scores and ratings if provided
+        // (default 2, 4 and 2)
+        let money_dp = parse_decimal_places(args, "--money-dp", DEFAULT_MONEY_DP)?;
+        let score_dp = parse_decimal_places(args, "--score-dp", DEFAULT_SCORE_DP)?;
+        let rating_dp = parse_decimal_places(args, "--rating-dp", DEFAULT_RATING_DP)?;
+
+        // Parse Pareto frontier step count and export path if provided
+        let pareto_steps = parse_count(args, "--pareto", "a number of steps")?;
+        let export_pareto_path = args
+            .iter()
+            .position(|a| a == "--export-pareto")
+            .and_then(|i| args.get(i + 1))
+            .cloned();
+
+        // Parse per-period budgets (comma-separated) if provided
+        let period_budgets = match args
+            .iter()
+            .position(|a| a == "--periods")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(spec) => Some(parse_periods(spec)?),
+            None => None,
+        };
+
+        // Parse risk reduction target for cost minimization if provided
+        let min_reduction = match args
+            .iter()
+            .position(|a| a == "--min-reduction")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(target) if target.is_finite() => Some(target),
+                _ => {
+                    return Err(format!(
+                        "Invalid --min-reduction '{}', expected a risk reduction target",
+                        value
+                    )
+                    .into())
+                }
+            },
+            None => None,
+        };
+
+        // Parse the soft-budget overspend penalty if provided
+        let overspend_penalty = match args
+            .iter()
+            .position(|a| a == "--overspend-penalty")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(penalty) if penalty.is_finite() && penalty >= 0.0 => Some(penalty),
+                _ => {
+                    return Err(format!(
+                        "Invalid --overspend-penalty '{}', expected a number >= 0",
+                        value
+                    )
+                    .into())
+                }
+            },
+            None => None,
+        };
+        if overspend_penalty.is_some() && budget.is_none() {
+            return Err("--overspend-penalty requires --budget".into());
+        }
+
+        // Parse budget sweep range (min:max:step) if provided
+        let sweep_budgets = match args
+            .iter()
+            .position(|a| a == "--sweep")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(spec) => Some(parse_sweep(spec)?),
+            None => None,
+        };
+
+        // Parse money formatting locale and currency symbol if provided
+        let mut money = match args
+            .iter()
+            .position(|a| a == "--locale")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(locale) => MoneyFormat::from_locale(locale).ok_or_else(|| {
+                format!(
+                    "Unknown --locale '{}', expected one of en-US, de-DE, fr-FR",
+                    locale
+                )
+            })?,
+            None => MoneyFormat::default(),
+        };
+        if let Some(symbol) = args
+            .iter()
+            .position(|a| a == "--currency")
+            .and_then(|i| args.get(i + 1))
+        {
+            money = money.with_symbol(symbol);
+        }
+
+        // Parse TUI color theme if provided (default: red/yellow/green)
+        let theme = match args
+            .iter()
+            .position(|a| a == "--theme")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(name) => Theme::from_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown --theme '{}', expected one of {}",
+                    name,
+                    THEME_NAMES.join(", ")
+                )
+            })?,
+            None => Theme::default(),
+        };
+
+        // Parse top-N limit if provided
+        let top_n = parse_count(args, "--top", "a number of alternatives")?;
+
+        // Parse how selected alternatives are ranked in printed solutions (default: risk)
+        let rank_by = match args
+            .iter()
+            .position(|a| a == "--rank-by")
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+        {
+            None | Some("risk") => Contribution::RiskReduction,
+            Some("priority") => Contribution::Priority,
+            Some(other) => {
+                return Err(
+                    format!("Invalid --rank-by '{}', expected risk or priority", other).into(),
+                )
+            }
+        };
+
+        let normalize_priority = args.contains(&"--normalize-priority".to_string());
+
+        // Parse minimum priority score threshold if provided
+        let min_priority = match args
+            .iter()
+            .position(|a| a == "--min-priority")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(threshold) if threshold.is_finite() => Some(threshold),
+                _ => {
+                    return Err(
+                        format!("Invalid --min-priority '{}', expected a number", value).into(),
+                    )
+                }
+            },
+            None => None,
+        };
+
+        // Parse the budget utilization below which a warning is printed (default 80%)
+        let min_utilization = match args
+            .iter()
+            .position(|a| a == "--min-utilization")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(value) => match value.parse::<f64>() {
+                Ok(ratio) if (0.0..=1.0).contains(&ratio) => ratio,
+                _ => {
+                    return Err(format!(
+                        "Invalid --min-utilization '{}', expected 0.0 to 1.0",
+                        value
+                    )
+                    .into())
+                }
+            },
+            None => 0.8,
+        };
+
+        // Parse custom safety level labels if provided
+        let mut risk_levels = RiskLevelConfig::default();
+        if let Some(labels) = args
+            .iter()
+            .position(|a| a == "--high-risk-labels")
+            .and_then(|i| args.get(i + 1))
+        {
+            risk_levels.high_risk_labels = parse_labels(labels);
+        }
+        if let Some(labels) = args
+            .iter()
+            .position(|a| a == "--critical-labels")
+            .and_then(|i| args.get(i + 1))
+        {
+            risk_levels.critical_labels = parse_labels(labels);
+        }
+
+        // Parse priority weights per safety level (Level=weight,...) if provided
+        let safety_weights = match args
+            .iter()
+            .position(|a| a == "--safety-weights")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(spec) => parse_safety_weights(spec)?,
+            None => SafetyWeights::default(),
+        };
+
+        // Parse exchange rates (CUR=rate,...) into the base currency if provided
+        let currency_rates = match args
+            .iter()
+            .position(|a| a == "--fx-rates")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(spec) => {
+                let base = args
+                    .iter()
+                    .position(|a| a == "--base-currency")
+                    .and_then(|i| args.get(i + 1))
+                    .map(String::as_str)
+                    .unwrap_or("USD");
+                Some(parse_rates(base, spec)?)
+            }
+            None => None,
+        };
+
+        // Parse per-category spending caps (CATEGORY=amount,...) if provided
+        let category_caps = match args
+            .iter()
+            .position(|a| a == "--category-caps")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(spec) => parse_caps(spec)?,
+            None => BTreeMap::new(),
+        };
+
+        // Parse results export path if provided
+        let export_results_path = args
+            .iter()
+            .position(|a| a == "--export-results")
+            .and_then(|i| args.get(i + 1))
+            .cloned();
+
+        // Parse Excel export path if provided (requires the `xlsx` feature)
+        let export_xlsx_path = args
+            .iter()
+            .position(|a| a == "--export-xlsx")
+            .and_then(|i| args.get(i + 1))
+            .cloned();
+        #[cfg(not(feature = "xlsx"))]
+        if let Some(path) = &export_xlsx_path {
+            return Err(format!(
+                "Cannot write {}: rebuild with `--features xlsx` to enable --export-xlsx",
+                path
+            )
+            .into());
+        }
+
+        // Parse solution export path if provided
+        let export_solution_path = args
+            .iter()
+            .position(|a| a == "--export-solution")
+            .and_then(|i| args.get(i + 1))
+            .cloned();
+
+        // Parse paths to save calculated results to, or load them from, if provided
+        let save_results_path = args
+            .iter()
+            .position(|a| a == "--save-results")
+            .and_then(|i| args.get(i + 1))
+            .cloned();
+        let load_results_path = args
+            .iter()
+            .position(|a| a == "--load-results")
+            .and_then(|i| args.get(i + 1))
+            .cloned();
+
+        // Parse the pair of strategies to compare (from,to) if provided
+        let compare_strategies = match args
+            .iter()
+            .position(|a| a == "--compare-strategies")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(spec) => Some(parse_strategy_pair(spec)?),
+            None => None,
+        };
+        if let Some((from, to)) = &compare_strategies {
+            if (from == "soft_budget" || to == "soft_budget") && overspend_penalty.is_none() {
+                return Err("--compare-strategies soft_budget requires --overspend-penalty".into());
+            }
+        }
+
+        let compare_heuristic = args.contains(&"--compare-heuristic".to_string());
+        if compare_heuristic && budget.is_none() {
+            return Err("--compare-heuristic requires --budget".into());
+        }
+        let profile = args.contains(&"--profile".to_string());
+
+        // Parse asset input paths if provided ("-" reads CSV from stdin); --input
+        // may be repeated or given a comma-separated list, and the files are merged
+        let mut inputs: Vec<String> = args
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| *a == "--input")
+            .filter_map(|(i, _)| args.get(i + 1))
+            .flat_map(|spec| spec.split(','))
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect();
+        if inputs.is_empty() {
+            inputs.push("assets.csv".to_string());
+        }
+        if inputs.iter().filter(|path| *path == "-").count() > 1 {
+            return Err("Invalid --input: stdin ('-') can only be read once".into());
+        }
+
+        // Parse CSV delimiter if provided (one ASCII character, or "tab")
+        let delimiter = match args
+            .iter()
+            .position(|a| a == "--delimiter")
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+        {
+            None => b',',
+            Some("tab" | "\\t") => b'\t',
+            Some(value) if value.len() == 1 && value.is_ascii() => value.as_bytes()[0],
+            Some(value) => {
+                return Err(
+                    format!("Invalid --delimiter '{}', expected one character", value).into(),
+                )
+            }
+        };
+
+        // Parse header aliases (Alias=Expected,...) if provided
+        let header_aliases = match args
+            .iter()
+            .position(|a| a == "--header-aliases")
+            .and_then(|i| args.get(i + 1))
+        {
+            Some(spec) => parse_header_aliases(spec)?,
+            None => HashMap::new(),
+        };
+
+        // --json promises a single JSON document on stdout, so nothing else may print there
+        if benchmark_json {
+            let conflicting = [
+                ("--budget", budget.is_some()),
+                ("--sweep", sweep_budgets.is_some()),
+                ("--periods", period_budgets.is_some()),
+                ("--min-reduction", min_reduction.is_some()),
+                ("--report", report_mode),
+                ("--load-results", load_results_path.is_some()),
+            ];
+            if let Some((flag, _)) = conflicting.iter().find(|(_, set)| *set) {
+                return Err(format!("--json cannot be combined with {}", flag).into());
+            }
+        }
+
+        Ok(Self {
+            inputs,
+            delimiter,
+            header_aliases,
+            benchmark_mode,
+            benchmark_json,
+            dedup_mode,
+            exclude_defer,
+            report_mode,
+            quiet,
+            check_mode,
+            budget,
+            risk_weight,
+            priority_weight,
+            threads,
+            opt_timeout,
+            discount_rate,
+            baseline_pof,
+            pof_uncertainty,
+            cost_inflation,
+            pof_scale,
+            formula_overrides,
+            money_dp,
+            score_dp,
+            rating_dp,
+            pareto_steps,
+            period_budgets,
+            min_reduction,
+            overspend_penalty,
+            sweep_budgets,
+            money,
+            theme,
+            top_n,
+            rank_by,
+            min_priority,
+            normalize_priority,
+            min_utilization,
+            risk_levels,
+            safety_weights,
+            currency_rates,
+            category_caps,
+            export_results_path,
+            export_xlsx_path,
+            export_solution_path,
+            save_results_path,
+            load_results_path,
+            compare_strategies,
+            compare_heuristic,
+            profile,
+            export_pareto_path,
+        })
+    }
+
+    /// CSV repository for `--input`, configured with the delimiter and header aliases
+    /// Several inputs are loaded and merged up front, warning about asset IDs
+    /// that appear in more than one of them
+    fn open_repository(&self) -> Result<Box<dyn AssetRepository>, CapAllocError> {
+        let open = |input: &str| -> Result<CsvAssetRepository, CapAllocError> {
+            Ok(CsvAssetRepository::open(input)?
+                .with_delimiter(self.delimiter)
+                .with_header_aliases(self.header_aliases.clone()))
+        };
+        if let [input] = self.inputs.as_slice() {
+            return Ok(Box::new(open(input)?));
+        }
+
+        let merged =
+            self.inputs
+                .iter()
+                .try_fold(MergedAssetRepository::new(), |merged, input| {
+                    Ok::<_, CapAllocError>(
+                        merged.with_source(source_name(input), Box::new(open(input)?)),
+                    )
+                })?;
+        let (assets, collisions) = merged.load_merged()?;
+        for collision in &collisions {
+            eprintln!(
+                "Warning: asset {} appears in more than one input: {}",
+                collision.asset_id,
+                collision.sources.join(", ")
+            );
+        }
+        info!(
+            "Merged {} asset alternatives from {} inputs",
+            assets.len(),
+            self.inputs.len()
         );
-        eprintln!("   For large datasets, use RELEASE mode:");
-        eprintln!(
-            "   cargo run --release{}\n",
-            if benchmark_mode {
-                " -- --benchmark"
-            } else {
-                ""
+        Ok(Box::new(InMemoryAssetRepository::new(assets)))
+    }
+
+    /// Inputs as named in messages
+    fn input_label(&self) -> String {
+        self.inputs
+            .iter()
+            .map(|input| source_name(input))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Optimizer settings shared by the CLI strategies and TUI re-optimization
+    fn optimizer(&self) -> PortfolioOptimizer {
+        let optimizer = PortfolioOptimizer::new()
+            .with_do_nothing_excluded(self.exclude_defer)
+            .with_category_caps(self.category_caps.clone());
+        match self.opt_timeout {
+            Some(timeout) => optimizer.with_timeout(timeout),
+            None => optimizer,
+        }
+    }
+
+    /// Application reading assets from `repository`, configured with these options
+    fn build_app(&self, repository: Box<dyn AssetRepository>) -> CapitalAllocationApp {
+        let mut app =
+            CapitalAllocationApp::new(repository, formula_repository(&self.formula_overrides))
+                .with_cancel_flag(&CANCELLED)
+                .with_optimizer(self.optimizer())
+                .with_risk_levels(self.risk_levels.clone())
+                .with_safety_weights(self.safety_weights.clone())
+                .with_discount_rate(self.discount_rate)
+                .with_baseline_pof(self.baseline_pof)
+                .with_precision(self.money_dp, self.score_dp, self.rating_dp);
+        if let Some(percent) = self.cost_inflation {
+            app = app.with_cost_inflation(percent);
+        }
+        if let Some(factor) = self.pof_scale {
+            app = app.with_pof_scale(factor);
+        }
+        if let Some(percent) = self.pof_uncertainty {
+            app = app.with_pof_uncertainty(percent);
+        }
+        match &self.currency_rates {
+            Some(rates) => app.with_currency_rates(rates.clone()),
+            None => app,
+        }
+    }
+}
+
+/// What `run` computed, for the report, the TUI and tests
+struct RunOutput {
+    /// Asset alternatives loaded, after deduplication
+    asset_count: usize,
+    /// Calculated results after the `--min-priority` and `--top` filters
+    results: Vec<RiskCalculationResult>,
+    total_time: Duration,
+    /// Strategies that succeeded for `--budget`, keyed as in `--export-solution`
+    solutions: Vec<(&'static str, OptimizationSolution)>,
+}
+
+impl RunOutput {
+    /// Solution of the strategy exported under `key`, if it succeeded
+    fn solution(&self, key: &str) -> Option<&OptimizationSolution> {
+        self.solutions
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, solution)| solution)
+    }
+}
+
+/// Parse `args`, run the calculation and optimizations, then show the report or TUI
+/// Returns the process exit code; errors map to `EXIT_ERROR` in `main`
+fn run_cli(args: &[String]) -> Result<i32, Box<dyn Error>> {
+    let config = AppConfig::from_args(args)?;
+    QUIET.store(config.quiet, Ordering::Relaxed);
+    // Saved results replace the asset inputs, so those are only opened to calculate or check
+    let repository: Box<dyn AssetRepository> = match &config.load_results_path {
+        Some(_) if !config.check_mode => Box::new(InMemoryAssetRepository::new(Vec::new())),
+        _ => config.open_repository()?,
+    };
+
+    // Validate the dataset and settings, then exit without calculating
+    if config.check_mode {
+        let report = config.build_app(repository).validate()?;
+        info!(
+            "Checked {} alternatives across {} assets",
+            report.alternatives, report.assets
+        );
+        for issue in &report.issues {
+            eprintln!("Invalid: {}", issue);
+        }
+        if !report.is_valid() {
+            eprintln!("Validation failed: {} issue(s)", report.issues.len());
+            return Ok(EXIT_VALIDATION_FAILED);
+        }
+        info!("No issues found");
+        return Ok(EXIT_SUCCESS);
+    }
+
+    let output = run(&config, repository)?;
+    if output.asset_count == 0 {
+        return Ok(EXIT_SUCCESS);
+    }
+    let exit_code = if config.budget.is_some() && output.solutions.is_empty() {
+        eprintln!("Error: All optimizations failed");
+        EXIT_OPTIMIZATION_FAILED
+    } else {
+        EXIT_SUCCESS
+    };
+
+    // If in report mode, print the full result table and exit without launching UI
+    if config.report_mode {
+        print_report(&output.results, &config.money);
+        return Ok(exit_code);
+    }
+
+    // If in benchmark mode, exit without launching UI
+    if config.benchmark_mode {
+        info!("\nBenchmark complete!");
+        return Ok(exit_code);
+    }
+
+    run_tui(&config, output)?;
+    Ok(exit_code)
+}
+
+/// Load, calculate and optimize as configured, printing progress and summaries
+fn run(
+    config: &AppConfig,
+    repository: Box<dyn AssetRepository>,
+) -> Result<RunOutput, CapAllocError> {
+    let money = &config.money;
+    let app = config.build_app(repository);
+    let mut timings = PhaseTimings::default();
+
+    // Reuse saved results if requested, otherwise load assets and calculate
+    let (mut results, total_time, asset_count) = match &config.load_results_path {
+        Some(path) => {
+            let load_start = std::time::Instant::now();
+            let results = JsonResultStore::new(path.clone()).load()?;
+            timings.record(Phase::Load, load_start.elapsed());
+            info!("Loaded {} saved results from {}", results.len(), path);
+            let count = results.len();
+            (results, Duration::ZERO, count)
+        }
+        None => match calculate_results(config, &app, &mut timings)? {
+            Some(calculated) => calculated,
+            None => {
+                return Ok(RunOutput {
+                    asset_count: 0,
+                    results: Vec::new(),
+                    total_time: Duration::ZERO,
+                    solutions: Vec::new(),
+                })
             }
+        },
+    };
+
+    // Save results for a later --load-results run if requested
+    if let Some(path) = &config.save_results_path {
+        JsonResultStore::new(path.clone()).save(&results)?;
+        info!("Saved {} results to {}", results.len(), path);
+    }
+
+    // Ranking from here on uses the normalized score; the formula score is kept alongside
+    if config.normalize_priority {
+        app.normalize_priority_scores(&mut results);
+        info!(
+            "Normalized priority scores across {} alternatives",
+            results.len()
         );
-        eprintln!("   Press Ctrl+C to cancel, or wait for debug build to complete...\n");
     }
 
-    println!("Calculating risk metrics in parallel...");
+    // Export calculation results if requested
+    if let Some(path) = &config.export_results_path {
+        CsvResultExporter::new(path.clone()).export(&results)?;
+        info!("Exported {} results to {}", results.len(), path);
+    }
 
-    // Calculate all risk metrics in parallel
-    let (results, total_time) = app.calculate_all_risks(assets);
+    // Without the `xlsx` feature `from_args` rejects --export-xlsx before any work
+    #[cfg(feature = "xlsx")]
+    if let Some(path) = &config.export_xlsx_path {
+        repository::XlsxResultExporter::new(path.clone())
+            .with_risk_levels(config.risk_levels.clone())
+            .export(&results)?;
+        info!("Exported {} results to {}", results.len(), path);
+    }
 
-    println!(
-        "Calculated risk metrics for {} alternatives in {:.2}ms",
-        results.len(),
-        total_time.as_secs_f64() * 1000.0
-    );
-    println!(
-        "Average time per calculation: {:.2}ms",
-        total_time.as_secs_f64() * 1000.0 / results.len() as f64
-    );
+    // Drop alternatives below the priority threshold before optimization and display
+    let results = match config.min_priority {
+        Some(threshold) => {
+            let (kept, removed) = CapitalAllocationApp::filter_by_min_priority(results, threshold);
+            info!(
+                "Filtered out {} alternatives with priority score below {}",
+                removed, threshold
+            );
+            kept
+        }
+        None => results,
+    };
 
-    // Run optimization if budget is provided
-    if let Some(budget_amount) = budget {
-        println!("\n=== PORTFOLIO OPTIMIZATION (Linear Programming) ===");
-        println!("Budget constraint: ${}", format_money(budget_amount));
-        println!("Using minilp solver for optimal solution");
+    // Limit optimization and display to the highest-priority alternatives
+    let results = match config.top_n {
+        Some(n) => {
+            info!("Keeping top {} alternatives by priority score", n);
+            CapitalAllocationApp::top_by_priority(&results, n)
+        }
+        None => results,
+    };
 
-        let opt_start = std::time::Instant::now();
+    let rank_by = config.rank_by;
+    let min_utilization = config.min_utilization;
+    let (risk_weight, priority_weight) = (config.risk_weight, config.priority_weight);
+
+    // Run optimization if budget is provided
+    let mut solutions = Vec::new();
+    if let Some(budget_amount) = config.budget {
+        info!("\n=== PORTFOLIO OPTIMIZATION (Linear Programming) ===");
+        info!("Budget constraint: {}", money.currency(budget_amount));
+        info!("Using minilp solver for optimal solution");
 
         // Strategy 1: Maximize risk reduction
-        match app.optimize_by_risk_reduction(&results, budget_amount) {
+        let (outcome, opt_time) = timed(&mut timings, || {
+            app.optimize_by_risk_reduction(&results, budget_amount)
+        });
+        match outcome {
             Ok(solution) => {
-                let opt_time = opt_start.elapsed();
-                println!("\n--- Strategy 1: Maximize Risk Reduction ---");
-                println!("Selected {} alternatives", solution.num_assets_optimized);
-                println!("Total cost: ${}", format_money(solution.total_cost));
-                println!(
-                    "Total risk reduction: ${}",
-                    format_money(solution.total_risk_reduction)
+                print_solution(
+                    "Strategy 1: Maximize Risk Reduction",
+                    &solution,
+                    &results,
+                    rank_by,
+                    min_utilization,
+                    Some(opt_time),
+                    money,
                 );
-                println!("Total priority score: {:.4}", solution.total_priority_score);
-                println!(
-                    "Optimization time: {:.2}ms",
-                    opt_time.as_secs_f64() * 1000.0
-                );
-
-                if solution.num_assets_optimized <= 10 {
-                    println!("\nSelected alternatives:");
-                    for alt in &solution.selected_alternatives {
-                        println!("  - {}", alt);
-                    }
-                }
+                solutions.push(("risk_reduction", solution));
             }
             Err(e) => eprintln!("Optimization error: {}", e),
         }
 
         // Strategy 2: Maximize priority score
-        let opt_start = std::time::Instant::now();
-        match app.optimize_by_priority(&results, budget_amount) {
+        let (outcome, opt_time) = timed(&mut timings, || {
+            app.optimize_by_priority(&results, budget_amount)
+        });
+        match outcome {
             Ok(solution) => {
-                let opt_time = opt_start.elapsed();
-                println!("\n--- Strategy 2: Maximize Priority Score ---");
-                println!("Selected {} alternatives", solution.num_assets_optimized);
-                println!("Total cost: ${}", format_money(solution.total_cost));
-                println!(
-                    "Total risk reduction: ${}",
-                    format_money(solution.total_risk_reduction)
-                );
-                println!("Total priority score: {:.4}", solution.total_priority_score);
-                println!(
-                    "Optimization time: {:.2}ms",
-                    opt_time.as_secs_f64() * 1000.0
+                print_solution(
+                    "Strategy 2: Maximize Priority Score",
+                    &solution,
+                    &results,
+                    rank_by,
+                    min_utilization,
+                    Some(opt_time),
+                    money,
                 );
-
-                if solution.num_assets_optimized <= 10 {
-                    println!("\nSelected alternatives:");
-                    for alt in &solution.selected_alternatives {
-                        println!("  - {}", alt);
-                    }
-                }
+                solutions.push(("priority", solution));
             }
             Err(e) => eprintln!("Priority optimization error: {}", e),
         }
 
         // Strategy 3: Combined weighted objective
-        let opt_start = std::time::Instant::now();
-        match app.optimize_combined(&results, budget_amount, 0.6, 0.4) {
+        let (outcome, opt_time) = timed(&mut timings, || {
+            app.optimize_combined(&results, budget_amount, risk_weight, priority_weight)
+        });
+        match outcome {
             Ok(solution) => {
-                let opt_time = opt_start.elapsed();
-                println!("\n--- Strategy 3: Combined (60% Risk, 40% Priority) ---");
-                println!("Selected {} alternatives", solution.num_assets_optimized);
-                println!("Total cost: ${}", format_money(solution.total_cost));
-                println!(
-                    "Total risk reduction: ${}",
-                    format_money(solution.total_risk_reduction)
+                print_solution(
+                    &format!(
+                        "Strategy 3: Combined ({:.0}% Risk, {:.0}% Priority)",
+                        risk_weight * 100.0,
+                        priority_weight * 100.0
+                    ),
+                    &solution,
+                    &results,
+                    rank_by,
+                    min_utilization,
+                    Some(opt_time),
+                    money,
                 );
-                println!("Total priority score: {:.4}", solution.total_priority_score);
-                println!(
-                    "Optimization time: {:.2}ms",
-                    opt_time.as_secs_f64() * 1000.0
+                solutions.push(("combined", solution));
+            }
+            Err(e) => eprintln!("Combined optimization error: {}", e),
+        }
+
+        // Baseline: greedy by cost effectiveness, for comparison with the LP strategies
+        let (outcome, opt_time) = timed(&mut timings, || {
+            app.optimize_greedy(&results, budget_amount)
+        });
+        match outcome {
+            Ok(solution) => {
+                print_solution(
+                    "Baseline: Greedy by Cost Effectiveness",
+                    &solution,
+                    &results,
+                    rank_by,
+                    min_utilization,
+                    Some(opt_time),
+                    money,
                 );
+                solutions.push(("greedy", solution));
+            }
+            Err(e) => eprintln!("Greedy optimization error: {}", e),
+        }
 
-                if solution.num_assets_optimized <= 10 {
-                    println!("\nSelected alternatives:");
-                    for alt in &solution.selected_alternatives {
-                        println!("  - {}", alt);
-                    }
+        // Soft budget: risk reduction bought with a penalized overspend
+        if let Some(penalty) = config.overspend_penalty {
+            let (outcome, opt_time) = timed(&mut timings, || {
+                app.optimize_soft_budget(&results, budget_amount, penalty)
+            });
+            match outcome {
+                Ok(solution) => {
+                    print_solution(
+                        &format!("Soft Budget (overspend penalty {} per dollar)", penalty),
+                        &solution,
+                        &results,
+                        rank_by,
+                        min_utilization,
+                        Some(opt_time),
+                        money,
+                    );
+                    solutions.push(("soft_budget", solution));
                 }
+                Err(e) => eprintln!("Soft budget optimization error: {}", e),
             }
-            Err(e) => eprintln!("Combined optimization error: {}", e),
         }
-    }
 
-    // If in benchmark mode, exit without launching UI
-    if benchmark_mode {
-        println!("\nBenchmark complete!");
-        return Ok(());
-    }
+        // Show how far the three LP strategies agree
+        let lp_selections: Vec<HashSet<String>> = ["risk_reduction", "priority", "combined"]
+            .iter()
+            .filter_map(|key| solutions.iter().find(|(name, _)| name == key))
+            .map(|(_, solution)| solution.selected_alternatives.iter().cloned().collect())
+            .collect();
+        if let [risk, priority, combined] = lp_selections.as_slice() {
+            print_overlap(&selection_overlap(risk, priority, combined));
+        }
 
-    // Run all three optimizations for UI if budget provided
-    let optimization_results = if let Some(budget_amount) = budget {
-        println!("\nRunning all three optimization strategies for UI display...");
+        // Export optimization solutions if requested
+        if let Some(path) = &config.export_solution_path {
+            JsonSolutionExporter::new(path.clone()).export(&solutions)?;
+            info!("\nExported {} solutions to {}", solutions.len(), path);
+        }
 
-        let risk_solution = app.optimize_by_risk_reduction(&results, budget_amount).ok();
-        let priority_solution = app.optimize_by_priority(&results, budget_amount).ok();
-        let combined_solution = app
-            .optimize_combined(&results, budget_amount, 0.6, 0.4)
-            .ok();
+        // Show what changes between two strategies' portfolios if requested
+        if let Some((from, to)) = &config.compare_strategies {
+            let find = |key: &str| solutions.iter().find(|(name, _)| *name == key);
+            match (find(from), find(to)) {
+                (Some((_, before)), Some((_, after))) => {
+                    print_diff(from, to, &before.diff(after), money)
+                }
+                _ => eprintln!(
+                    "Cannot compare {} and {}: a strategy failed to optimize",
+                    from, to
+                ),
+            }
+        }
 
-        if risk_solution.is_some() || priority_solution.is_some() || combined_solution.is_some() {
+        // Show how much the LP gains over the greedy heuristic if requested
+        if config.compare_heuristic {
+            let find = |key: &str| solutions.iter().find(|(name, _)| *name == key);
+            match (find("risk_reduction"), find("greedy")) {
+                (Some((_, lp)), Some((_, greedy))) => {
+                    if lp.was_fallback {
+                        eprintln!("Warning: the LP solver fell back to greedy; both columns show the greedy portfolio");
+                    }
+                    print_heuristic_comparison(&HeuristicComparison::new(lp, greedy), money)
+                }
+                _ => eprintln!("Cannot compare LP and greedy: a strategy failed to optimize"),
+            }
+        }
+
+        // Trace the risk vs. cost trade-off curve up to the budget
+        if let Some(steps) = config.pareto_steps {
+            let (frontier, _) = timed(&mut timings, || {
+                app.pareto_frontier(&results, budget_amount, steps)
+            });
+            let frontier = frontier?;
+            info!("\n=== PARETO FRONTIER (Risk Reduction vs. Cost) ===");
             println!(
-                "Risk Strategy: {} selected",
-                risk_solution
-                    .as_ref()
-                    .map(|s| s.num_assets_optimized)
-                    .unwrap_or(0)
+                "{:>18} {:>20} {:>10} {:>10}",
+                "Cost", "Risk Reduction", "Selected", "Captured"
             );
+            for solution in &frontier {
+                println!(
+                    "{:>18} {:>20} {:>10} {:>9.1}%",
+                    money.currency(solution.total_cost),
+                    money.currency(solution.total_risk_reduction),
+                    solution.num_assets_optimized,
+                    solution.capture_ratio * 100.0
+                );
+            }
+
+            if let Some(path) = &config.export_pareto_path {
+                CsvFrontierExporter::new(path.clone()).export(&frontier)?;
+                info!("Exported {} frontier points to {}", frontier.len(), path);
+            }
+        }
+    }
+
+    // Run budget sensitivity analysis if requested
+    if let Some(budgets) = &config.sweep_budgets {
+        info!("\n=== BUDGET SENSITIVITY (Maximize Risk Reduction) ===");
+        println!(
+            "{:>18} {:>20} {:>10} {:>10}",
+            "Budget", "Risk Reduction", "Selected", "Captured"
+        );
+        let (sweep, _) = timed(&mut timings, || app.budget_sweep(&results, budgets));
+        for (budget_level, solution) in sweep? {
             println!(
-                "Priority Strategy: {} selected",
-                priority_solution
-                    .as_ref()
-                    .map(|s| s.num_assets_optimized)
-                    .unwrap_or(0)
+                "{:>18} {:>20} {:>10} {:>9.1}%",
+                money.currency(budget_level),
+                money.currency(solution.total_risk_reduction),
+                solution.num_assets_optimized,
+                solution.capture_ratio * 100.0
             );
-            println!(
-                "Combined Strategy: {} selected",
-                combined_solution
-                    .as_ref()
-                    .map(|s| s.num_assets_optimized)
-                    .unwrap_or(0)
+        }
+    }
+
+    // Run multi-period planning if requested
+    if let Some(budgets) = &config.period_budgets {
+        info!("\n=== MULTI-PERIOD PLAN (Maximize Risk Reduction) ===");
+        let (periods, opt_time) = timed(&mut timings, || {
+            app.optimize_multi_period(&results, budgets)
+        });
+        let periods = periods?;
+        info!(
+            "Plan optimization time: {:.2}ms",
+            opt_time.as_secs_f64() * 1000.0
+        );
+        for (i, (solution, budget_level)) in periods.iter().zip(budgets).enumerate() {
+            print_solution(
+                &format!(
+                    "Period {} (budget {})",
+                    i + 1,
+                    money.currency(*budget_level)
+                ),
+                solution,
+                &results,
+                rank_by,
+                min_utilization,
+                None,
+                money,
             );
-            Some((risk_solution, priority_solution, combined_solution))
-        } else {
-            eprintln!("Warning: All optimizations failed");
-            None
         }
+    }
+
+    // Find the cheapest portfolio reaching the risk reduction target if requested
+    if let Some(target) = config.min_reduction {
+        info!("\n=== COST MINIMIZATION (Risk Reduction Target) ===");
+        info!("Target risk reduction: {}", money.currency(target));
+        let (outcome, opt_time) = timed(&mut timings, || app.optimize_min_cost(&results, target));
+        match outcome {
+            Ok(solution) => print_solution(
+                "Cheapest Portfolio Meeting the Target",
+                &solution,
+                &results,
+                rank_by,
+                min_utilization,
+                Some(opt_time),
+                money,
+            ),
+            Err(e) => eprintln!("Cost minimization error: {}", e),
+        }
+    }
+
+    // Profile output goes to stderr so it never mixes with results
+    if config.profile {
+        eprintln!("\n=== PROFILE ===");
+        eprintln!("{}", timings.report());
+    }
+
+    Ok(RunOutput {
+        asset_count,
+        results,
+        total_time,
+        solutions,
+    })
+}
+
+/// Load assets and calculate their risk metrics, printing progress and timing
+/// Returns the results, total calculation time and asset count, or None without assets
+/// The load and calculation phases are added to `timings`
+fn calculate_results(
+    config: &AppConfig,
+    app: &CapitalAllocationApp,
+    timings: &mut PhaseTimings,
+) -> Result<Option<(Vec<RiskCalculationResult>, Duration, usize)>, CapAllocError> {
+    let money = &config.money;
+    let thread_pool = match config.threads {
+        Some(n) => Some(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .map_err(|e| CapAllocError::Validation(e.to_string()))?,
+        ),
+        None => None,
+    };
+    // Load assets from repository
+    let load_start = std::time::Instant::now();
+    let assets = app.load_assets()?;
+    timings.record(Phase::Load, load_start.elapsed());
+    if assets.is_empty() {
+        info!(
+            "No asset alternatives found in {}; nothing to calculate.",
+            config.input_label()
+        );
+        info!("Add rows below the header (see the CSV Schema section of the README).");
+        return Ok(None);
+    }
+
+    // Warn about alternative IDs reused under one asset with different data
+    for conflict in domain::find_alternative_conflicts(&assets) {
+        let variants: Vec<String> = conflict
+            .variants
+            .iter()
+            .map(|(cost, pof)| format!("cost {} / PoF {:.4}", money.currency(*cost), pof))
+            .collect();
+        eprintln!(
+            "Warning: {} has conflicting rows for alternative {}: {}",
+            conflict.asset_id,
+            conflict.alternative_id,
+            variants.join(", ")
+        );
+    }
+    let (assets, duplicate_count) = if config.dedup_mode {
+        domain::deduplicate_assets(assets)
     } else {
-        None
+        let count = domain::count_duplicate_assets(&assets);
+        (assets, count)
     };
+    let asset_count = assets.len();
 
-    // Setup terminal
+    info!("Loaded {} asset alternatives", asset_count);
+    if duplicate_count > 0 {
+        if config.dedup_mode {
+            info!("Removed {} duplicate rows", duplicate_count);
+        } else {
+            eprintln!(
+                "Warning: {} duplicate Asset_ID/Alternative_ID rows found (use --dedup to remove)",
+                duplicate_count
+            );
+        }
+    }
+
+    // Warn about large datasets in debug mode
+    #[cfg(debug_assertions)]
+    if asset_count > 1000 {
+        eprintln!(
+            "\n⚠️  WARNING: Running {} assets in DEBUG mode will be very slow!",
+            asset_count
+        );
+        eprintln!("   For large datasets, use RELEASE mode:");
+        eprintln!(
+            "   cargo run --release{}\n",
+            if config.benchmark_mode {
+                " -- --benchmark"
+            } else {
+                ""
+            }
+        );
+        eprintln!("   Press Ctrl+C to cancel, or wait for debug build to complete...\n");
+    }
+
+    info!("Calculating risk metrics in parallel...");
+
+    // Calculate all risk metrics in parallel
+    // Progress output is skipped in benchmark mode to keep timing clean, and with --quiet
+    // With --top and no full-result export or save, only the best N are ever held in memory
+    // Normalized scores depend on every result, so --normalize-priority keeps them all
+    let stream_top = !config.normalize_priority
+        && config.export_results_path.is_none()
+        && config.export_xlsx_path.is_none()
+        && config.save_results_path.is_none();
+    let show_progress = !config.benchmark_mode && !config.quiet;
+    let calculate = || match config.top_n {
+        Some(n) if stream_top => app.calculate_top_by_priority(assets, n, show_progress),
+        _ if show_progress => app.calculate_all_risks_with_progress(assets, true),
+        _ => app.calculate_all_risks(assets),
+    };
+    CALCULATING.store(true, Ordering::Relaxed);
+    let calculate_start = std::time::Instant::now();
+    let calculated = match &thread_pool {
+        Some(pool) => pool.install(calculate),
+        None => calculate(),
+    };
+    timings.record(Phase::Calculate, calculate_start.elapsed());
+    CALCULATING.store(false, Ordering::Relaxed);
+    let (results, failures, total_time) = calculated?;
+    let calculated = asset_count - failures.len();
+
+    info!(
+        "Calculated risk metrics for {} alternatives in {:.2}ms",
+        calculated,
+        total_time.as_secs_f64() * 1000.0
+    );
+    if !failures.is_empty() {
+        eprintln!("{} calculations failed", failures.len());
+        for (_asset, reason) in failures.iter().take(5) {
+            eprintln!("  - {}", reason);
+        }
+        if failures.len() > 5 {
+            eprintln!("  ... and {} more", failures.len() - 5);
+        }
+    }
+    info!(
+        "Average time per calculation: {:.2}ms",
+        domain::average_time_ms(total_time, calculated)
+    );
+    info!(
+        "Throughput: {:.0} alternatives/sec",
+        domain::throughput_per_sec(total_time, calculated)
+    );
+    if config.benchmark_json {
+        let summary =
+            domain::BenchmarkSummary::new(&results, total_time, asset_count, failures.len());
+        println!("{}", serde_json::to_string(&summary)?);
+    } else if config.benchmark_mode {
+        let stats = domain::benchmark_stats(&results);
+        info!(
+            "Per-calculation time (ms) over {} results: min {:.3}, p50 {:.3}, p95 {:.3}, p99 {:.3}, max {:.3}",
+            stats.count, stats.min_ms, stats.p50_ms, stats.p95_ms, stats.p99_ms, stats.max_ms
+        );
+    }
+
+    Ok(Some((results, total_time, asset_count)))
+}
+
+/// Show `output` in the interactive TUI until the user quits
+fn run_tui(config: &AppConfig, output: RunOutput) -> Result<(), Box<dyn Error>> {
+    // Pass the three LP strategies' selections to the UI if a budget was provided
+    let selections = |key: &str| {
+        output
+            .solution(key)
+            .map(|s| s.selected_alternatives.clone())
+            .unwrap_or_default()
+    };
+    let optimization_results = match config.budget {
+        Some(budget_amount) if !output.solutions.is_empty() => {
+            for strategy in Strategy::ALL {
+                info!(
+                    "{} Strategy: {} selected",
+                    strategy.label(),
+                    output
+                        .solution(strategy.key())
+                        .map_or(0, |s| s.num_assets_optimized)
+                );
+            }
+            let [risk, priority, combined] = Strategy::ALL.map(|s| selections(s.key()));
+            Some((risk, priority, combined, budget_amount))
+        }
+        _ => None,
+    };
+
+    // Setup terminal; the guard restores it however the TUI exits
     enable_raw_mode()?;
+    let guard = TerminalGuard;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create UI state and run
-    let mut state = if let Some((risk_sol, priority_sol, combined_sol)) = optimization_results {
-        AppState::with_optimization(
-            results,
-            total_time,
-            risk_sol
-                .map(|s| s.selected_alternatives)
-                .unwrap_or_default(),
-            priority_sol
-                .map(|s| s.selected_alternatives)
-                .unwrap_or_default(),
-            combined_sol
-                .map(|s| s.selected_alternatives)
-                .unwrap_or_default(),
-            budget.unwrap(),
-        )
-    } else {
-        AppState::new(results, total_time)
-    };
+    let solutions: BTreeMap<Strategy, OptimizationSolution> = Strategy::ALL
+        .into_iter()
+        .filter_map(|s| Some((s, output.solution(s.key())?.clone())))
+        .collect();
+    let mut state = match optimization_results {
+        Some((risk, priority, combined, budget_amount)) => AppState::with_optimization(
+            output.results,
+            output.total_time,
+            risk,
+            priority,
+            combined,
+            budget_amount,
+        ),
+        None => AppState::new(output.results, output.total_time),
+    }
+    .with_solutions(solutions)
+    .with_combined_weights(config.risk_weight, config.priority_weight)
+    .with_optimizer(config.optimizer())
+    .with_risk_levels(config.risk_levels.clone())
+    .with_money_format(config.money.clone())
+    .with_precision(config.score_dp, config.rating_dp)
+    .with_theme(config.theme);
     let res = run_ui(&mut terminal, &mut state);
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    drop(guard);
 
     if let Err(err) = res {
         println!("{:?}", err)
@@ -284,6 +1497,516 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Parse a `min:max:step` budget range into the list of budget levels
+/// `max` is always the last level, even when the step does not divide the range
+fn parse_sweep(spec: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+    let parts: Vec<f64> = spec
+        .split(':')
+        .map(|p| p.parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid --sweep '{}', expected min:max:step", spec))?;
+
+    match parts.as_slice() {
+        [min, max, step] if *step > 0.0 && min <= max => {
+            // Tolerate float error so e.g. 0:0.3:0.1 counts three steps, not two
+            let tolerance = step * 1e-9;
+            let count = ((max - min + tolerance) / step).floor() as usize;
+            let mut levels: Vec<f64> = (0..=count).map(|i| min + step * i as f64).collect();
+            match levels.last_mut() {
+                Some(last) if (*max - *last).abs() <= tolerance => *last = *max,
+                _ => levels.push(*max),
+            }
+            Ok(levels)
+        }
+        _ => Err(format!(
+            "Invalid --sweep '{}', expected min:max:step with min <= max and step > 0",
+            spec
+        )
+        .into()),
+    }
+}
+
+/// Parse the decimal places given after `flag` (0 to 10), or `default` when absent
+fn parse_decimal_places(args: &[String], flag: &str, default: u32) -> Result<u32, Box<dyn Error>> {
+    match args
+        .iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => match value.parse::<u32>() {
+            Ok(places) if places <= 10 => Ok(places),
+            _ => Err(format!("Invalid {} '{}', expected 0 to 10", flag, value).into()),
+        },
+        None => Ok(default),
+    }
+}
+
+/// Parse the count after `flag`, if given; a missing or unparseable value is an
+/// error rather than leaving the flag off
+fn parse_count(
+    args: &[String],
+    flag: &str,
+    expected: &str,
+) -> Result<Option<usize>, Box<dyn Error>> {
+    let Some(i) = args.iter().position(|a| a == flag) else {
+        return Ok(None);
+    };
+    let value = args.get(i + 1).map_or("", String::as_str);
+    match value.parse::<usize>() {
+        Ok(count) => Ok(Some(count)),
+        Err(_) => Err(format!("Invalid {} '{}', expected {}", flag, value, expected).into()),
+    }
+}
+
+/// Parse "CUR=rate,..." exchange rates into `base`
+fn parse_rates(base: &str, spec: &str) -> Result<CurrencyRates, Box<dyn Error>> {
+    let mut rates = CurrencyRates::new(base);
+    for pair in spec.split(',').filter(|p| !p.trim().is_empty()) {
+        let parsed = pair
+            .split_once('=')
+            .and_then(|(currency, rate)| Some((currency.trim(), rate.trim().parse::<f64>().ok()?)));
+        match parsed {
+            Some((currency, rate)) if !currency.is_empty() && rate > 0.0 => {
+                rates = rates.with_rate(currency, rate);
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid --fx-rates '{}', expected CUR=rate,... with positive rates",
+                    spec
+                )
+                .into())
+            }
+        }
+    }
+    Ok(rates)
+}
+
+/// Built-in formulas with `overrides` (name to expression) applied on top
+fn formula_repository(overrides: &BTreeMap<String, String>) -> Box<dyn FormulaRepository> {
+    let base: Box<dyn FormulaRepository> = Box::new(InMemoryFormulaRepository::new());
+    if overrides.is_empty() {
+        return base;
+    }
+    Box::new(overrides.iter().fold(
+        LayeredFormulaRepository::new(base),
+        |layered, (name, expression)| layered.with_override(name, expression),
+    ))
+}
+
+/// Parse every "--formula-override name=expression"; expressions may contain commas,
+/// so each override is its own flag. Names are checked up front rather than
+/// failing every calculation
+fn parse_formula_overrides(args: &[String]) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let mut overrides = BTreeMap::new();
+    for pair in args.windows(2).filter(|w| w[0] == "--formula-override") {
+        match pair[1].split_once('=') {
+            Some((name, expression)) if !expression.trim().is_empty() => {
+                overrides.insert(name.trim().to_string(), expression.to_string());
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid --formula-override '{}', expected name=expression",
+                    pair[1]
+                )
+                .into())
+            }
+        }
+    }
+    formula_repository(&overrides).load_all()?;
+    Ok(overrides)
+}
+
+/// Parse "CATEGORY=amount,..." spending caps
+fn parse_caps(spec: &str) -> Result<BTreeMap<String, f64>, Box<dyn Error>> {
+    let mut caps = BTreeMap::new();
+    for pair in spec.split(',').filter(|p| !p.trim().is_empty()) {
+        let parsed = pair
+            .split_once('=')
+            .and_then(|(category, cap)| Some((category.trim(), cap.trim().parse::<f64>().ok()?)));
+        match parsed {
+            Some((category, cap)) if !category.is_empty() && cap.is_finite() && cap >= 0.0 => {
+                caps.insert(category.to_string(), cap);
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid --category-caps '{}', expected CATEGORY=amount,...",
+                    spec
+                )
+                .into())
+            }
+        }
+    }
+    Ok(caps)
+}
+
+/// Parse "Level=weight,..." safety level priority weights on top of the defaults
+fn parse_safety_weights(spec: &str) -> Result<SafetyWeights, Box<dyn Error>> {
+    let mut weights = SafetyWeights::default();
+    for pair in spec.split(',').filter(|p| !p.trim().is_empty()) {
+        let parsed = pair
+            .split_once('=')
+            .and_then(|(level, weight)| Some((level.trim(), weight.trim().parse::<f64>().ok()?)));
+        match parsed {
+            Some((level, weight)) if !level.is_empty() && weight.is_finite() && weight >= 0.0 => {
+                weights = weights.with_weight(level, weight);
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid --safety-weights '{}', expected Level=weight,... with non-negative weights",
+                    spec
+                )
+                .into())
+            }
+        }
+    }
+    Ok(weights)
+}
+
+/// Parse "from,to" strategy keys for `--compare-strategies`
+fn parse_strategy_pair(spec: &str) -> Result<(String, String), Box<dyn Error>> {
+    match spec.split_once(',') {
+        Some((from, to))
+            if STRATEGY_KEYS.contains(&from.trim()) && STRATEGY_KEYS.contains(&to.trim()) =>
+        {
+            Ok((from.trim().to_string(), to.trim().to_string()))
+        }
+        _ => Err(format!(
+            "Invalid --compare-strategies '{}', expected two of {} separated by a comma",
+            spec,
+            STRATEGY_KEYS.join(", ")
+        )
+        .into()),
+    }
+}
+
+/// Parse "Alias=Expected,..." CSV header aliases
+fn parse_header_aliases(spec: &str) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    let mut aliases = HashMap::new();
+    for pair in spec.split(',').filter(|p| !p.trim().is_empty()) {
+        match pair.split_once('=') {
+            Some((alias, header)) if !alias.trim().is_empty() && !header.trim().is_empty() => {
+                aliases.insert(alias.trim().to_string(), header.trim().to_string());
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid --header-aliases '{}', expected Alias=Header,...",
+                    spec
+                )
+                .into())
+            }
+        }
+    }
+    Ok(aliases)
+}
+
+/// Split a comma-separated list of safety level labels
+fn parse_labels(spec: &str) -> Vec<String> {
+    spec.split(',')
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .collect()
+}
+
+/// Parse a comma-separated list of non-negative per-period budgets
+fn parse_periods(spec: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+    let budgets: Vec<f64> = spec
+        .split(',')
+        .map(|p| p.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid --periods '{}', expected b1,b2,...", spec))?;
+
+    if budgets.iter().any(|b| *b < 0.0) {
+        return Err(format!("Invalid --periods '{}', budgets must be non-negative", spec).into());
+    }
+    Ok(budgets)
+}
+
+/// Print the totals of an optimization solution, listing selections when there are few
+/// Solutions are results, so they print even with `--quiet`
+fn print_solution(
+    title: &str,
+    solution: &OptimizationSolution,
+    results: &[RiskCalculationResult],
+    rank_by: Contribution,
+    min_utilization: f64,
+    opt_time: Option<Duration>,
+    money: &MoneyFormat,
+) {
+    print!(
+        "{}",
+        format_solution(title, solution, results, rank_by, opt_time, money)
+    );
+    if solution.was_fallback {
+        eprintln!(
+            "Warning: {}: the LP solver failed or timed out; showing the greedy portfolio instead",
+            title
+        );
+    }
+    if solution.fractional_selections > 0 {
+        eprintln!(
+            "Warning: {}: {} LP values were fractional and rounded; the portfolio may be suboptimal",
+            title, solution.fractional_selections
+        );
+    }
+    if let Some(utilization) = solution.budget_utilization {
+        if solution.is_underutilized(min_utilization) {
+            eprintln!(
+                "Warning: {} uses only {:.1}% of the budget (threshold {:.0}%); the candidate set may be too sparse",
+                title,
+                utilization * 100.0,
+                min_utilization * 100.0
+            );
+        }
+    }
+}
+
+/// The lines `print_solution` writes to stdout
+fn format_solution(
+    title: &str,
+    solution: &OptimizationSolution,
+    results: &[RiskCalculationResult],
+    rank_by: Contribution,
+    opt_time: Option<Duration>,
+    money: &MoneyFormat,
+) -> String {
+    let mut lines = vec![format!("\n--- {} ---", title)];
+    lines.push(format!(
+        "Selected {} alternatives",
+        solution.num_assets_optimized
+    ));
+    lines.push(format!(
+        "Total cost: {}",
+        money.currency(solution.total_cost)
+    ));
+    lines.push(format!(
+        "Total risk reduction: {}",
+        money.currency(solution.total_risk_reduction)
+    ));
+    lines.push(format!(
+        "Residual risk: {}",
+        money.currency(solution.residual_risk)
+    ));
+    lines.push(format!(
+        "Captured {:.1}% of achievable risk reduction ({})",
+        solution.capture_ratio * 100.0,
+        money.currency(solution.achievable_ceiling)
+    ));
+    lines.push(format!(
+        "Total priority score: {:.4}",
+        solution.total_priority_score
+    ));
+    if let Some(opt_time) = opt_time {
+        lines.push(format!(
+            "Optimization time: {:.2}ms",
+            opt_time.as_secs_f64() * 1000.0
+        ));
+    }
+    if let (Some(utilization), Some(slack)) = (solution.budget_utilization, solution.budget_slack) {
+        lines.push(match solution.overspend {
+            Some(overspend) if overspend > 0.0 => format!(
+                "Budget utilization: {:.1}% ({} over budget)",
+                utilization * 100.0,
+                money.currency(overspend)
+            ),
+            _ => format!(
+                "Budget utilization: {:.1}% ({} unspent)",
+                utilization * 100.0,
+                money.currency(slack)
+            ),
+        });
+    }
+
+    let ranked = solution.ranked_selections(results, rank_by);
+    if !ranked.is_empty() {
+        lines.push("\nTop selected alternatives:".to_string());
+        for result in ranked.iter().take(10) {
+            let contribution = match rank_by {
+                Contribution::RiskReduction => money.currency(result.risk_reduction),
+                Contribution::Priority => format!("priority {:.4}", result.effective_priority()),
+            };
+            lines.push(format!(
+                "  - {} ({}): {}",
+                result.asset.asset_id, result.asset.alternative_id, contribution
+            ));
+        }
+        if ranked.len() > 10 {
+            lines.push(format!("  ...and {} more", ranked.len() - 10));
+        }
+
+        lines.push("\nBy category:".to_string());
+        for category in domain::summarize_by_category(ranked) {
+            lines.push(format!(
+                "  {:<24} {:>4} assets {:>18} cost {:>20} risk reduction",
+                category.category,
+                category.assets,
+                money.currency(category.total_cost),
+                money.currency(category.total_risk_reduction)
+            ));
+        }
+    }
+
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Run one optimization and add how long it took to the optimize phase
+fn timed<T>(timings: &mut PhaseTimings, solve: impl FnOnce() -> T) -> (T, Duration) {
+    let start = std::time::Instant::now();
+    let outcome = solve();
+    let elapsed = start.elapsed();
+    timings.record(Phase::Optimize, elapsed);
+    (outcome, elapsed)
+}
+
+/// Name of an `--input` path in messages: stdin for "-"
+fn source_name(input: &str) -> &str {
+    if input == "-" {
+        "stdin"
+    } else {
+        input
+    }
+}
+
+/// Print how many alternatives the risk, priority and combined strategies agree on
+fn print_overlap(overlap: &SelectionOverlap) {
+    info!("\n--- Strategy Overlap ---");
+    println!("Selected by all three: {}", overlap.all_three);
+    println!(
+        "Selected by exactly two: {} (risk+priority {}, risk+combined {}, priority+combined {})",
+        overlap.exactly_two(),
+        overlap.first_second,
+        overlap.first_third,
+        overlap.second_third
+    );
+    println!(
+        "Selected by one only: {} (risk {}, priority {}, combined {})",
+        overlap.exactly_one(),
+        overlap.first_only,
+        overlap.second_only,
+        overlap.third_only
+    );
+}
+
+/// Print the alternatives and totals that change from strategy `from` to `to`
+fn print_diff(from: &str, to: &str, diff: &SolutionDiff, money: &MoneyFormat) {
+    info!("\n=== STRATEGY COMPARISON ({} -> {}) ===", from, to);
+    if diff.is_same_selection() {
+        println!("Both strategies select the same alternatives");
+    }
+    for added in &diff.added {
+        println!("  + {}", added);
+    }
+    for removed in &diff.removed {
+        println!("  - {}", removed);
+    }
+    let sign = |delta: f64| if delta < 0.0 { "-" } else { "+" };
+    println!(
+        "Cost: {}{}",
+        sign(diff.cost_delta),
+        money.currency(diff.cost_delta.abs())
+    );
+    println!(
+        "Risk reduction: {}{}",
+        sign(diff.risk_reduction_delta),
+        money.currency(diff.risk_reduction_delta.abs())
+    );
+    println!("Priority score: {:+.4}", diff.priority_score_delta);
+}
+
+/// Print the LP and greedy portfolios' totals side by side
+fn print_heuristic_comparison(comparison: &HeuristicComparison, money: &MoneyFormat) {
+    info!("\n=== LP VS. GREEDY (Maximize Risk Reduction) ===");
+    println!("{:<16} {:>20} {:>20}", "", "LP", "Greedy");
+    println!(
+        "{:<16} {:>20} {:>20}",
+        "Selected", comparison.lp_selected, comparison.greedy_selected
+    );
+    println!(
+        "{:<16} {:>20} {:>20}",
+        "Total cost",
+        money.currency(comparison.lp_cost),
+        money.currency(comparison.greedy_cost)
+    );
+    println!(
+        "{:<16} {:>20} {:>20}",
+        "Risk reduction",
+        money.currency(comparison.lp_risk_reduction),
+        money.currency(comparison.greedy_risk_reduction)
+    );
+    match comparison.improvement_percent() {
+        Some(percent) => println!("LP improvement over greedy: {:+.2}%", percent),
+        None => println!("LP improvement over greedy: n/a (greedy reduces no risk)"),
+    }
+}
+
+/// Print all results as a text table sorted by priority score (highest first),
+/// followed by a histogram of the priority scores
+fn print_report(results: &[RiskCalculationResult], money: &MoneyFormat) {
+    let mut sorted: Vec<&RiskCalculationResult> = results.iter().collect();
+    sorted.sort_by(|a, b| b.effective_priority().total_cmp(&a.effective_priority()));
+
+    println!(
+        "\n{:<24} {:<24} {:<10} {:>16} {:>18} {:>10} {:>10} {:>10}",
+        "Asset", "Alternative", "Safety", "Cost", "Risk Reduction", "ROI", "Priority", "Payback"
+    );
+    println!("{}", "-".repeat(129));
+    for result in sorted {
+        println!(
+            "{:<24} {:<24} {:<10} {:>16} {:>18} {:>10} {:>10.4} {:>10}",
+            result.asset.asset_id,
+            result.asset.alternative_id,
+            result.asset.safety_risk_level,
+            money.currency(result.asset.cost_usd),
+            money.currency(result.risk_reduction),
+            result
+                .roi
+                .map_or("N/A".to_string(), |roi| format!("{:.4}", roi)),
+            result.effective_priority(),
+            result
+                .payback_period
+                .map_or("N/A".to_string(), |months| format!("{:.1}", months))
+        );
+    }
+
+    let scores: Vec<f64> = results.iter().map(|r| r.effective_priority()).collect();
+    let buckets = histogram(&scores, HISTOGRAM_BUCKETS);
+    if buckets.is_empty() {
+        return;
+    }
+    let max_count = buckets
+        .iter()
+        .map(|(_, _, count)| *count)
+        .max()
+        .unwrap_or(0);
+    info!("\nPriority Score Distribution:");
+    for (start, end, count) in buckets {
+        let bar_len = (count * 40).div_ceil(max_count.max(1));
+        let line = format!(
+            "{:>10.4} - {:<10.4} {:>6} {}",
+            start,
+            end,
+            count,
+            "#".repeat(bar_len)
+        );
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Leaves raw mode, the alternate screen and mouse capture when dropped, so an
+/// error or panic in the TUI never leaves the terminal unusable
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(
+            io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            cursor::Show
+        );
+    }
+}
+
 fn run_ui(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     state: &mut AppState,
@@ -293,11 +2016,38 @@ fn run_ui(
 
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+                // Raw mode delivers Ctrl-C as a key press rather than a signal
+                if key.kind == KeyEventKind::Press
+                    && key.code == KeyCode::Char('c')
+                    && key.modifiers.contains(KeyModifiers::CONTROL)
+                {
+                    return Ok(());
+                }
+                if key.kind == KeyEventKind::Press && state.budget_input.is_some() {
+                    match key.code {
+                        KeyCode::Esc => state.cancel_budget_input(),
+                        KeyCode::Enter => state.submit_budget_input(),
+                        KeyCode::Backspace => state.pop_budget_char(),
+                        KeyCode::Char(ch) => state.push_budget_char(ch),
+                        _ => {}
+                    }
+                } else if key.kind == KeyEventKind::Press && state.search_mode {
+                    match key.code {
+                        KeyCode::Esc => state.clear_search(),
+                        KeyCode::Enter => state.finish_search(),
+                        KeyCode::Backspace => state.pop_search_char(),
+                        KeyCode::Char(ch) => state.push_search_char(ch),
+                        KeyCode::Down => state.select_next(),
+                        KeyCode::Up => state.select_previous(),
+                        _ => {}
+                    }
+                } else if key.kind == KeyEventKind::Press {
                     match key.code {
                         KeyCode::Char('q') => return Ok(()),
                         KeyCode::Down | KeyCode::Char('j') => state.select_next(),
                         KeyCode::Up | KeyCode::Char('k') => state.select_previous(),
+                        KeyCode::Home | KeyCode::Char('g') => state.select_first(),
+                        KeyCode::End | KeyCode::Char('G') => state.select_last(),
                         KeyCode::PageDown => {
                             let page_size = terminal.size()?.height.saturating_sub(6) as usize;
                             state.page_down(page_size);
@@ -307,6 +2057,15 @@ fn run_ui(
                             state.page_up(page_size);
                         }
                         KeyCode::Enter | KeyCode::Char(' ') => state.toggle_expand(),
+                        KeyCode::Char('/') => state.start_search(),
+                        KeyCode::Char('t') => state.toggle_summary(),
+                        KeyCode::Char('c') => state.toggle_comparison(),
+                        KeyCode::Char('h') => state.toggle_histogram(),
+                        KeyCode::Char('s') => state.toggle_cost_benefit_sort(),
+                        KeyCode::Char('f') => state.toggle_selected_only(),
+                        KeyCode::Char('b') => state.start_budget_input(),
+                        KeyCode::Char('e') => state.export_now(),
+                        KeyCode::Esc => state.clear_search(),
                         _ => {}
                     }
                 }
@@ -314,3 +2073,296 @@ fn run_ui(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str =
+        "Asset_ID,Alternative_ID,Cost_USD,PoF_Post_Action,CoF_Total_USD,Safety_Risk_Level\n";
+
+    /// Write `rows` under the CSV header to a temp file named after `name`
+    fn write_csv(name: &str, rows: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("capalloc_run_{}_{}.csv", name, std::process::id()));
+        std::fs::write(&path, format!("{}{}", HEADER, rows)).unwrap();
+        path
+    }
+
+    fn create_asset(asset_id: &str, alternative_id: &str, cost: f64, pof: f64) -> domain::Asset {
+        domain::Asset {
+            pof_post_action: pof,
+            ..domain::Asset::fixture(asset_id, alternative_id, cost)
+        }
+    }
+
+    fn config(flags: &[&str]) -> AppConfig {
+        let args: Vec<String> = ["capalloc", "-q"]
+            .iter()
+            .chain(flags)
+            .map(|f| f.to_string())
+            .collect();
+        AppConfig::from_args(&args).unwrap()
+    }
+
+    fn in_memory(assets: Vec<domain::Asset>) -> Box<dyn AssetRepository> {
+        Box::new(repository::InMemoryAssetRepository::new(assets))
+    }
+
+    #[test]
+    fn test_run_calculates_and_optimizes() {
+        let assets = vec![
+            create_asset("IT_SYSTEM_001", "Defer", 0.0, 0.25),
+            create_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0, 0.05),
+            create_asset("DATACENTER_002", "Defer", 0.0, 0.25),
+            create_asset("DATACENTER_002", "Full_Implementation", 120000.0, 0.01),
+        ];
+
+        let output = run(
+            &config(&["--budget", "100000", "--overspend-penalty", "2"]),
+            in_memory(assets.clone()),
+        )
+        .unwrap();
+
+        assert_eq!(output.asset_count, 4);
+        assert_eq!(output.results.len(), 4);
+        let keys: Vec<&str> = output.solutions.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, STRATEGY_KEYS);
+        let risk = output.solution("risk_reduction").unwrap();
+        assert!(risk.total_cost <= 100000.0);
+        assert!(risk
+            .selected_alternatives
+            .contains(&"IT_SYSTEM_001 (Pilot_Program)".to_string()));
+
+        // Without a budget only the calculation runs
+        let output = run(&config(&["--top", "2"]), in_memory(assets)).unwrap();
+        assert_eq!(output.results.len(), 2);
+        assert!(output.solutions.is_empty());
+    }
+
+    #[test]
+    fn test_quiet_run_still_prints_solution() {
+        let assets = vec![
+            create_asset("IT_SYSTEM_001", "Defer", 0.0, 0.25),
+            create_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0, 0.05),
+        ];
+        let config = config(&["--budget", "100000"]);
+        assert!(config.quiet);
+        QUIET.store(true, Ordering::Relaxed);
+        let output = run(&config, in_memory(assets)).unwrap();
+
+        let solution = output.solution("risk_reduction").unwrap();
+        let printed = format_solution(
+            "Strategy 1",
+            solution,
+            &output.results,
+            config.rank_by,
+            None,
+            &config.money,
+        );
+        assert!(printed.contains("--- Strategy 1 ---"), "{}", printed);
+        assert!(printed.contains("Selected 1 alternatives"), "{}", printed);
+        assert!(
+            printed.contains("IT_SYSTEM_001 (Pilot_Program)"),
+            "{}",
+            printed
+        );
+    }
+
+    #[test]
+    fn test_inputs_are_repeatable_and_comma_separated() {
+        assert_eq!(config(&[]).inputs, vec!["assets.csv"]);
+        assert_eq!(
+            config(&["--input", "a.csv, b.csv", "--input", "c.csv"]).inputs,
+            vec!["a.csv", "b.csv", "c.csv"]
+        );
+        let args: Vec<String> = ["capalloc", "--input", "-,-"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        assert!(AppConfig::from_args(&args).is_err());
+
+        let north = write_csv("north", "PUMP_001,Overhaul,20000,0.1,200000,Low\n");
+        let south = write_csv("south", "PUMP_001,Replace,50000,0.01,200000,Low\n");
+        let inputs = format!("{},{}", north.display(), south.display());
+        let merged = config(&["--input", &inputs])
+            .open_repository()
+            .and_then(|repository| repository.load_all());
+        std::fs::remove_file(&north).ok();
+        std::fs::remove_file(&south).ok();
+        assert_eq!(merged.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_unparseable_numeric_flags_are_rejected() {
+        for flags in [
+            &["--risk-weight", "0,6"][..],
+            &["--priority-weight", "high"],
+            &["--min-reduction", "1e6x"],
+            &["--top", "ten"],
+            &["--top"],
+            &["--pareto", "abc"],
+        ] {
+            let args: Vec<String> = ["capalloc"]
+                .iter()
+                .chain(flags)
+                .map(|a| a.to_string())
+                .collect();
+            let err = AppConfig::from_args(&args).err().expect("flag accepted");
+            assert!(err.to_string().contains(flags[0]), "{}", err);
+        }
+    }
+
+    #[cfg(not(feature = "xlsx"))]
+    #[test]
+    fn test_export_xlsx_needs_the_feature() {
+        let args: Vec<String> = ["capalloc", "--export-xlsx", "results.xlsx"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let err = AppConfig::from_args(&args).err().expect("flag accepted");
+        assert!(err.to_string().contains("--features xlsx"), "{}", err);
+    }
+
+    #[test]
+    fn test_json_rejects_flags_that_print_to_stdout() {
+        let parse = |flags: &[&str]| {
+            let args: Vec<String> = ["capalloc", "--benchmark", "--json"]
+                .iter()
+                .chain(flags)
+                .map(|a| a.to_string())
+                .collect();
+            AppConfig::from_args(&args)
+        };
+        assert!(parse(&[]).is_ok());
+        assert!(parse(&["--profile"]).is_ok());
+        for flags in [
+            &["--budget", "100000"][..],
+            &["--sweep", "0:100:50"],
+            &["--periods", "100,200"],
+            &["--min-reduction", "1000"],
+            &["--report"],
+            &["--load-results", "results.json"],
+        ] {
+            let err = parse(flags).err().expect("combination accepted");
+            assert!(err.to_string().contains(flags[0]), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_parse_sweep_always_includes_max() {
+        assert_eq!(parse_sweep("0:0.3:0.1").unwrap().last(), Some(&0.3));
+        assert_eq!(parse_sweep("0:0.3:0.1").unwrap().len(), 4);
+        assert_eq!(
+            parse_sweep("100:250:100").unwrap(),
+            vec![100.0, 200.0, 250.0]
+        );
+        assert_eq!(parse_sweep("5:5:1").unwrap(), vec![5.0]);
+        assert!(parse_sweep("10:0:1").is_err());
+    }
+
+    #[test]
+    fn test_formula_override_replaces_priority_score() {
+        let assets = vec![
+            create_asset("IT_SYSTEM_001", "Pilot_Program", 45000.0, 0.05),
+            create_asset("DATACENTER_002", "Full_Implementation", 120000.0, 0.01),
+        ];
+        let base = run(&config(&[]), in_memory(assets.clone())).unwrap();
+        let overridden = run(
+            &config(&["--formula-override", "priority_score=return 42"]),
+            in_memory(assets),
+        )
+        .unwrap();
+
+        for (before, after) in base.results.iter().zip(&overridden.results) {
+            assert_eq!(after.priority_score, 42.0);
+            assert_eq!(after.risk_reduction, before.risk_reduction);
+            assert_eq!(after.roi, before.roi);
+        }
+
+        for spec in ["priority_score", "priority_score=", "2fast=return 1"] {
+            let args: Vec<String> = ["capalloc", "--formula-override", spec]
+                .iter()
+                .map(|f| f.to_string())
+                .collect();
+            assert!(AppConfig::from_args(&args).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn test_run_empty_repository() {
+        let output = run(&config(&["--budget", "100000"]), in_memory(Vec::new())).unwrap();
+        assert_eq!(output.asset_count, 0);
+        assert!(output.results.is_empty());
+        assert!(output.solutions.is_empty());
+    }
+
+    fn run_with(path: &std::path::Path, flags: &[&str]) -> Result<i32, Box<dyn Error>> {
+        let mut args = vec![
+            "capalloc".to_string(),
+            "-q".to_string(),
+            "--input".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        args.extend(flags.iter().map(|f| f.to_string()));
+        run_cli(&args)
+    }
+
+    #[test]
+    fn test_run_cli_exit_codes() {
+        let valid = write_csv(
+            "valid",
+            "IT_SYSTEM_001,Defer,0,0.25,500000,High\n\
+             IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,Low\n",
+        );
+        let invalid = write_csv(
+            "invalid",
+            "IT_SYSTEM_001,Pilot_Program,45000,1.5,500000,Low\n",
+        );
+        let missing = std::env::temp_dir().join("capalloc_run_does_not_exist.csv");
+
+        let checked = run_with(&valid, &["--check"]);
+        let reported = run_with(&valid, &["--report", "--budget", "100000"]);
+        let rejected = run_with(&invalid, &["--check"]);
+        let not_loaded = run_with(&missing, &["--report"]);
+        let bad_flag = run_with(&valid, &["--report", "--money-dp", "11"]);
+        std::fs::remove_file(&valid).ok();
+        std::fs::remove_file(&invalid).ok();
+
+        assert_eq!(checked.unwrap(), EXIT_SUCCESS);
+        assert_eq!(reported.unwrap(), EXIT_SUCCESS);
+        assert_eq!(rejected.unwrap(), EXIT_VALIDATION_FAILED);
+        assert!(not_loaded.is_err());
+        assert!(bad_flag.is_err());
+    }
+
+    #[test]
+    fn test_load_results_does_not_open_inputs() {
+        let valid = write_csv(
+            "saved",
+            "IT_SYSTEM_001,Defer,0,0.25,500000,High\n\
+             IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,Low\n",
+        );
+        let saved =
+            std::env::temp_dir().join(format!("capalloc_run_saved_{}.json", std::process::id()));
+        let saved_arg = saved.to_string_lossy().to_string();
+        let missing = std::env::temp_dir().join("capalloc_run_saved_does_not_exist.csv");
+
+        let save = run_with(&valid, &["--report", "--save-results", &saved_arg]);
+        let reload = run_with(
+            &missing,
+            &[
+                "--report",
+                "--budget",
+                "100000",
+                "--load-results",
+                &saved_arg,
+            ],
+        );
+        std::fs::remove_file(&valid).ok();
+        std::fs::remove_file(&saved).ok();
+
+        assert_eq!(save.unwrap(), EXIT_SUCCESS);
+        assert_eq!(reload.unwrap(), EXIT_SUCCESS);
+    }
+}
@@ -1,25 +1,99 @@
 use crate::domain::Asset;
-use std::error::Error;
+use crate::error::CapAllocError;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+
+/// Columns an asset CSV may add to the required ones
+const OPTIONAL_COLUMNS: [&str; 2] = ["Currency", "Remaining_Life_Years"];
+
+/// Columns every asset CSV must have, in the documented order
+const REQUIRED_COLUMNS: [&str; 6] = [
+    "Asset_ID",
+    "Alternative_ID",
+    "Cost_USD",
+    "PoF_Post_Action",
+    "CoF_Total_USD",
+    "Safety_Risk_Level",
+];
 
 /// Repository for loading asset data
-pub trait AssetRepository {
-    fn load_all(&self) -> Result<Vec<Asset>, Box<dyn Error>>;
+pub trait AssetRepository: Send + Sync {
+    fn load_all(&self) -> Result<Vec<Asset>, CapAllocError>;
 }
 
 /// CSV-based implementation of AssetRepository
 pub struct CsvAssetRepository {
-    file_path: String,
+    source: CsvSource,
+    delimiter: u8,
+    header_aliases: HashMap<String, String>,
+}
+
+/// Where CSV data is read from
+enum CsvSource {
+    File(String),
+    /// Contents already read from a stream such as stdin
+    Bytes(Vec<u8>),
 }
 
 impl CsvAssetRepository {
     pub fn new(file_path: String) -> Self {
-        Self { file_path }
+        Self::with_source(CsvSource::File(file_path))
     }
-}
 
-impl AssetRepository for CsvAssetRepository {
-    fn load_all(&self) -> Result<Vec<Asset>, Box<dyn Error>> {
-        let mut rdr = csv::Reader::from_path(&self.file_path)?;
+    fn with_source(source: CsvSource) -> Self {
+        Self {
+            source,
+            delimiter: b',',
+            header_aliases: HashMap::new(),
+        }
+    }
+
+    /// Field delimiter, e.g. `b';'` for semicolon-separated exports (default `b','`)
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Map alternative header spellings to the expected ones, e.g. "Cost" -> "Cost_USD"
+    pub fn with_header_aliases(mut self, aliases: HashMap<String, String>) -> Self {
+        self.header_aliases = aliases;
+        self
+    }
+
+    /// Read all CSV data from `reader` up front, so it can be loaded like a file
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, CapAllocError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Ok(Self::with_source(CsvSource::Bytes(bytes)))
+    }
+
+    /// Repository for an input path, where "-" means stdin
+    pub fn open(input: &str) -> Result<Self, CapAllocError> {
+        if input == "-" {
+            Self::from_reader(std::io::stdin().lock())
+        } else {
+            Ok(Self::new(input.to_string()))
+        }
+    }
+
+    fn deserialize<R: Read>(&self, reader: R) -> Result<Vec<Asset>, CapAllocError> {
+        // Headers are trimmed here so the check below and serde see the same names
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .trim(csv::Trim::Headers)
+            .from_reader(reader);
+        if !self.header_aliases.is_empty() {
+            let headers: csv::StringRecord = rdr
+                .headers()?
+                .iter()
+                .map(|h| self.header_aliases.get(h).map_or(h, String::as_str))
+                .collect();
+            rdr.set_headers(headers);
+        }
+        // Completely empty input has no header to check and loads as no assets
+        if !rdr.headers()?.is_empty() {
+            check_headers(rdr.headers()?)?;
+        }
         let mut assets = Vec::new();
 
         for result in rdr.deserialize() {
@@ -30,3 +104,441 @@ impl AssetRepository for CsvAssetRepository {
         Ok(assets)
     }
 }
+
+/// Name the required columns the header lacks, pairing each with an unrecognized
+/// header that looks like a misspelling of it where there is one
+fn check_headers(headers: &csv::StringRecord) -> Result<(), CapAllocError> {
+    let present: Vec<&str> = headers.iter().collect();
+    let mut unknown: Vec<&str> = present
+        .iter()
+        .copied()
+        .filter(|h| !REQUIRED_COLUMNS.contains(h) && !OPTIONAL_COLUMNS.contains(h))
+        .collect();
+
+    let mut missing = Vec::new();
+    let mut misspelled = Vec::new();
+    for column in REQUIRED_COLUMNS {
+        if present.contains(&column) {
+            continue;
+        }
+        let closest = unknown
+            .iter()
+            .enumerate()
+            .map(|(i, header)| (i, header_distance(header, column)))
+            .filter(|(_, distance)| *distance <= 2)
+            .min_by_key(|(_, distance)| *distance);
+        match closest {
+            Some((i, _)) => {
+                misspelled.push(format!("'{}' (expected {})", unknown.remove(i), column))
+            }
+            None => missing.push(column),
+        }
+    }
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("missing {}", missing.join(", ")));
+    }
+    if !misspelled.is_empty() {
+        problems.push(format!("misspelled {}", misspelled.join(", ")));
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(CapAllocError::Validation(format!(
+            "Invalid CSV header: {}",
+            problems.join("; ")
+        )))
+    }
+}
+
+/// Edit distance between two headers, ignoring case, spaces, dashes and underscores
+fn header_distance(a: &str, b: &str) -> usize {
+    let normalize = |s: &str| -> Vec<char> {
+        s.chars()
+            .filter(|c| !matches!(c, '_' | '-' | ' '))
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+impl AssetRepository for CsvAssetRepository {
+    fn load_all(&self) -> Result<Vec<Asset>, CapAllocError> {
+        match &self.source {
+            CsvSource::File(path) => self.deserialize(std::fs::File::open(path)?),
+            CsvSource::Bytes(bytes) => self.deserialize(bytes.as_slice()),
+        }
+    }
+}
+
+/// In-memory implementation of AssetRepository, for tests and for embedding
+/// the optimizer where assets are already loaded
+pub struct InMemoryAssetRepository {
+    assets: Vec<Asset>,
+}
+
+impl InMemoryAssetRepository {
+    pub fn new(assets: Vec<Asset>) -> Self {
+        Self { assets }
+    }
+}
+
+impl AssetRepository for InMemoryAssetRepository {
+    fn load_all(&self) -> Result<Vec<Asset>, CapAllocError> {
+        Ok(self.assets.clone())
+    }
+}
+
+/// Asset ID found in more than one merged source
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetIdCollision {
+    pub asset_id: String,
+    /// Names of the sources containing the asset, in merge order
+    pub sources: Vec<String>,
+}
+
+/// Concatenation of several named repositories, such as one CSV per business unit
+#[derive(Default)]
+pub struct MergedAssetRepository {
+    sources: Vec<(String, Box<dyn AssetRepository>)>,
+}
+
+impl MergedAssetRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `repository`, named `name` in errors and collisions
+    pub fn with_source(mut self, name: &str, repository: Box<dyn AssetRepository>) -> Self {
+        self.sources.push((name.to_string(), repository));
+        self
+    }
+
+    /// Assets of every source in order, and the asset IDs appearing in more than
+    /// one source, sorted by ID. Colliding rows are all kept, so deduplication
+    /// and conflict warnings still see them
+    pub fn load_merged(&self) -> Result<(Vec<Asset>, Vec<AssetIdCollision>), CapAllocError> {
+        let mut assets = Vec::new();
+        // Source indices per asset ID, so a file merged twice still collides
+        let mut sources_by_id: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (index, (name, repository)) in self.sources.iter().enumerate() {
+            let loaded = repository.load_all().map_err(|e| e.with_context(name))?;
+            for asset in &loaded {
+                let sources = sources_by_id.entry(asset.asset_id.clone()).or_default();
+                if sources.last() != Some(&index) {
+                    sources.push(index);
+                }
+            }
+            assets.extend(loaded);
+        }
+
+        let collisions = sources_by_id
+            .into_iter()
+            .filter(|(_, sources)| sources.len() > 1)
+            .map(|(asset_id, sources)| AssetIdCollision {
+                asset_id,
+                sources: sources
+                    .into_iter()
+                    .map(|index| self.sources[index].0.clone())
+                    .collect(),
+            })
+            .collect();
+        Ok((assets, collisions))
+    }
+}
+
+impl AssetRepository for MergedAssetRepository {
+    fn load_all(&self) -> Result<Vec<Asset>, CapAllocError> {
+        Ok(self.load_merged()?.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_asset(id: &str, alternative: &str) -> Asset {
+        Asset::fixture(id, alternative, 45000.0)
+    }
+
+    #[test]
+    fn test_in_memory_repository_returns_assets_in_order() {
+        let repository = InMemoryAssetRepository::new(vec![
+            create_test_asset("IT_SYSTEM_001", "Pilot_Program"),
+            create_test_asset("DATACENTER_002", "Defer"),
+        ]);
+
+        let assets = repository.load_all().unwrap();
+
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].asset_id, "IT_SYSTEM_001");
+        assert_eq!(assets[1].alternative_id, "Defer");
+        // Loading again yields the same data
+        assert_eq!(repository.load_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_in_memory_repository_empty() {
+        let repository = InMemoryAssetRepository::new(Vec::new());
+        assert!(repository.load_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merged_repository_concatenates_and_reports_collisions() {
+        let header =
+            "Asset_ID,Alternative_ID,Cost_USD,PoF_Post_Action,CoF_Total_USD,Safety_Risk_Level\n";
+        let dir = std::env::temp_dir();
+        let north = dir.join(format!("capalloc_merge_north_{}.csv", std::process::id()));
+        let south = dir.join(format!("capalloc_merge_south_{}.csv", std::process::id()));
+        std::fs::write(
+            &north,
+            format!(
+                "{}IT_SYSTEM_001,Defer,0,0.25,500000,High\n\
+                 IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,High\n\
+                 PUMP_002,Overhaul,20000,0.1,200000,Low\n",
+                header
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            &south,
+            format!(
+                "{}IT_SYSTEM_001,Replace,90000,0.01,500000,High\n\
+                 VALVE_003,Replace,5000,0.02,80000,Medium\n",
+                header
+            ),
+        )
+        .unwrap();
+
+        let open = |path: &std::path::Path| -> Box<dyn AssetRepository> {
+            Box::new(CsvAssetRepository::new(path.to_string_lossy().to_string()))
+        };
+        let merged = MergedAssetRepository::new()
+            .with_source("north.csv", open(&north))
+            .with_source("south.csv", open(&south));
+        let loaded = merged.load_merged();
+        std::fs::remove_file(&north).ok();
+        std::fs::remove_file(&south).ok();
+
+        let (assets, collisions) = loaded.unwrap();
+        assert_eq!(assets.len(), 5);
+        assert_eq!(assets[3].alternative_id, "Replace");
+        assert_eq!(
+            collisions,
+            vec![AssetIdCollision {
+                asset_id: "IT_SYSTEM_001".to_string(),
+                sources: vec!["north.csv".to_string(), "south.csv".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merged_repository_names_failing_source() {
+        let merged = MergedAssetRepository::new()
+            .with_source(
+                "ok.csv",
+                Box::new(InMemoryAssetRepository::new(vec![create_test_asset(
+                    "IT_SYSTEM_001",
+                    "Defer",
+                )])),
+            )
+            .with_source(
+                "bad.csv",
+                Box::new(
+                    CsvAssetRepository::from_reader("Asset_ID,Cost_USD\nX,1\n".as_bytes()).unwrap(),
+                ),
+            );
+
+        let err = merged.load_all().unwrap_err();
+        assert!(err.to_string().starts_with("bad.csv: "), "{}", err);
+    }
+
+    #[test]
+    fn test_csv_from_reader() {
+        let csv =
+            "Asset_ID,Alternative_ID,Cost_USD,PoF_Post_Action,CoF_Total_USD,Safety_Risk_Level\n\
+                   IT_SYSTEM_001,Defer,0,0.25,500000,High\n\
+                   IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,Low\n";
+        let repository = CsvAssetRepository::from_reader(csv.as_bytes()).unwrap();
+
+        // Buffered input can be loaded more than once
+        for _ in 0..2 {
+            let assets = repository.load_all().unwrap();
+            assert_eq!(assets.len(), 2);
+            assert_eq!(assets[1].alternative_id, "Pilot_Program");
+            assert_eq!(assets[1].cost_usd, 45000.0);
+            assert_eq!(assets[1].safety_risk_level, "Low");
+        }
+
+        let truncated = CsvAssetRepository::from_reader(
+            "Asset_ID,Alternative_ID,Cost_USD\nIT_SYSTEM_001,Defer,0\n".as_bytes(),
+        )
+        .unwrap();
+        assert!(matches!(
+            truncated.load_all(),
+            Err(CapAllocError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_csv_missing_column_is_named() {
+        let csv = "Asset_ID,Alternative_ID,Cost_USD,PoF_Post_Action,CoF_Total_USD\n\
+                   IT_SYSTEM_001,Pilot_Program,45000,0.05,500000\n";
+        let repository = CsvAssetRepository::from_reader(csv.as_bytes()).unwrap();
+
+        let err = repository.load_all().unwrap_err();
+        assert!(matches!(err, CapAllocError::Validation(_)));
+        assert_eq!(
+            err.to_string(),
+            "Invalid CSV header: missing Safety_Risk_Level"
+        );
+    }
+
+    #[test]
+    fn test_csv_empty_input_has_no_assets() {
+        let repository = CsvAssetRepository::from_reader("".as_bytes()).unwrap();
+        assert!(repository.load_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_csv_padded_headers_load() {
+        let csv =
+            " Asset_ID,Alternative_ID,Cost_USD ,PoF_Post_Action,CoF_Total_USD,Safety_Risk_Level\n\
+                   IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,Low\n";
+        let repository = CsvAssetRepository::from_reader(csv.as_bytes()).unwrap();
+
+        let assets = repository.load_all().unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].asset_id, "IT_SYSTEM_001");
+        assert_eq!(assets[0].cost_usd, 45000.0);
+    }
+
+    #[test]
+    fn test_csv_misspelled_column_is_named() {
+        let csv = "Asset_ID,Alternative_ID,Cost_USDD,PoF_Post_Action,cof_total_usd,Notes\n\
+                   IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,x\n";
+        let repository = CsvAssetRepository::from_reader(csv.as_bytes()).unwrap();
+
+        assert_eq!(
+            repository.load_all().unwrap_err().to_string(),
+            "Invalid CSV header: missing Safety_Risk_Level; \
+             misspelled 'Cost_USDD' (expected Cost_USD), 'cof_total_usd' (expected CoF_Total_USD)"
+        );
+    }
+
+    #[test]
+    fn test_csv_semicolon_delimiter() {
+        let path = std::env::temp_dir().join(format!(
+            "capalloc_assets_semicolon_{}.csv",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "Asset_ID;Alternative_ID;Cost_USD;PoF_Post_Action;CoF_Total_USD;Safety_Risk_Level\n\
+             IT_SYSTEM_001;Pilot_Program;45000;0.05;500000;Low\n",
+        )
+        .unwrap();
+
+        let default = CsvAssetRepository::new(path.to_string_lossy().to_string()).load_all();
+        let semicolon = CsvAssetRepository::new(path.to_string_lossy().to_string())
+            .with_delimiter(b';')
+            .load_all();
+        std::fs::remove_file(&path).ok();
+
+        assert!(default.is_err());
+        let assets = semicolon.unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].cost_usd, 45000.0);
+        assert_eq!(assets[0].safety_risk_level, "Low");
+    }
+
+    #[test]
+    fn test_csv_header_aliases() {
+        let csv = "Asset_ID,Alternative,Cost,PoF_Post_Action,CoF_Total_USD,Safety_Risk_Level\n\
+                   IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,Low\n";
+        let aliases = HashMap::from([
+            ("Cost".to_string(), "Cost_USD".to_string()),
+            ("Alternative".to_string(), "Alternative_ID".to_string()),
+        ]);
+
+        let plain = CsvAssetRepository::from_reader(csv.as_bytes()).unwrap();
+        assert!(plain.load_all().is_err());
+
+        let aliased = CsvAssetRepository::from_reader(csv.as_bytes())
+            .unwrap()
+            .with_header_aliases(aliases);
+        let assets = aliased.load_all().unwrap();
+        assert_eq!(assets[0].alternative_id, "Pilot_Program");
+        assert_eq!(assets[0].cost_usd, 45000.0);
+    }
+
+    #[test]
+    fn test_missing_file_is_io_error() {
+        let repository = CsvAssetRepository::new("does/not/exist.csv".to_string());
+        assert!(matches!(repository.load_all(), Err(CapAllocError::Io(_))));
+    }
+
+    #[test]
+    fn test_csv_currency_column_is_optional() {
+        let dir = std::env::temp_dir();
+        let without = dir.join(format!("capalloc_assets_plain_{}.csv", std::process::id()));
+        let with = dir.join(format!(
+            "capalloc_assets_currency_{}.csv",
+            std::process::id()
+        ));
+        std::fs::write(
+            &without,
+            "Asset_ID,Alternative_ID,Cost_USD,PoF_Post_Action,CoF_Total_USD,Safety_Risk_Level\n\
+             IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,Low\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &with,
+            "Asset_ID,Alternative_ID,Cost_USD,PoF_Post_Action,CoF_Total_USD,Safety_Risk_Level,Currency\n\
+             IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,Low,BRL\n\
+             DATACENTER_002,Defer,0,0.25,300000,High,\n",
+        )
+        .unwrap();
+
+        let plain = CsvAssetRepository::new(without.to_string_lossy().to_string()).load_all();
+        let mixed = CsvAssetRepository::new(with.to_string_lossy().to_string()).load_all();
+        std::fs::remove_file(&without).ok();
+        std::fs::remove_file(&with).ok();
+
+        assert_eq!(plain.unwrap()[0].currency, None);
+        let mixed = mixed.unwrap();
+        assert_eq!(mixed[0].currency.as_deref(), Some("BRL"));
+        assert_eq!(mixed[1].currency, None);
+    }
+
+    #[test]
+    fn test_csv_remaining_life_column_is_optional() {
+        let path =
+            std::env::temp_dir().join(format!("capalloc_assets_life_{}.csv", std::process::id()));
+        std::fs::write(
+            &path,
+            "Asset_ID,Alternative_ID,Cost_USD,PoF_Post_Action,CoF_Total_USD,Safety_Risk_Level,Remaining_Life_Years\n\
+             IT_SYSTEM_001,Pilot_Program,45000,0.05,500000,Low,2.5\n\
+             DATACENTER_002,Defer,0,0.25,300000,High,\n",
+        )
+        .unwrap();
+
+        let assets = CsvAssetRepository::new(path.to_string_lossy().to_string()).load_all();
+        std::fs::remove_file(&path).ok();
+
+        let assets = assets.unwrap();
+        assert_eq!(assets[0].remaining_life_years, Some(2.5));
+        assert_eq!(assets[1].remaining_life_years, None);
+    }
+}
@@ -1,9 +1,10 @@
-use formcalc::Formula;
-use std::error::Error;
+use crate::error::CapAllocError;
+use formcalc::{Formula, FormulaT};
+use std::collections::BTreeMap;
 
 /// Repository abstraction for loading capital allocation formulas
 pub trait FormulaRepository: Send + Sync {
-    fn load_all(&self) -> Result<Vec<Formula>, Box<dyn Error>>;
+    fn load_all(&self) -> Result<Vec<Formula>, CapAllocError>;
 }
 
 /// In-memory formula repository that loads formulas as if from a data source
@@ -22,7 +23,7 @@ impl Default for InMemoryFormulaRepository {
 }
 
 impl FormulaRepository for InMemoryFormulaRepository {
-    fn load_all(&self) -> Result<Vec<Formula>, Box<dyn Error>> {
+    fn load_all(&self) -> Result<Vec<Formula>, CapAllocError> {
         Ok(vec![
             self.load_baseline_risk_formula(),
             self.load_safety_multiplier_formula(),
@@ -41,17 +42,84 @@ impl FormulaRepository for InMemoryFormulaRepository {
     }
 }
 
+/// Base formula set with some formulas replaced or added by name, e.g. a custom
+/// `priority_score` on top of the built-in formulas
+pub struct LayeredFormulaRepository {
+    base: Box<dyn FormulaRepository>,
+    overrides: BTreeMap<String, String>,
+}
+
+impl LayeredFormulaRepository {
+    pub fn new(base: Box<dyn FormulaRepository>) -> Self {
+        Self {
+            base,
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Use `expression` for the formula `name`, replacing a base formula of that
+    /// name or adding a new one
+    pub fn with_override(mut self, name: &str, expression: &str) -> Self {
+        self.overrides
+            .insert(name.to_string(), expression.to_string());
+        self
+    }
+}
+
+impl FormulaRepository for LayeredFormulaRepository {
+    /// Base formulas in order with overrides applied in place, then new formulas
+    /// by name. Override names must be identifiers (letters, digits, `_`, not
+    /// starting with a digit) so formulas can reference them
+    fn load_all(&self) -> Result<Vec<Formula>, CapAllocError> {
+        let invalid: Vec<&str> = self
+            .overrides
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !is_identifier(name))
+            .collect();
+        if !invalid.is_empty() {
+            return Err(CapAllocError::Validation(format!(
+                "Invalid formula override name: {}",
+                invalid.join(", ")
+            )));
+        }
+
+        let mut formulas = self.base.load_all()?;
+        for formula in formulas.iter_mut() {
+            if let Some(expression) = self.overrides.get(formula.name()) {
+                *formula = Formula::new(formula.name(), expression);
+            }
+        }
+        let added: Vec<Formula> = self
+            .overrides
+            .iter()
+            .filter(|(name, _)| !formulas.iter().any(|f| f.name() == name.as_str()))
+            .map(|(name, expression)| Formula::new(name, expression))
+            .collect();
+        formulas.extend(added);
+        Ok(formulas)
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 impl InMemoryFormulaRepository {
     fn load_baseline_risk_formula(&self) -> Formula {
         Formula::new(
             "baseline_risk",
             r#"
-            // Baseline risk assumes worst-case scenario (PoF = 1.0 for do-nothing)
+            // Baseline risk assumes the do-nothing PoF, baseline_pof (worst case 1.0)
             // Uses exponential scaling for high consequence assets
             if (cof_total > 1000000) then
-                return rnd(exp(0.5) * cof_total, 2)
+                return rnd(exp(0.5) * baseline_pof * cof_total, money_dp)
             else
-                return rnd(1.0 * cof_total, 2)
+                return rnd(baseline_pof * cof_total, money_dp)
             end
         "#,
         )
@@ -81,11 +149,11 @@ impl InMemoryFormulaRepository {
             // Calculate asset criticality using multiple factors
             // Combines PoF, CoF, and safety classification
             if (is_critical) then
-                return rnd(((pof_post_action * 10) + (cof_total / 500000)) * 1.5, 2)
+                return rnd(((pof_post_action * 10) + (cof_total / 500000)) * 1.5, rating_dp)
             else if (is_high_risk) then
-                return rnd(((pof_post_action * 10) + (cof_total / 500000)) * 1.25, 2)
+                return rnd(((pof_post_action * 10) + (cof_total / 500000)) * 1.25, rating_dp)
             else
-                return rnd((pof_post_action * 10) + (cof_total / 500000), 2)
+                return rnd((pof_post_action * 10) + (cof_total / 500000), rating_dp)
             end
         "#,
         )
@@ -97,7 +165,7 @@ impl InMemoryFormulaRepository {
             r#"
             // Models asset degradation over time
             // Higher PoF indicates more degradation
-            return rnd(1.0 - min(pof_post_action * 2, 0.95), 4)
+            return rnd(1.0 - min(pof_post_action * 2, 0.95), score_dp)
         "#,
         )
     }
@@ -108,7 +176,7 @@ impl InMemoryFormulaRepository {
             r#"
             // Risk = Probability × Consequence × Safety Multiplier × Degradation
             // Apply degradation factor (lower degradation = lower risk)
-            return rnd(pof_post_action * cof_total * get_output_from('safety_multiplier') * max(get_output_from('degradation_factor'), 0.5), 2)
+            return rnd(pof_post_action * cof_total * get_output_from('safety_multiplier') * max(get_output_from('degradation_factor'), 0.5), money_dp)
         "#,
         )
     }
@@ -119,7 +187,7 @@ impl InMemoryFormulaRepository {
             r#"
             // Risk reduction = baseline risk - post action risk
             // Ensure non-negative reduction
-            return rnd(max(get_output_from('baseline_risk') - get_output_from('post_action_risk'), 0), 2)
+            return rnd(max(get_output_from('baseline_risk') - get_output_from('post_action_risk'), 0), money_dp)
         "#,
         )
     }
@@ -131,11 +199,11 @@ impl InMemoryFormulaRepository {
             // Calculate implementation difficulty based on cost and asset type
             // Higher cost and critical assets = higher complexity
             if (is_critical) then
-                return rnd(min((cost / 100000) * 2.0, 10), 2)
+                return rnd(min((cost / 100000) * 2.0, 10), rating_dp)
             else if (is_high_risk) then
-                return rnd(min((cost / 100000) * 1.5, 10), 2)
+                return rnd(min((cost / 100000) * 1.5, 10), rating_dp)
             else
-                return rnd(min(cost / 100000, 10), 2)
+                return rnd(min(cost / 100000, 10), rating_dp)
             end
         "#,
         )
@@ -147,8 +215,8 @@ impl InMemoryFormulaRepository {
             r#"
             // Discount factor for time value of money
             // More complex projects take longer, reducing present value
-            // Present value discount: 1 / (1 + r)^n
-            return rnd(1.0 / (1.0 + 0.006666667) ^ ceil(get_output_from('implementation_complexity') * 2), 4)
+            // Present value discount: 1 / (1 + r)^n, r = monthly_rate
+            return rnd(1.0 / (1.0 + monthly_rate) ^ ceil(get_output_from('implementation_complexity') * 2), score_dp)
         "#,
         )
     }
@@ -158,7 +226,7 @@ impl InMemoryFormulaRepository {
             "adjusted_cost",
             r#"
             // Adjust cost for time value and complexity
-            return rnd(cost * (1 + get_output_from('implementation_complexity') * 0.05) * get_output_from('time_value_adjustment'), 2)
+            return rnd(cost * (1 + get_output_from('implementation_complexity') * 0.05) * get_output_from('time_value_adjustment'), money_dp)
         "#,
         )
     }
@@ -169,7 +237,7 @@ impl InMemoryFormulaRepository {
             r#"
             // ROI = Risk Reduction / Adjusted Cost
             if (get_output_from('adjusted_cost') > 0) then
-                return rnd(get_output_from('risk_reduction') / get_output_from('adjusted_cost'), 4)
+                return rnd(get_output_from('risk_reduction') / get_output_from('adjusted_cost'), score_dp)
             else
                 return 999.9999
             end
@@ -184,7 +252,7 @@ impl InMemoryFormulaRepository {
             // Normalized cost effectiveness score (0-100)
             // Considers both ROI and criticality
             // Weighted combination: 70% ROI, 30% criticality
-            return rnd(min((min(get_output_from('roi'), 20) * 3.5) + (min(get_output_from('criticality_score'), 10) * 3), 100), 2)
+            return rnd(min((min(get_output_from('roi'), 20) * 3.5) + (min(get_output_from('criticality_score'), 10) * 3), 100), rating_dp)
         "#,
         )
     }
@@ -194,11 +262,13 @@ impl InMemoryFormulaRepository {
             "priority_score",
             r#"
             // Overall priority score combining multiple factors
-            // Uses weighted formula with exponential scaling for critical assets
+            // Uses weighted formula with exponential scaling for critical assets,
+            // then applies the configured weight for the asset's safety level and
+            // an urgency boost of up to 1.5x as remaining life falls below 10 years
             if (is_critical) then
-                return rnd(((get_output_from('risk_reduction') / 1000000) * 0.4 + (min(get_output_from('roi'), 10) / 10) * 0.35 + (get_output_from('criticality_score') / 10) * 0.25) * 1.3, 4)
+                return rnd(((get_output_from('risk_reduction') / 1000000) * 0.4 + (min(get_output_from('roi'), 10) / 10) * 0.35 + (get_output_from('criticality_score') / 10) * 0.25) * 1.3 * safety_weight * (1 + 0.5 * max(1 - remaining_life_years / 10, 0)), score_dp)
             else
-                return rnd((get_output_from('risk_reduction') / 1000000) * 0.4 + (min(get_output_from('roi'), 10) / 10) * 0.35 + (get_output_from('criticality_score') / 10) * 0.25, 4)
+                return rnd(((get_output_from('risk_reduction') / 1000000) * 0.4 + (min(get_output_from('roi'), 10) / 10) * 0.35 + (get_output_from('criticality_score') / 10) * 0.25) * safety_weight * (1 + 0.5 * max(1 - remaining_life_years / 10, 0)), score_dp)
             end
         "#,
         )
@@ -208,10 +278,10 @@ impl InMemoryFormulaRepository {
         Formula::new(
             "payback_period",
             r#"
-            // Estimated payback period in months
+            // Estimated payback period in months, one place coarser than ratings
             // Based on risk reduction as annual savings
             if (get_output_from('risk_reduction') > 0) then
-                return rnd((get_output_from('adjusted_cost') / get_output_from('risk_reduction')) * 12, 1)
+                return rnd((get_output_from('adjusted_cost') / get_output_from('risk_reduction')) * 12, max(rating_dp - 1, 0))
             else
                 return 999.9
             end
@@ -223,7 +293,6 @@ impl InMemoryFormulaRepository {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use formcalc::FormulaT;
 
     #[test]
     fn test_load_all_formulas() {
@@ -247,4 +316,60 @@ mod tests {
         assert!(names.contains(&"criticality_score"));
         assert!(names.contains(&"priority_score"));
     }
+
+    #[test]
+    fn test_layered_overrides_priority_score() {
+        let repo = LayeredFormulaRepository::new(Box::new(InMemoryFormulaRepository::new()))
+            .with_override(
+                "priority_score",
+                "return rnd(get_output_from('roi') * 2, 4)",
+            )
+            .with_override(
+                "risk_per_cost",
+                "return get_output_from('risk_reduction') / cost",
+            );
+        let base = InMemoryFormulaRepository::new().load_all().unwrap();
+        let layered = repo.load_all().unwrap();
+
+        assert_eq!(layered.len(), base.len() + 1);
+        assert_eq!(layered.last().unwrap().name(), "risk_per_cost");
+        // Overrides keep their position, so dependency order is unchanged
+        for (before, after) in base.iter().zip(&layered) {
+            assert_eq!(before.name(), after.name());
+        }
+
+        let asset = crate::domain::Asset::fixture("IT_SYSTEM_001", "Pilot_Program", 45000.0);
+        let service = |repo: Box<dyn FormulaRepository>| {
+            crate::services::RiskCalculationService::new(repo)
+                .calculate(&asset)
+                .unwrap()
+        };
+        let default = service(Box::<InMemoryFormulaRepository>::default());
+        let overridden = service(Box::new(repo));
+
+        let roi = overridden.roi.unwrap();
+        assert!((overridden.priority_score - roi * 2.0).abs() < 1e-9);
+        assert_ne!(overridden.priority_score, default.priority_score);
+        assert_eq!(overridden.roi, default.roi);
+        assert_eq!(overridden.risk_reduction, default.risk_reduction);
+        assert_eq!(overridden.cost_effectiveness, default.cost_effectiveness);
+    }
+
+    #[test]
+    fn test_layered_rejects_invalid_override_names() {
+        for name in ["", "priority score", "2fast", "roi-v2"] {
+            let repo = LayeredFormulaRepository::new(Box::new(InMemoryFormulaRepository::new()))
+                .with_override(name, "return 1");
+            match repo.load_all() {
+                Err(CapAllocError::Validation(message)) => {
+                    assert!(
+                        message.contains("Invalid formula override name"),
+                        "{}",
+                        message
+                    )
+                }
+                other => panic!("{:?} accepted: {:?}", name, other.map(|f| f.len())),
+            }
+        }
+    }
 }
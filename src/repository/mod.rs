@@ -1,5 +1,21 @@
 mod asset_repository;
 mod formula_repository;
+mod frontier_exporter;
+mod result_exporter;
+mod result_store;
+mod solution_exporter;
+#[cfg(feature = "xlsx")]
+mod xlsx_exporter;
 
-pub use asset_repository::{AssetRepository, CsvAssetRepository};
-pub use formula_repository::{FormulaRepository, InMemoryFormulaRepository};
+pub use asset_repository::{
+    AssetRepository, CsvAssetRepository, InMemoryAssetRepository, MergedAssetRepository,
+};
+pub use formula_repository::{
+    FormulaRepository, InMemoryFormulaRepository, LayeredFormulaRepository,
+};
+pub use frontier_exporter::CsvFrontierExporter;
+pub use result_exporter::CsvResultExporter;
+pub use result_store::JsonResultStore;
+pub use solution_exporter::JsonSolutionExporter;
+#[cfg(feature = "xlsx")]
+pub use xlsx_exporter::XlsxResultExporter;
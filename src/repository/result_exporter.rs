@@ -0,0 +1,110 @@
+use crate::domain::RiskCalculationResult;
+use crate::error::CapAllocError;
+use std::collections::HashSet;
+
+/// CSV writer for exporting calculated risk metrics
+pub struct CsvResultExporter {
+    file_path: String,
+    /// Strategy name and its selected "Asset_ID (Alternative_ID)" keys
+    selections: Vec<(String, HashSet<String>)>,
+}
+
+impl CsvResultExporter {
+    pub fn new(file_path: String) -> Self {
+        Self {
+            file_path,
+            selections: Vec::new(),
+        }
+    }
+
+    /// Append a `selected_by_<strategy>` column marking the alternatives in `selected`
+    pub fn with_selection(mut self, strategy: &str, selected: HashSet<String>) -> Self {
+        self.selections.push((strategy.to_string(), selected));
+        self
+    }
+
+    /// Write one row per calculation result, preceded by a header row
+    pub fn export(&self, results: &[RiskCalculationResult]) -> Result<(), CapAllocError> {
+        let mut writer = csv::Writer::from_path(&self.file_path)?;
+
+        let mut header: Vec<String> = RiskCalculationResult::ROW_LABELS
+            .iter()
+            .map(|label| label.to_string())
+            .collect();
+        header.extend(
+            self.selections
+                .iter()
+                .map(|(strategy, _)| format!("selected_by_{}", strategy)),
+        );
+        writer.write_record(&header)?;
+        for result in results {
+            let key = result.selection_key();
+            let mut row: Vec<String> = result
+                .to_row()
+                .into_iter()
+                .map(|(_, value)| value)
+                .collect();
+            row.extend(
+                self.selections
+                    .iter()
+                    .map(|(_, selected)| selected.contains(&key).to_string()),
+            );
+            writer.write_record(&row)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Asset;
+
+    fn create_test_result(asset_id: &str, alternative: &str) -> RiskCalculationResult {
+        RiskCalculationResult::new(
+            Asset::fixture(asset_id, alternative, 45000.0),
+            500000.0,
+            25000.0,
+            475000.0,
+            Some(10.5),
+            1.0,
+            0.75,
+            85.0,
+            Some(1.2),
+            0.5,
+        )
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "capalloc_export_results_{}.csv",
+            std::process::id()
+        ));
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Pilot_Program"),
+            create_test_result("DATACENTER_002", "Full_Implementation"),
+        ];
+
+        let exporter = CsvResultExporter::new(path.to_string_lossy().to_string());
+        exporter.export(&results).unwrap();
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let headers = reader.headers().unwrap().clone();
+        let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(headers.len(), 13);
+        assert_eq!(&headers[0], "asset_id");
+        assert_eq!(&headers[10], "payback_period");
+        assert_eq!(&headers[11], "cost_benefit_ratio");
+        assert_eq!(&headers[12], "normalized_priority_score");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[1][0], "DATACENTER_002");
+        assert_eq!(&rows[1][5], "475000");
+        assert_eq!(rows[1][11].parse::<f64>().unwrap(), 475000.0 / 45000.0);
+        assert_eq!(&rows[1][12], "");
+    }
+}
@@ -0,0 +1,94 @@
+use crate::domain::RiskCalculationResult;
+use crate::error::CapAllocError;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+/// JSON file of calculated results, so later runs can skip the calculation
+pub struct JsonResultStore {
+    file_path: String,
+}
+
+impl JsonResultStore {
+    pub fn new(file_path: String) -> Self {
+        Self { file_path }
+    }
+
+    /// Write results as a JSON array
+    pub fn save(&self, results: &[RiskCalculationResult]) -> Result<(), CapAllocError> {
+        let writer = BufWriter::new(File::create(&self.file_path)?);
+        serde_json::to_writer(writer, results)?;
+        Ok(())
+    }
+
+    /// Read results written by `save`; a file without results is an error
+    pub fn load(&self) -> Result<Vec<RiskCalculationResult>, CapAllocError> {
+        let reader = BufReader::new(File::open(&self.file_path)?);
+        let results: Vec<RiskCalculationResult> = serde_json::from_reader(reader)?;
+        if results.is_empty() {
+            return Err(CapAllocError::Validation(format!(
+                "{} contains no saved results",
+                self.file_path
+            )));
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Asset;
+
+    fn create_test_result(alternative: &str, roi: Option<f64>) -> RiskCalculationResult {
+        let asset = Asset {
+            currency: Some("USD".to_string()),
+            ..Asset::fixture("IT_SYSTEM_001", alternative, 45000.0)
+        };
+        RiskCalculationResult::new(
+            asset, 500000.0, 25000.0, 475000.0, roi, 1.5, 0.8123, 62.5, None, 0.123,
+        )
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!(
+                "capalloc_results_{}_{}.json",
+                name,
+                std::process::id()
+            ))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = temp_path("round_trip");
+        let results = vec![
+            create_test_result("Pilot_Program", Some(10.5556)),
+            create_test_result("Defer", None),
+        ];
+        let store = JsonResultStore::new(path.clone());
+
+        store.save(&results).unwrap();
+        let loaded = store.load();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.unwrap(), results);
+    }
+
+    #[test]
+    fn test_load_empty_results_errors() {
+        let path = temp_path("empty");
+        let store = JsonResultStore::new(path.clone());
+
+        store.save(&[]).unwrap();
+        let loaded = store.load();
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(loaded, Err(CapAllocError::Validation(_))));
+        assert!(matches!(
+            JsonResultStore::new(temp_path("missing")).load(),
+            Err(CapAllocError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::CapAllocError;
+use crate::services::OptimizationSolution;
+use std::fs::File;
+use std::io::BufWriter;
+
+/// JSON writer for exporting optimization solutions
+pub struct JsonSolutionExporter {
+    file_path: String,
+}
+
+impl JsonSolutionExporter {
+    pub fn new(file_path: String) -> Self {
+        Self { file_path }
+    }
+
+    /// Write solutions as JSON
+    /// A single solution is written as-is; multiple solutions are keyed by strategy name
+    pub fn export(&self, solutions: &[(&str, OptimizationSolution)]) -> Result<(), CapAllocError> {
+        let writer = BufWriter::new(File::create(&self.file_path)?);
+
+        match solutions {
+            [(_, solution)] => serde_json::to_writer_pretty(writer, solution)?,
+            _ => {
+                let keyed: serde_json::Map<String, serde_json::Value> = solutions
+                    .iter()
+                    .map(|(name, solution)| Ok((name.to_string(), serde_json::to_value(solution)?)))
+                    .collect::<Result<_, serde_json::Error>>()?;
+                serde_json::to_writer_pretty(writer, &keyed)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_solution(cost: f64) -> OptimizationSolution {
+        OptimizationSolution {
+            selected_alternatives: vec!["IT_SYSTEM_001 (Pilot_Program)".to_string()],
+            total_cost: cost,
+            total_risk_reduction: 50000.0,
+            total_priority_score: 0.75,
+            num_assets_optimized: 1,
+            achievable_ceiling: 100000.0,
+            capture_ratio: 0.5,
+            budget_slack: None,
+            budget_utilization: None,
+            was_fallback: false,
+            overspend: None,
+            fractional_selections: 0,
+            residual_risk: 0.0,
+        }
+    }
+
+    fn export_to_string(solutions: &[(&str, OptimizationSolution)], name: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "capalloc_export_solution_{}_{}.json",
+            name,
+            std::process::id()
+        ));
+        JsonSolutionExporter::new(path.to_string_lossy().to_string())
+            .export(solutions)
+            .unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        json
+    }
+
+    #[test]
+    fn test_export_single_solution() {
+        let json = export_to_string(
+            &[("risk_reduction", create_test_solution(10000.0))],
+            "single",
+        );
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["total_cost"], 10000.0);
+        assert_eq!(
+            value["selected_alternatives"][0],
+            "IT_SYSTEM_001 (Pilot_Program)"
+        );
+    }
+
+    #[test]
+    fn test_export_multiple_solutions_keyed_by_strategy() {
+        let json = export_to_string(
+            &[
+                ("risk_reduction", create_test_solution(10000.0)),
+                ("priority", create_test_solution(20000.0)),
+            ],
+            "multiple",
+        );
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["risk_reduction"]["total_cost"], 10000.0);
+        assert_eq!(value["priority"]["total_cost"], 20000.0);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::domain::{RiskCalculationResult, RiskLevelConfig, RowKind};
+use crate::error::CapAllocError;
+use rust_xlsxwriter::{Color, Format, Workbook};
+
+/// Excel writer for exporting calculated risk metrics with formatting
+pub struct XlsxResultExporter {
+    file_path: String,
+    risk_levels: RiskLevelConfig,
+}
+
+impl XlsxResultExporter {
+    pub fn new(file_path: String) -> Self {
+        Self {
+            file_path,
+            risk_levels: RiskLevelConfig::default(),
+        }
+    }
+
+    /// Set which safety level labels are colored as high risk and critical
+    pub fn with_risk_levels(mut self, risk_levels: RiskLevelConfig) -> Self {
+        self.risk_levels = risk_levels;
+        self
+    }
+
+    /// Write a header row and one row per result in `to_row` column order, with
+    /// the safety level after the IDs colored by severity and each number
+    /// formatted by its `RowKind`; undefined values are written as N/A
+    pub fn export(&self, results: &[RiskCalculationResult]) -> Result<(), CapAllocError> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+
+        let header = Format::new()
+            .set_bold()
+            .set_background_color(Color::RGB(0xD9D9D9));
+        let money = Format::new().set_num_format("$#,##0.00");
+        let ratio = Format::new().set_num_format("0.0000");
+        let months = Format::new().set_num_format("0.0");
+        let plain = Format::new();
+
+        // The safety level sits after the two ID columns
+        const SAFETY_COL: u16 = 2;
+        let col = |field: usize| field as u16 + u16::from(field as u16 >= SAFETY_COL);
+
+        worksheet.write_string_with_format(0, SAFETY_COL, "Safety Level", &header)?;
+        for (field, label) in RiskCalculationResult::ROW_LABELS.into_iter().enumerate() {
+            let (title, _) = RiskCalculationResult::row_column(label);
+            worksheet.write_string_with_format(0, col(field), title, &header)?;
+        }
+        for c in 0..=RiskCalculationResult::ROW_LABELS.len() as u16 {
+            worksheet.set_column_width(c, 18)?;
+        }
+
+        for (i, result) in results.iter().enumerate() {
+            let row = i as u32 + 1;
+            let safety =
+                Format::new().set_background_color(safety_color(result, &self.risk_levels));
+            worksheet.write_string_with_format(
+                row,
+                SAFETY_COL,
+                &result.asset.safety_risk_level,
+                &safety,
+            )?;
+
+            for (field, (label, value)) in result.to_row().into_iter().enumerate() {
+                let format = match RiskCalculationResult::row_column(label).1 {
+                    RowKind::Text => None,
+                    RowKind::Money => Some(&money),
+                    RowKind::Score => Some(&ratio),
+                    RowKind::Rating => Some(&plain),
+                    RowKind::Months => Some(&months),
+                };
+                match (format, value.parse::<f64>()) {
+                    (Some(format), Ok(number)) => {
+                        worksheet.write_number_with_format(row, col(field), number, format)?
+                    }
+                    _ if value.is_empty() => worksheet.write_string(row, col(field), "N/A")?,
+                    _ => worksheet.write_string(row, col(field), &value)?,
+                };
+            }
+        }
+
+        workbook.save(&self.file_path)?;
+        Ok(())
+    }
+}
+
+/// Background color for the safety level cell, matching the TUI palette
+fn safety_color(result: &RiskCalculationResult, levels: &RiskLevelConfig) -> Color {
+    if result.asset.is_critical(levels) {
+        Color::RGB(0xF4CCCC)
+    } else if result.asset.is_high_risk(levels) {
+        Color::RGB(0xFFF2CC)
+    } else {
+        Color::RGB(0xD9EAD3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Asset;
+
+    fn create_test_result(asset_id: &str, safety_level: &str) -> RiskCalculationResult {
+        RiskCalculationResult::new(
+            Asset {
+                safety_risk_level: safety_level.to_string(),
+                ..Asset::fixture(asset_id, "Pilot_Program", 45000.0)
+            },
+            500000.0,
+            25000.0,
+            475000.0,
+            Some(10.5),
+            1.0,
+            0.75,
+            85.0,
+            Some(1.2),
+            0.5,
+        )
+    }
+
+    #[test]
+    fn test_export_creates_workbook() {
+        let path = std::env::temp_dir().join(format!(
+            "capalloc_export_results_{}.xlsx",
+            std::process::id()
+        ));
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Low"),
+            create_test_result("DATACENTER_002", "Critical"),
+        ];
+
+        XlsxResultExporter::new(path.to_string_lossy().to_string())
+            .export(&results)
+            .unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // An .xlsx file is a zip archive containing the worksheet part
+        assert!(!bytes.is_empty());
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+        assert!(bytes
+            .windows(b"xl/worksheets/sheet1.xml".len())
+            .any(|w| w == b"xl/worksheets/sheet1.xml"));
+    }
+}
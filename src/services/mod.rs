@@ -1,5 +1,11 @@
 mod optimizer;
 mod risk_calculator;
 
-pub use optimizer::{OptimizationSolution, PortfolioOptimizer};
-pub use risk_calculator::RiskCalculationService;
+pub use optimizer::{
+    selection_overlap, Contribution, HeuristicComparison, OptimizationSolution, PortfolioOptimizer,
+    SelectionOverlap, SolutionDiff,
+};
+pub use risk_calculator::{
+    RiskCalculationService, DEFAULT_BASELINE_POF, DEFAULT_DISCOUNT_RATE, DEFAULT_MONEY_DP,
+    DEFAULT_RATING_DP, DEFAULT_SCORE_DP,
+};
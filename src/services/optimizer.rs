@@ -1,24 +1,533 @@
-use crate::domain::RiskCalculationResult;
-use minilp::{ComparisonOp, OptimizationDirection, Problem, Variable};
-use std::collections::HashMap;
+use crate::domain::{asset_category, RiskCalculationResult};
+use crate::error::CapAllocError;
+use crate::format::MoneyFormat;
+use minilp::{ComparisonOp, OptimizationDirection, Problem, Solution, Variable};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Objective coefficients are rescaled so the largest magnitude is this big, which
+/// gives the tie-break bonus the same weight whatever the objective's units
+const OBJECTIVE_SCALE: f64 = 1e6;
+
+/// Largest tie-break bonus relative to the largest rescaled objective coefficient.
+/// Adjacent ranks then differ by `OBJECTIVE_SCALE * TIE_BREAK_EPSILON / count`,
+/// well above minilp's 1e-8 optimality tolerance for any realistic count, while
+/// real differences smaller than this fraction may be decided by rank
+const TIE_BREAK_EPSILON: f64 = 1e-7;
+
+/// Factor rescaling objective coefficients whose largest magnitude is `largest`
+/// to `OBJECTIVE_SCALE`; 1.0 when every coefficient is zero
+fn objective_scale(largest: f64) -> f64 {
+    if largest > 0.0 {
+        OBJECTIVE_SCALE / largest
+    } else {
+        1.0
+    }
+}
+
+/// Tie-break bonus for the variable at `position` (1 to `count`) of a rescaled objective
+fn tie_break(position: f64, count: f64) -> f64 {
+    OBJECTIVE_SCALE * TIE_BREAK_EPSILON * position / count
+}
+
+/// LP values within this distance of 0 or 1 are solver noise and snap to that integer
+const INTEGRALITY_EPSILON: f64 = 1e-6;
+
+/// LP values strictly above this select the alternative, provided it still fits the
+/// budget. A binary variable's LP relaxation value is closest to 1 above 0.5, so
+/// this rounds to the nearest integer, with an exact 0.5 rounding down
+const SELECTION_THRESHOLD: f64 = 0.5;
+
+/// Read an LP value as (selected, fractional): values within `INTEGRALITY_EPSILON`
+/// of 0 or 1 snap to it, anything in between is rounded at `SELECTION_THRESHOLD`
+/// and flagged, since rounding it may cost optimality or break a constraint
+fn round_selection(value: f64) -> (bool, bool) {
+    if value <= INTEGRALITY_EPSILON {
+        (false, false)
+    } else if value >= 1.0 - INTEGRALITY_EPSILON {
+        (true, false)
+    } else {
+        (value > SELECTION_THRESHOLD, true)
+    }
+}
 
 /// Result of portfolio optimization
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct OptimizationSolution {
-    pub selected_alternatives: Vec<String>, // Asset IDs of selected alternatives
+    pub selected_alternatives: Vec<String>, // See RiskCalculationResult::selection_key
     pub total_cost: f64,
     pub total_risk_reduction: f64,
     pub total_priority_score: f64,
     pub num_assets_optimized: usize,
+    /// Sum of the best risk reduction per asset, ignoring the budget
+    pub achievable_ceiling: f64,
+    /// Fraction of the achievable ceiling captured by this solution
+    pub capture_ratio: f64,
+    /// Budget left unspent; None when the solution was not budget-constrained
+    pub budget_slack: Option<f64>,
+    /// Fraction of the budget spent (1.0 for a zero budget)
+    pub budget_utilization: Option<f64>,
+    /// The LP solver failed or timed out and this is the greedy heuristic's portfolio instead
+    pub was_fallback: bool,
+    /// Amount spent beyond the budget; None unless the budget was a soft constraint
+    pub overspend: Option<f64>,
+    /// LP values that were genuinely fractional and had to be rounded; a nonzero
+    /// count means the portfolio may be suboptimal
+    pub fractional_selections: usize,
+    /// Exposure left after the spend: post-action risk of the selected alternatives
+    /// plus baseline risk of every asset with none selected
+    pub residual_risk: f64,
+}
+
+impl OptimizationSolution {
+    fn new(
+        selected_alternatives: Vec<String>,
+        total_cost: f64,
+        total_risk_reduction: f64,
+        total_priority_score: f64,
+        achievable_ceiling: f64,
+    ) -> Self {
+        let capture_ratio = if achievable_ceiling > 0.0 {
+            total_risk_reduction / achievable_ceiling
+        } else {
+            0.0
+        };
+
+        Self {
+            num_assets_optimized: selected_alternatives.len(),
+            selected_alternatives,
+            total_cost,
+            total_risk_reduction,
+            total_priority_score,
+            achievable_ceiling,
+            capture_ratio,
+            budget_slack: None,
+            budget_utilization: None,
+            was_fallback: false,
+            overspend: None,
+            fractional_selections: 0,
+            residual_risk: 0.0,
+        }
+    }
+
+    /// Record how many LP values were rounded from a fractional value
+    fn with_fractional_selections(mut self, count: usize) -> Self {
+        self.fractional_selections = count;
+        self
+    }
+
+    /// Record the exposure this selection leaves across every asset in `results`
+    fn with_residual_risk(mut self, results: &[RiskCalculationResult]) -> Self {
+        self.residual_risk =
+            PortfolioOptimizer::residual_risk(results, &self.selected_alternatives);
+        self
+    }
+
+    /// Mark this solution as a greedy stand-in for a failed LP solve
+    fn into_fallback(mut self) -> Self {
+        self.was_fallback = true;
+        self
+    }
+
+    /// Record how much of `budget` this solution leaves unspent
+    fn with_budget(mut self, budget: f64) -> Self {
+        self.budget_slack = Some(budget - self.total_cost);
+        self.budget_utilization = Some(if budget > 0.0 {
+            self.total_cost / budget
+        } else {
+            1.0
+        });
+        self
+    }
+
+    /// Whether less than `threshold` of the budget was spent
+    pub fn is_underutilized(&self, threshold: f64) -> bool {
+        self.budget_utilization
+            .is_some_and(|utilization| utilization < threshold)
+    }
+
+    /// Results for the selected alternatives, largest contribution first
+    /// Ties are ordered by asset and alternative ID
+    pub fn ranked_selections<'a>(
+        &self,
+        results: &'a [RiskCalculationResult],
+        by: Contribution,
+    ) -> Vec<&'a RiskCalculationResult> {
+        let selected: HashSet<&str> = self
+            .selected_alternatives
+            .iter()
+            .map(String::as_str)
+            .collect();
+        let mut ranked: Vec<&RiskCalculationResult> = results
+            .iter()
+            .filter(|r| selected.contains(r.selection_key().as_str()))
+            .collect();
+        ranked.sort_by(|a, b| {
+            by.value(b)
+                .total_cmp(&by.value(a))
+                .then_with(|| a.asset.asset_id.cmp(&b.asset.asset_id))
+                .then_with(|| a.asset.alternative_id.cmp(&b.asset.alternative_id))
+        });
+        ranked
+    }
+
+    /// Changes from this solution to `other`: alternatives `other` adds and drops,
+    /// in selection order, and the change in its totals (`other` minus `self`)
+    pub fn diff(&self, other: &OptimizationSolution) -> SolutionDiff {
+        let ours: HashSet<&String> = self.selected_alternatives.iter().collect();
+        let theirs: HashSet<&String> = other.selected_alternatives.iter().collect();
+        SolutionDiff {
+            added: other
+                .selected_alternatives
+                .iter()
+                .filter(|a| !ours.contains(a))
+                .cloned()
+                .collect(),
+            removed: self
+                .selected_alternatives
+                .iter()
+                .filter(|a| !theirs.contains(a))
+                .cloned()
+                .collect(),
+            cost_delta: other.total_cost - self.total_cost,
+            risk_reduction_delta: other.total_risk_reduction - self.total_risk_reduction,
+            priority_score_delta: other.total_priority_score - self.total_priority_score,
+        }
+    }
+}
+
+/// Difference between two solutions, see `OptimizationSolution::diff`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolutionDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub cost_delta: f64,
+    pub risk_reduction_delta: f64,
+    pub priority_score_delta: f64,
+}
+
+impl SolutionDiff {
+    /// Both solutions select the same alternatives
+    pub fn is_same_selection(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Totals of an LP portfolio next to the greedy one at the same budget
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeuristicComparison {
+    pub lp_selected: usize,
+    pub greedy_selected: usize,
+    pub lp_cost: f64,
+    pub greedy_cost: f64,
+    pub lp_risk_reduction: f64,
+    pub greedy_risk_reduction: f64,
+}
+
+impl HeuristicComparison {
+    pub fn new(lp: &OptimizationSolution, greedy: &OptimizationSolution) -> Self {
+        Self {
+            lp_selected: lp.num_assets_optimized,
+            greedy_selected: greedy.num_assets_optimized,
+            lp_cost: lp.total_cost,
+            greedy_cost: greedy.total_cost,
+            lp_risk_reduction: lp.total_risk_reduction,
+            greedy_risk_reduction: greedy.total_risk_reduction,
+        }
+    }
+
+    /// Percentage by which LP risk reduction exceeds greedy's (negative if it falls
+    /// short); 0.0 when they are equal, None when only LP reduces any risk
+    pub fn improvement_percent(&self) -> Option<f64> {
+        if self.lp_risk_reduction == self.greedy_risk_reduction {
+            Some(0.0)
+        } else if self.greedy_risk_reduction > 0.0 {
+            Some((self.lp_risk_reduction / self.greedy_risk_reduction - 1.0) * 100.0)
+        } else {
+            None
+        }
+    }
+}
+
+/// How many alternatives three strategies agree on, one count per Venn region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SelectionOverlap {
+    pub all_three: usize,
+    pub first_second: usize,
+    pub first_third: usize,
+    pub second_third: usize,
+    pub first_only: usize,
+    pub second_only: usize,
+    pub third_only: usize,
+}
+
+impl SelectionOverlap {
+    /// Selected by exactly two of the strategies
+    pub fn exactly_two(&self) -> usize {
+        self.first_second + self.first_third + self.second_third
+    }
+
+    /// Selected by exactly one of the strategies
+    pub fn exactly_one(&self) -> usize {
+        self.first_only + self.second_only + self.third_only
+    }
+}
+
+/// Count the alternatives in each region of the Venn diagram of three selections
+pub fn selection_overlap(
+    first: &HashSet<String>,
+    second: &HashSet<String>,
+    third: &HashSet<String>,
+) -> SelectionOverlap {
+    let mut overlap = SelectionOverlap::default();
+    let any: HashSet<&String> = first.iter().chain(second).chain(third).collect();
+    for alternative in any {
+        let region = match (
+            first.contains(alternative),
+            second.contains(alternative),
+            third.contains(alternative),
+        ) {
+            (true, true, true) => &mut overlap.all_three,
+            (true, true, false) => &mut overlap.first_second,
+            (true, false, true) => &mut overlap.first_third,
+            (false, true, true) => &mut overlap.second_third,
+            (true, false, false) => &mut overlap.first_only,
+            (false, true, false) => &mut overlap.second_only,
+            (false, false, _) => &mut overlap.third_only,
+        };
+        *region += 1;
+    }
+    overlap
+}
+
+/// Per-alternative measure used to rank selected alternatives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contribution {
+    RiskReduction,
+    Priority,
+}
+
+impl Contribution {
+    pub fn value(&self, result: &RiskCalculationResult) -> f64 {
+        match self {
+            Contribution::RiskReduction => result.risk_reduction,
+            Contribution::Priority => result.effective_priority(),
+        }
+    }
 }
 
 /// Portfolio optimizer using linear programming
 /// Selects alternatives that maximize value under budget constraint
-pub struct PortfolioOptimizer;
+pub struct PortfolioOptimizer {
+    exclude_do_nothing: bool,
+    category_caps: BTreeMap<String, f64>,
+    solver: fn(&Problem) -> Result<Solution, minilp::Error>,
+    timeout: Option<Duration>,
+}
 
 impl PortfolioOptimizer {
     pub fn new() -> Self {
-        Self
+        Self {
+            exclude_do_nothing: false,
+            category_caps: BTreeMap::new(),
+            solver: Problem::solve,
+            timeout: None,
+        }
+    }
+
+    /// Give up on an LP solve after `timeout`: the budget strategies fall back to
+    /// the greedy portfolio, multi-period and cost minimization return `TimedOut`
+    /// minilp cannot be interrupted, so the solve keeps running on its worker
+    /// thread until it finishes and its result is discarded
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Replace the LP solver, so tests can exercise solver failures
+    #[cfg(test)]
+    fn with_solver(mut self, solver: fn(&Problem) -> Result<Solution, minilp::Error>) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    /// Limit total spend per asset category (see `asset_category`), on top of the budget
+    /// Multi-period plans apply each cap to every period
+    pub fn with_category_caps(mut self, caps: BTreeMap<String, f64>) -> Self {
+        self.category_caps = caps;
+        self
+    }
+
+    /// Caps must be finite and non-negative; any such set is jointly feasible with
+    /// the budget, since selecting nothing in a category always satisfies its cap
+    pub fn validate_category_caps(&self) -> Result<(), CapAllocError> {
+        for (category, &cap) in &self.category_caps {
+            if !cap.is_finite() || cap < 0.0 {
+                return Err(CapAllocError::Validation(format!(
+                    "Spending cap for category {} must be finite and non-negative, got {}",
+                    category, cap
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Add `sum(cost * x) <= cap` for each capped category present in `vars`
+    /// Rows are divided by the cap: minilp misreports degenerate, exactly-spent
+    /// caps as infeasible when coefficients are in raw currency units
+    fn add_category_cap_constraints<'a>(
+        &self,
+        problem: &mut Problem,
+        vars: impl IntoIterator<Item = (Variable, &'a RiskCalculationResult)>,
+    ) {
+        if self.category_caps.is_empty() {
+            return;
+        }
+        let mut spend: BTreeMap<&str, Vec<(Variable, f64)>> = BTreeMap::new();
+        for (var, result) in vars {
+            let category = asset_category(&result.asset.asset_id);
+            if let Some(&cap) = self.category_caps.get(category) {
+                let scale = if cap > 0.0 { cap } else { 1.0 };
+                spend
+                    .entry(category)
+                    .or_default()
+                    .push((var, result.asset.cost_usd / scale));
+            }
+        }
+        for (category, constraint) in spend {
+            let cap = self.category_caps[category];
+            let rhs = if cap > 0.0 { 1.0 } else { 0.0 };
+            problem.add_constraint(&constraint, ComparisonOp::Le, rhs);
+        }
+    }
+
+    /// Whether funding `result` keeps its category within cap, given `spent` so far
+    fn fits_category_cap(
+        &self,
+        spent: &HashMap<&str, f64>,
+        result: &RiskCalculationResult,
+    ) -> bool {
+        let category = asset_category(&result.asset.asset_id);
+        match self.category_caps.get(category) {
+            Some(cap) => spent.get(category).unwrap_or(&0.0) + result.asset.cost_usd <= *cap,
+            None => true,
+        }
+    }
+
+    /// Best-effort reason `result` is not part of `solution`, optimized from `results`
+    /// at `budget`: do-nothing alternatives are excluded, another alternative of its
+    /// asset was chosen, it does not fit in the unspent budget or under its category
+    /// cap, or it fits but the optimizer preferred the selected alternatives
+    pub fn explain_exclusion(
+        &self,
+        result: &RiskCalculationResult,
+        results: &[RiskCalculationResult],
+        solution: &OptimizationSolution,
+        budget: f64,
+        money: &MoneyFormat,
+    ) -> String {
+        if self.exclude_do_nothing && result.is_do_nothing() {
+            return "excluded: do-nothing alternatives are not candidates".to_string();
+        }
+
+        let selected: HashSet<&str> = solution
+            .selected_alternatives
+            .iter()
+            .map(String::as_str)
+            .collect();
+        let chosen: Vec<&RiskCalculationResult> = results
+            .iter()
+            .filter(|r| selected.contains(r.selection_key().as_str()))
+            .collect();
+        let sibling = chosen.iter().find(|r| {
+            r.asset.asset_id == result.asset.asset_id
+                && r.asset.alternative_id != result.asset.alternative_id
+        });
+        if let Some(sibling) = sibling {
+            return format!(
+                "excluded: another alternative of this asset ({}) was chosen",
+                sibling.asset.alternative_id
+            );
+        }
+
+        let overrun = solution.total_cost + result.asset.cost_usd - budget;
+        if overrun > 0.0 {
+            return format!(
+                "excluded: adding it would exceed budget by {}",
+                money.currency(overrun)
+            );
+        }
+
+        let mut spent: HashMap<&str, f64> = HashMap::new();
+        for r in &chosen {
+            *spent
+                .entry(asset_category(&r.asset.asset_id))
+                .or_insert(0.0) += r.asset.cost_usd;
+        }
+        if !self.fits_category_cap(&spent, result) {
+            let category = result.asset.category();
+            return format!(
+                "excluded: adding it would exceed the {} cap of {}",
+                category,
+                money.currency(self.category_caps[category])
+            );
+        }
+
+        "excluded: lower priority than selected alternatives within budget".to_string()
+    }
+
+    /// Treat do-nothing alternatives (Defer or zero cost) as the implicit baseline:
+    /// they are never candidates, so an asset with no funded alternative is deferred
+    pub fn with_do_nothing_excluded(mut self, exclude: bool) -> Self {
+        self.exclude_do_nothing = exclude;
+        self
+    }
+
+    /// Alternatives eligible for selection under the current settings
+    fn candidates<'a>(
+        &self,
+        results: &'a [RiskCalculationResult],
+    ) -> Vec<&'a RiskCalculationResult> {
+        results
+            .iter()
+            .filter(|result| !(self.exclude_do_nothing && result.is_do_nothing()))
+            .collect()
+    }
+
+    /// Maximum risk reduction reachable with unlimited budget:
+    /// the best candidate alternative of each asset, summed
+    pub fn achievable_ceiling(&self, results: &[RiskCalculationResult]) -> f64 {
+        let mut best: HashMap<&str, f64> = HashMap::new();
+        for result in self.candidates(results) {
+            let entry = best.entry(result.asset.asset_id.as_str()).or_insert(0.0);
+            *entry = entry.max(result.risk_reduction);
+        }
+        best.values().sum()
+    }
+
+    /// Remaining exposure after funding `selected` ("Asset_ID (Alternative_ID)" keys):
+    /// the post-action risk of each selected alternative plus the baseline risk of
+    /// every asset with none selected (its highest, should its alternatives differ)
+    pub fn residual_risk(results: &[RiskCalculationResult], selected: &[String]) -> f64 {
+        let selected: HashSet<&str> = selected.iter().map(String::as_str).collect();
+        let mut exposure: HashMap<&str, (Option<f64>, f64)> = HashMap::new();
+        for result in results {
+            let entry = exposure
+                .entry(result.asset.asset_id.as_str())
+                .or_insert((None, 0.0));
+            entry.1 = entry.1.max(result.baseline_risk);
+            if selected.contains(result.selection_key().as_str()) {
+                entry.0 = Some(result.post_action_risk);
+            }
+        }
+        exposure
+            .values()
+            .map(|(funded, baseline)| funded.unwrap_or(*baseline))
+            .sum()
     }
 
     /// Optimize asset portfolio under budget constraint using linear programming
@@ -31,48 +540,206 @@ impl PortfolioOptimizer {
         &self,
         results: &[RiskCalculationResult],
         budget: f64,
-    ) -> Result<OptimizationSolution, Box<dyn std::error::Error>> {
+    ) -> Result<OptimizationSolution, CapAllocError> {
+        if results.is_empty() {
+            return Err(CapAllocError::Validation(
+                "No alternatives to optimize".to_string(),
+            ));
+        }
+
+        // Objective coefficient is the risk reduction
+        self.solve(results, budget, None, |result| result.risk_reduction)
+    }
+
+    /// Optimize with priority score as objective
+    /// Uses linear programming to find optimal solution
+    pub fn optimize_by_priority(
+        &self,
+        results: &[RiskCalculationResult],
+        budget: f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
+        if results.is_empty() {
+            return Err(CapAllocError::Validation(
+                "No alternatives to optimize".to_string(),
+            ));
+        }
+
+        self.solve(results, budget, None, |result| result.effective_priority())
+    }
+
+    /// Optimize using combined objective (weighted risk + priority)
+    /// Allows balancing between risk reduction and priority score
+    /// Weights must be finite and non-negative with at least one positive; they are
+    /// normalized to sum to 1.0, so only their ratio matters (0.6/0.4 == 6/4)
+    pub fn optimize_combined(
+        &self,
+        results: &[RiskCalculationResult],
+        budget: f64,
+        risk_weight: f64,
+        priority_weight: f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
         if results.is_empty() {
-            return Err("No alternatives to optimize".into());
+            return Err(CapAllocError::Validation(
+                "No alternatives to optimize".to_string(),
+            ));
+        }
+        if !risk_weight.is_finite()
+            || !priority_weight.is_finite()
+            || risk_weight < 0.0
+            || priority_weight < 0.0
+        {
+            return Err(CapAllocError::Validation(format!(
+                "Weights must be finite and non-negative (risk: {}, priority: {})",
+                risk_weight, priority_weight
+            )));
+        }
+        let weight_sum = risk_weight + priority_weight;
+        if weight_sum <= 0.0 {
+            return Err(CapAllocError::Validation(
+                "At least one of the risk and priority weights must be positive".to_string(),
+            ));
+        }
+        let risk_weight = risk_weight / weight_sum;
+        let priority_weight = priority_weight / weight_sum;
+
+        self.solve(results, budget, None, |result| {
+            // Normalize to similar scales before weighting
+            let normalized_risk = result.risk_reduction / 1_000_000.0; // Scale to millions
+            let normalized_priority = result.effective_priority();
+            risk_weight * normalized_risk + priority_weight * normalized_priority
+        })
+    }
+
+    /// Build and solve the LP shared by all strategies
+    /// Alternatives are ordered by (asset_id, alternative_id) and each objective
+    /// coefficient gets a tiny rank-based bonus, so equal-value alternatives
+    /// always resolve to the one with the smallest IDs regardless of input order.
+    /// If the solver fails, the greedy portfolio is returned with `was_fallback` set.
+    /// With an `overspend_penalty` the budget is soft (see `optimize_soft_budget`)
+    fn solve(
+        &self,
+        results: &[RiskCalculationResult],
+        budget: f64,
+        overspend_penalty: Option<f64>,
+        objective: impl Fn(&RiskCalculationResult) -> f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
+        self.validate_category_caps()?;
+        let overspend_of =
+            |total_cost: f64| overspend_penalty.map(|_| (total_cost - budget).max(0.0));
+        let mut ordered = self.candidates(results);
+        if ordered.is_empty() {
+            let mut solution = OptimizationSolution::new(
+                Vec::new(),
+                0.0,
+                0.0,
+                0.0,
+                self.achievable_ceiling(results),
+            )
+            .with_residual_risk(results)
+            .with_budget(budget);
+            solution.overspend = overspend_of(0.0);
+            return Ok(solution);
         }
+        ordered.sort_by(|a, b| {
+            (&a.asset.asset_id, &a.asset.alternative_id)
+                .cmp(&(&b.asset.asset_id, &b.asset.alternative_id))
+        });
+
+        let coefficients: Vec<f64> = ordered.iter().map(|result| objective(result)).collect();
+        let scale = objective_scale(coefficients.iter().fold(0.0_f64, |max, c| max.max(c.abs())));
+        let count = ordered.len() as f64;
 
-        // Create LP problem: maximize risk reduction
         let mut problem = Problem::new(OptimizationDirection::Maximize);
 
         // Create binary decision variables for each alternative
+        // Binary variable: 1 if selected, 0 otherwise
         let mut vars: Vec<(Variable, &RiskCalculationResult)> = Vec::new();
-        for result in results {
-            // Binary variable: 1 if selected, 0 otherwise
-            // Objective coefficient is the risk reduction
-            let var = problem.add_var(result.risk_reduction, (0.0, 1.0));
+        for (rank, (result, coefficient)) in ordered.into_iter().zip(coefficients).enumerate() {
+            let bonus = tie_break(count - rank as f64, count);
+            let var = problem.add_var(coefficient * scale + bonus, (0.0, 1.0));
             vars.push((var, result));
         }
 
-        // Constraint 1: Total cost <= budget
-        let cost_constraint: Vec<(Variable, f64)> = vars
+        // Constraint 1: Total cost <= budget, or with a soft budget,
+        // total cost - overspend <= budget where each overspent dollar is penalized
+        let mut cost_constraint: Vec<(Variable, f64)> = vars
             .iter()
             .map(|(var, result)| (*var, result.asset.cost_usd))
             .collect();
+        if let Some(penalty) = overspend_penalty {
+            let overspend = problem.add_var(-penalty * scale, (0.0, f64::INFINITY));
+            cost_constraint.push((overspend, -1.0));
+        }
         problem.add_constraint(&cost_constraint, ComparisonOp::Le, budget);
 
         // Constraint 2: At most one alternative per asset
-        // Group alternatives by asset_id
-        let mut asset_groups: HashMap<String, Vec<Variable>> = HashMap::new();
+        // Group alternatives by asset_id (ordered map keeps the model deterministic)
+        let mut asset_groups: BTreeMap<&str, Vec<Variable>> = BTreeMap::new();
         for (var, result) in &vars {
             asset_groups
-                .entry(result.asset.asset_id.clone())
+                .entry(result.asset.asset_id.as_str())
                 .or_default()
                 .push(*var);
         }
 
         // For each asset, add constraint: sum of alternatives <= 1
-        for (_asset_id, asset_vars) in asset_groups.iter() {
+        for asset_vars in asset_groups.values() {
             let constraint: Vec<(Variable, f64)> = asset_vars.iter().map(|v| (*v, 1.0)).collect();
             problem.add_constraint(&constraint, ComparisonOp::Le, 1.0);
         }
 
-        // Solve the problem
-        let solution = problem.solve()?;
+        // Constraint 3: spend per capped category <= its cap
+        self.add_category_cap_constraints(&mut problem, vars.iter().map(|(v, r)| (*v, *r)));
+
+        // Solve the problem; caps are hard limits, so with caps set the relaxation is
+        // made integral instead of rounded, which could overspend a category. A
+        // rounded soft-budget selection would misstate its overspend, so it is too
+        let integral = !self.category_caps.is_empty() || overspend_penalty.is_some();
+        let var_list: Vec<Variable> = vars.iter().map(|(var, _)| *var).collect();
+        let solution = self.run_solver(problem, move |solution| {
+            if integral {
+                make_integral(solution, var_list.into_iter())
+            } else {
+                Ok(solution)
+            }
+        });
+        let solution = match solution {
+            Ok(solution) => solution,
+            // Greedy never overspends, so it is no stand-in for a soft budget
+            Err(e) if overspend_penalty.is_some() => return Err(e),
+            Err(_) => {
+                return self
+                    .optimize_greedy(results, budget)
+                    .map(OptimizationSolution::into_fallback)
+            }
+        };
+
+        // Round each LP value to a selection, counting genuinely fractional ones.
+        // Integral picks are kept first, then values rounded up are added largest
+        // first only while they fit, so rounding never overspends the budget
+        let rounded: Vec<(bool, bool)> = vars
+            .iter()
+            .map(|(var, _)| round_selection(solution[*var]))
+            .collect();
+        let fractional = rounded.iter().filter(|(_, frac)| *frac).count();
+        let mut chosen: Vec<bool> = rounded.iter().map(|&(sel, frac)| sel && !frac).collect();
+        let mut spend: f64 = vars
+            .iter()
+            .zip(&chosen)
+            .filter(|(_, keep)| **keep)
+            .map(|((_, result), _)| result.asset.cost_usd)
+            .sum();
+        let mut rounded_up: Vec<usize> = (0..vars.len())
+            .filter(|&i| rounded[i] == (true, true))
+            .collect();
+        rounded_up.sort_by(|&a, &b| solution[vars[b].0].total_cmp(&solution[vars[a].0]));
+        for i in rounded_up {
+            let cost = vars[i].1.asset.cost_usd;
+            if spend + cost <= budget {
+                chosen[i] = true;
+                spend += cost;
+            }
+        }
 
         // Extract selected alternatives
         let mut selected = Vec::new();
@@ -80,183 +747,516 @@ impl PortfolioOptimizer {
         let mut total_risk_reduction = 0.0;
         let mut total_priority = 0.0;
 
-        for (var, result) in &vars {
-            // Check if variable is selected (value close to 1)
-            if solution[*var] > 0.5 {
-                selected.push(format!(
-                    "{} ({})",
-                    result.asset.asset_id, result.asset.alternative_id
-                ));
+        for ((_, result), is_selected) in vars.iter().zip(chosen) {
+            if is_selected {
+                selected.push(result.selection_key());
                 total_cost += result.asset.cost_usd;
                 total_risk_reduction += result.risk_reduction;
-                total_priority += result.priority_score;
+                total_priority += result.effective_priority();
             }
         }
 
-        Ok(OptimizationSolution {
-            selected_alternatives: selected.clone(),
+        let mut solution = OptimizationSolution::new(
+            selected,
             total_cost,
             total_risk_reduction,
-            total_priority_score: total_priority,
-            num_assets_optimized: selected.len(),
-        })
+            total_priority,
+            self.achievable_ceiling(results),
+        )
+        .with_fractional_selections(fractional)
+        .with_residual_risk(results)
+        .with_budget(budget);
+        solution.overspend = overspend_of(total_cost);
+        Ok(solution)
     }
 
-    /// Optimize with priority score as objective
-    /// Uses linear programming to find optimal solution
-    pub fn optimize_by_priority(
+    /// Sensitivity analysis: maximize risk reduction at each budget level
+    /// Budgets are solved in parallel; returns one (budget, solution) pair per requested budget, in order
+    pub fn budget_sweep(
+        &self,
+        results: &[RiskCalculationResult],
+        budgets: &[f64],
+    ) -> Result<Vec<(f64, OptimizationSolution)>, CapAllocError> {
+        // Each LP solve is independent
+        budgets
+            .par_iter()
+            .map(|&budget| {
+                self.optimize_by_risk_reduction(results, budget)
+                    .map(|solution| (budget, solution))
+                    .map_err(|e| e.with_context(format!("Budget {}", budget)))
+            })
+            .collect()
+    }
+
+    /// Risk vs. cost trade-off curve: maximize risk reduction at `steps` evenly
+    /// spaced budgets up to `budget`, then drop dominated solutions
+    /// Returned solutions are sorted by ascending cost with strictly increasing risk reduction
+    pub fn pareto_frontier(
         &self,
         results: &[RiskCalculationResult],
         budget: f64,
-    ) -> Result<OptimizationSolution, Box<dyn std::error::Error>> {
+        steps: usize,
+    ) -> Result<Vec<OptimizationSolution>, CapAllocError> {
+        if steps == 0 {
+            return Err(CapAllocError::Validation(
+                "Pareto frontier needs at least one step".to_string(),
+            ));
+        }
+
+        let budgets: Vec<f64> = (1..=steps)
+            .map(|i| budget * i as f64 / steps as f64)
+            .collect();
+        let mut solutions: Vec<OptimizationSolution> = self
+            .budget_sweep(results, &budgets)?
+            .into_iter()
+            .map(|(_, solution)| solution)
+            .collect();
+        solutions.sort_by(|a, b| {
+            a.total_cost
+                .total_cmp(&b.total_cost)
+                .then_with(|| b.total_risk_reduction.total_cmp(&a.total_risk_reduction))
+        });
+
+        // A solution survives only if it beats every cheaper (or equally cheap) one
+        let mut frontier: Vec<OptimizationSolution> = Vec::new();
+        for solution in solutions {
+            let best = frontier
+                .last()
+                .map_or(f64::NEG_INFINITY, |s| s.total_risk_reduction);
+            if solution.total_risk_reduction > best {
+                frontier.push(solution);
+            }
+        }
+
+        Ok(frontier)
+    }
+
+    /// Multi-period planning: maximize total risk reduction across several budget
+    /// periods (e.g. fiscal years), returning one solution per period, in order
+    /// Each alternative is funded in at most one period, each period stays within
+    /// its own budget, and each asset is funded at most once across all periods.
+    /// Ties prefer earlier periods. Fractional LP values are rounded by keeping
+    /// integral picks first, then adding fractional ones (largest value first)
+    /// only where they still fit, then filling leftover budget by risk reduction,
+    /// so every period is guaranteed within budget and category caps.
+    /// Each period's residual risk counts everything funded up to and including it
+    pub fn optimize_multi_period(
+        &self,
+        results: &[RiskCalculationResult],
+        budgets: &[f64],
+    ) -> Result<Vec<OptimizationSolution>, CapAllocError> {
         if results.is_empty() {
-            return Err("No alternatives to optimize".into());
+            return Err(CapAllocError::Validation(
+                "No alternatives to optimize".to_string(),
+            ));
+        }
+        if budgets.is_empty() {
+            return Err(CapAllocError::Validation(
+                "At least one budget period is required".to_string(),
+            ));
+        }
+        self.validate_category_caps()?;
+
+        let ceiling = self.achievable_ceiling(results);
+        let mut ordered = self.candidates(results);
+        if ordered.is_empty() {
+            return Ok(budgets
+                .iter()
+                .map(|&budget| {
+                    OptimizationSolution::new(Vec::new(), 0.0, 0.0, 0.0, ceiling)
+                        .with_residual_risk(results)
+                        .with_budget(budget)
+                })
+                .collect());
         }
+        ordered.sort_by(|a, b| {
+            (&a.asset.asset_id, &a.asset.alternative_id)
+                .cmp(&(&b.asset.asset_id, &b.asset.alternative_id))
+        });
+
+        let scale = objective_scale(
+            ordered
+                .iter()
+                .fold(0.0_f64, |max, r| max.max(r.risk_reduction.abs())),
+        );
+        let count = (ordered.len() * budgets.len()) as f64;
 
-        // Create LP problem: maximize priority score
         let mut problem = Problem::new(OptimizationDirection::Maximize);
 
-        // Create binary decision variables
-        let mut vars: Vec<(Variable, &RiskCalculationResult)> = Vec::new();
-        for result in results {
-            let var = problem.add_var(result.priority_score, (0.0, 1.0));
-            vars.push((var, result));
+        // One variable per (alternative, period); ranks run period-major so
+        // the tie-break bonus favors funding in earlier periods
+        let mut vars: Vec<(Variable, usize, &RiskCalculationResult)> = Vec::new();
+        for period in 0..budgets.len() {
+            for result in &ordered {
+                let bonus = tie_break(count - vars.len() as f64, count);
+                let var = problem.add_var(result.risk_reduction * scale + bonus, (0.0, 1.0));
+                vars.push((var, period, result));
+            }
         }
 
-        // Budget constraint
-        let cost_constraint: Vec<(Variable, f64)> = vars
-            .iter()
-            .map(|(var, result)| (*var, result.asset.cost_usd))
-            .collect();
-        problem.add_constraint(&cost_constraint, ComparisonOp::Le, budget);
+        // Constraint 1: each period's cost <= its budget
+        for (period, &budget) in budgets.iter().enumerate() {
+            let cost_constraint: Vec<(Variable, f64)> = vars
+                .iter()
+                .filter(|(_, p, _)| *p == period)
+                .map(|(var, _, result)| (*var, result.asset.cost_usd))
+                .collect();
+            problem.add_constraint(&cost_constraint, ComparisonOp::Le, budget);
+        }
 
-        // One alternative per asset constraint
-        let mut asset_groups: HashMap<String, Vec<Variable>> = HashMap::new();
-        for (var, result) in &vars {
+        // Constraint 2: at most one alternative per asset across all periods
+        let mut asset_groups: BTreeMap<&str, Vec<Variable>> = BTreeMap::new();
+        for (var, _, result) in &vars {
             asset_groups
-                .entry(result.asset.asset_id.clone())
+                .entry(result.asset.asset_id.as_str())
                 .or_default()
                 .push(*var);
         }
-
-        for (_asset_id, asset_vars) in asset_groups.iter() {
+        for asset_vars in asset_groups.values() {
             let constraint: Vec<(Variable, f64)> = asset_vars.iter().map(|v| (*v, 1.0)).collect();
             problem.add_constraint(&constraint, ComparisonOp::Le, 1.0);
         }
 
-        // Solve
-        let solution = problem.solve()?;
+        // Constraint 3: each period's spend per capped category <= its cap
+        for period in 0..budgets.len() {
+            self.add_category_cap_constraints(
+                &mut problem,
+                vars.iter()
+                    .filter(|(_, p, _)| *p == period)
+                    .map(|(var, _, result)| (*var, *result)),
+            );
+        }
 
-        // Extract results
-        let mut selected = Vec::new();
-        let mut total_cost = 0.0;
-        let mut total_risk_reduction = 0.0;
-        let mut total_priority = 0.0;
+        let solution = self.run_solver(problem, Ok)?;
 
-        for (var, result) in &vars {
-            if solution[*var] > 0.5 {
-                selected.push(format!(
-                    "{} ({})",
-                    result.asset.asset_id, result.asset.alternative_id
-                ));
-                total_cost += result.asset.cost_usd;
-                total_risk_reduction += result.risk_reduction;
-                total_priority += result.priority_score;
+        // Integral picks first, then fractional ones by descending LP value,
+        // then anything left unfunded that adds risk reduction, by descending reduction
+        let mut picks: Vec<(f64, usize, &RiskCalculationResult)> = vars
+            .iter()
+            .map(|(var, period, result)| (solution[*var], *period, *result))
+            .collect();
+        picks.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| b.2.risk_reduction.total_cmp(&a.2.risk_reduction))
+        });
+
+        let mut funded_assets: HashSet<&str> = HashSet::new();
+        let mut periods: Vec<(Vec<String>, f64, f64, f64)> =
+            vec![(Vec::new(), 0.0, 0.0, 0.0); budgets.len()];
+        let mut category_spend: Vec<HashMap<&str, f64>> = vec![HashMap::new(); budgets.len()];
+        for (value, period, result) in picks {
+            let (selected, cost, risk_reduction, priority) = &mut periods[period];
+            if (value <= INTEGRALITY_EPSILON && result.risk_reduction <= 0.0)
+                || funded_assets.contains(result.asset.asset_id.as_str())
+                || *cost + result.asset.cost_usd > budgets[period]
+                || !self.fits_category_cap(&category_spend[period], result)
+            {
+                continue;
             }
+            funded_assets.insert(&result.asset.asset_id);
+            *category_spend[period]
+                .entry(asset_category(&result.asset.asset_id))
+                .or_insert(0.0) += result.asset.cost_usd;
+            selected.push(result.selection_key());
+            *cost += result.asset.cost_usd;
+            *risk_reduction += result.risk_reduction;
+            *priority += result.effective_priority();
         }
 
-        Ok(OptimizationSolution {
-            selected_alternatives: selected.clone(),
-            total_cost,
-            total_risk_reduction,
-            total_priority_score: total_priority,
-            num_assets_optimized: selected.len(),
-        })
+        let mut funded_so_far: Vec<String> = Vec::new();
+        Ok(periods
+            .into_iter()
+            .zip(budgets)
+            .map(
+                |((mut selected, cost, risk_reduction, priority), &budget)| {
+                    selected.sort();
+                    funded_so_far.extend(selected.iter().cloned());
+                    let mut solution = OptimizationSolution::new(
+                        selected,
+                        cost,
+                        risk_reduction,
+                        priority,
+                        ceiling,
+                    )
+                    .with_budget(budget);
+                    solution.residual_risk = Self::residual_risk(results, &funded_so_far);
+                    solution
+                },
+            )
+            .collect())
     }
 
-    /// Optimize using combined objective (weighted risk + priority)
-    /// Allows balancing between risk reduction and priority score
-    pub fn optimize_combined(
+    /// Cheapest portfolio reaching a total risk reduction target
+    /// The LP relaxation is made integral by fixing fractional variables one at a time;
+    /// errors when the target exceeds what the candidates can reach within any category caps
+    /// Formulation: Minimize sum of cost
+    /// Subject to:
+    ///   - Total risk reduction >= min_reduction
+    ///   - At most one alternative per asset
+    pub fn optimize_min_cost(
         &self,
         results: &[RiskCalculationResult],
-        budget: f64,
-        risk_weight: f64,
-        priority_weight: f64,
-    ) -> Result<OptimizationSolution, Box<dyn std::error::Error>> {
+        min_reduction: f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
         if results.is_empty() {
-            return Err("No alternatives to optimize".into());
+            return Err(CapAllocError::Validation(
+                "No alternatives to optimize".to_string(),
+            ));
         }
+        if !min_reduction.is_finite() {
+            return Err(CapAllocError::Validation(format!(
+                "Invalid risk reduction target: {}",
+                min_reduction
+            )));
+        }
+        self.validate_category_caps()?;
 
-        // Create LP problem with combined objective
-        let mut problem = Problem::new(OptimizationDirection::Maximize);
+        let reachable = self.achievable_ceiling(results);
+        if min_reduction > reachable {
+            return Err(CapAllocError::Infeasible(format!(
+                "Risk reduction target {:.2} is unreachable; at most {:.2} is achievable",
+                min_reduction, reachable
+            )));
+        }
+        let mut ordered = self.candidates(results);
+        if ordered.is_empty() {
+            return Ok(
+                OptimizationSolution::new(Vec::new(), 0.0, 0.0, 0.0, reachable)
+                    .with_residual_risk(results),
+            );
+        }
+        ordered.sort_by(|a, b| {
+            (&a.asset.asset_id, &a.asset.alternative_id)
+                .cmp(&(&b.asset.asset_id, &b.asset.alternative_id))
+        });
 
-        // Create variables with weighted objective
-        let mut vars: Vec<(Variable, &RiskCalculationResult)> = Vec::new();
-        for result in results {
-            // Normalize to similar scales before weighting
-            let normalized_risk = result.risk_reduction / 1_000_000.0; // Scale to millions
-            let normalized_priority = result.priority_score;
-            let objective_coeff =
-                risk_weight * normalized_risk + priority_weight * normalized_priority;
+        let scale = objective_scale(
+            ordered
+                .iter()
+                .fold(0.0_f64, |max, r| max.max(r.asset.cost_usd.abs())),
+        );
+        let count = ordered.len() as f64;
+
+        let mut problem = Problem::new(OptimizationDirection::Minimize);
 
-            let var = problem.add_var(objective_coeff, (0.0, 1.0));
+        // The tie-break penalty grows with rank, so equal-cost alternatives resolve to
+        // the smallest IDs and zero-cost ones are only picked when they are needed
+        let mut vars: Vec<(Variable, &RiskCalculationResult)> = Vec::new();
+        for (rank, result) in ordered.into_iter().enumerate() {
+            let penalty = tie_break(rank as f64 + 1.0, count);
+            let var = problem.add_var(result.asset.cost_usd * scale + penalty, (0.0, 1.0));
             vars.push((var, result));
         }
 
-        // Add constraints
-        let cost_constraint: Vec<(Variable, f64)> = vars
+        // Constraint 1: total risk reduction >= target
+        let reduction_constraint: Vec<(Variable, f64)> = vars
             .iter()
-            .map(|(var, result)| (*var, result.asset.cost_usd))
+            .map(|(var, result)| (*var, result.risk_reduction))
             .collect();
-        problem.add_constraint(&cost_constraint, ComparisonOp::Le, budget);
+        problem.add_constraint(&reduction_constraint, ComparisonOp::Ge, min_reduction);
 
-        let mut asset_groups: HashMap<String, Vec<Variable>> = HashMap::new();
+        // Constraint 2: at most one alternative per asset
+        let mut asset_groups: BTreeMap<&str, Vec<Variable>> = BTreeMap::new();
         for (var, result) in &vars {
             asset_groups
-                .entry(result.asset.asset_id.clone())
+                .entry(result.asset.asset_id.as_str())
                 .or_default()
                 .push(*var);
         }
-
-        for (_asset_id, asset_vars) in asset_groups.iter() {
+        for asset_vars in asset_groups.values() {
             let constraint: Vec<(Variable, f64)> = asset_vars.iter().map(|v| (*v, 1.0)).collect();
             problem.add_constraint(&constraint, ComparisonOp::Le, 1.0);
         }
 
-        // Solve
-        let solution = problem.solve()?;
+        // Constraint 3: spend per capped category <= its cap
+        self.add_category_cap_constraints(&mut problem, vars.iter().map(|(v, r)| (*v, *r)));
+
+        let var_list: Vec<Variable> = vars.iter().map(|(var, _)| *var).collect();
+        let solution = self
+            .run_solver(problem, move |solution| {
+                make_integral(solution, var_list.into_iter())
+            })
+            .map_err(|e| match e {
+                CapAllocError::TimedOut(_) => e,
+                e => e.with_context(format!(
+                    "Risk reduction target {:.2} cannot be met",
+                    min_reduction
+                )),
+            })?;
 
-        // Extract results
         let mut selected = Vec::new();
         let mut total_cost = 0.0;
         let mut total_risk_reduction = 0.0;
         let mut total_priority = 0.0;
-
+        let mut fractional = 0;
         for (var, result) in &vars {
-            if solution[*var] > 0.5 {
-                selected.push(format!(
-                    "{} ({})",
-                    result.asset.asset_id, result.asset.alternative_id
-                ));
+            let (is_selected, is_fractional) = round_selection(solution[*var]);
+            fractional += usize::from(is_fractional);
+            if is_selected {
+                selected.push(result.selection_key());
                 total_cost += result.asset.cost_usd;
                 total_risk_reduction += result.risk_reduction;
-                total_priority += result.priority_score;
+                total_priority += result.effective_priority();
             }
         }
 
-        Ok(OptimizationSolution {
-            num_assets_optimized: selected.len(),
-            selected_alternatives: selected,
+        Ok(OptimizationSolution::new(
+            selected,
             total_cost,
             total_risk_reduction,
-            total_priority_score: total_priority,
-        })
+            total_priority,
+            self.achievable_ceiling(results),
+        )
+        .with_fractional_selections(fractional)
+        .with_residual_risk(results))
     }
-}
 
-impl Default for PortfolioOptimizer {
-    fn default() -> Self {
-        Self::new()
+    /// Maximize risk reduction with `budget` as a soft constraint: spending beyond it
+    /// is allowed but every overspent dollar costs `overspend_penalty` of risk reduction
+    /// in the objective, so a high penalty keeps the portfolio within budget and a low
+    /// one buys extra risk reduction when it is worth more than the penalty.
+    /// Category caps stay hard limits. The chosen amount is returned as `overspend`
+    /// (and `budget_slack` goes negative); an LP failure is an error, not a greedy fallback
+    /// Formulation: Maximize sum of risk reduction - overspend_penalty * overspend
+    /// Subject to:
+    ///   - Total cost - overspend <= budget, overspend >= 0
+    ///   - At most one alternative per asset
+    pub fn optimize_soft_budget(
+        &self,
+        results: &[RiskCalculationResult],
+        budget: f64,
+        overspend_penalty: f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
+        if results.is_empty() {
+            return Err(CapAllocError::Validation(
+                "No alternatives to optimize".to_string(),
+            ));
+        }
+        if !budget.is_finite() || budget < 0.0 {
+            return Err(CapAllocError::Validation(format!(
+                "Budget must be finite and non-negative: {}",
+                budget
+            )));
+        }
+        if !overspend_penalty.is_finite() || overspend_penalty < 0.0 {
+            return Err(CapAllocError::Validation(format!(
+                "Overspend penalty must be finite and non-negative: {}",
+                overspend_penalty
+            )));
+        }
+        self.solve(results, budget, Some(overspend_penalty), |result| {
+            result.risk_reduction
+        })
+    }
+
+    /// Naive baseline for comparison with the LP strategies
+    /// Funds alternatives in descending cost-effectiveness order, skipping any
+    /// that would exceed the remaining budget or a category cap, or whose asset is
+    /// already funded
+    pub fn optimize_greedy(
+        &self,
+        results: &[RiskCalculationResult],
+        budget: f64,
+    ) -> Result<OptimizationSolution, CapAllocError> {
+        if results.is_empty() {
+            return Err(CapAllocError::Validation(
+                "No alternatives to optimize".to_string(),
+            ));
+        }
+
+        self.validate_category_caps()?;
+
+        let mut ranked = self.candidates(results);
+        ranked.sort_by(|a, b| b.cost_effectiveness.total_cmp(&a.cost_effectiveness));
+
+        let mut funded_assets: HashSet<&str> = HashSet::new();
+        let mut category_spend: HashMap<&str, f64> = HashMap::new();
+        let mut selected = Vec::new();
+        let mut total_cost = 0.0;
+        let mut total_risk_reduction = 0.0;
+        let mut total_priority = 0.0;
+
+        for result in ranked {
+            if funded_assets.contains(result.asset.asset_id.as_str())
+                || total_cost + result.asset.cost_usd > budget
+                || !self.fits_category_cap(&category_spend, result)
+            {
+                continue;
+            }
+
+            funded_assets.insert(&result.asset.asset_id);
+            *category_spend
+                .entry(asset_category(&result.asset.asset_id))
+                .or_insert(0.0) += result.asset.cost_usd;
+            selected.push(result.selection_key());
+            total_cost += result.asset.cost_usd;
+            total_risk_reduction += result.risk_reduction;
+            total_priority += result.effective_priority();
+        }
+
+        Ok(OptimizationSolution::new(
+            selected,
+            total_cost,
+            total_risk_reduction,
+            total_priority,
+            self.achievable_ceiling(results),
+        )
+        .with_residual_risk(results)
+        .with_budget(budget))
+    }
+
+    /// Solve `problem` and post-process the solution with `finish`, on a worker
+    /// thread when a timeout is set so the wait can be abandoned
+    fn run_solver(
+        &self,
+        problem: Problem,
+        finish: impl FnOnce(Solution) -> Result<Solution, minilp::Error> + Send + 'static,
+    ) -> Result<Solution, CapAllocError> {
+        let solver = self.solver;
+        let Some(timeout) = self.timeout else {
+            return Ok(solver(&problem).and_then(finish)?);
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The receiver is gone if the wait already timed out
+            let _ = sender.send(solver(&problem).and_then(finish));
+        });
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => Ok(result?),
+            Err(RecvTimeoutError::Timeout) => Err(CapAllocError::TimedOut(format!(
+                "LP solver did not finish within {} ms",
+                timeout.as_millis()
+            ))),
+            Err(RecvTimeoutError::Disconnected) => Err(CapAllocError::Infeasible(
+                "LP solver stopped without a result".to_string(),
+            )),
+        }
+    }
+}
+
+/// Fix the most-selected fractional variable to 1 (or to 0 if that is infeasible)
+/// and re-solve, until every variable is 0 or 1
+fn make_integral(
+    mut solution: Solution,
+    vars: impl Iterator<Item = Variable> + Clone,
+) -> Result<Solution, minilp::Error> {
+    while let Some(var) = vars
+        .clone()
+        .filter(|var| round_selection(solution[*var]).1)
+        .max_by(|a, b| solution[*a].total_cmp(&solution[*b]))
+    {
+        solution = match solution.clone().fix_var(var, 1.0) {
+            Ok(fixed) => fixed,
+            Err(_) => solution.fix_var(var, 0.0)?,
+        };
+    }
+    Ok(solution)
+}
+
+impl Default for PortfolioOptimizer {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -273,22 +1273,15 @@ mod tests {
         priority: f64,
     ) -> RiskCalculationResult {
         RiskCalculationResult::new(
-            Asset {
-                asset_id: asset_id.to_string(),
-                alternative_id: alternative.to_string(),
-                cost_usd: cost,
-                pof_post_action: 0.05,
-                cof_total_usd: 500000.0,
-                safety_risk_level: "Low".to_string(),
-            },
+            Asset::fixture(asset_id, alternative, cost),
             500000.0,
             25000.0,
             risk_reduction,
-            risk_reduction / cost,
+            Some(risk_reduction / cost),
             5.0,
             priority,
             75.0,
-            12.0,
+            Some(12.0),
             0.5,
         )
     }
@@ -322,6 +1315,98 @@ mod tests {
         assert!(solution.num_assets_optimized > 0);
     }
 
+    #[test]
+    fn test_ranked_selections() {
+        let results = vec![
+            create_test_result("ASSET_A", "Pilot", 10000.0, 50000.0, 9.0),
+            create_test_result("ASSET_A", "Full", 20000.0, 90000.0, 1.0),
+            create_test_result("ASSET_B", "Full", 15000.0, 80000.0, 8.0),
+            create_test_result("ASSET_C", "Full", 20000.0, 80000.0, 2.0),
+        ];
+        let solution = OptimizationSolution::new(
+            vec![
+                "ASSET_C (Full)".to_string(),
+                "ASSET_A (Pilot)".to_string(),
+                "ASSET_B (Full)".to_string(),
+            ],
+            45000.0,
+            210000.0,
+            19.0,
+            250000.0,
+        );
+        let labels = |ranked: Vec<&RiskCalculationResult>| -> Vec<String> {
+            ranked.iter().map(|r| r.selection_key()).collect()
+        };
+
+        // Unselected ASSET_A (Full) is skipped; equal risk reductions fall back to ID order
+        assert_eq!(
+            labels(solution.ranked_selections(&results, Contribution::RiskReduction)),
+            vec!["ASSET_B (Full)", "ASSET_C (Full)", "ASSET_A (Pilot)"]
+        );
+        assert_eq!(
+            labels(solution.ranked_selections(&results, Contribution::Priority)),
+            vec!["ASSET_A (Pilot)", "ASSET_B (Full)", "ASSET_C (Full)"]
+        );
+    }
+
+    #[test]
+    fn test_diff_overlapping_selections() {
+        let before = OptimizationSolution::new(
+            vec!["ASSET_A (Pilot)".to_string(), "ASSET_B (Full)".to_string()],
+            25000.0,
+            130000.0,
+            17.0,
+            250000.0,
+        );
+        let after = OptimizationSolution::new(
+            vec!["ASSET_B (Full)".to_string(), "ASSET_C (Full)".to_string()],
+            35000.0,
+            160000.0,
+            10.0,
+            250000.0,
+        );
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, vec!["ASSET_C (Full)"]);
+        assert_eq!(diff.removed, vec!["ASSET_A (Pilot)"]);
+        assert_eq!(diff.cost_delta, 10000.0);
+        assert_eq!(diff.risk_reduction_delta, 30000.0);
+        assert_eq!(diff.priority_score_delta, -7.0);
+        assert!(!diff.is_same_selection());
+
+        // Reversing the comparison swaps the lists and negates the deltas
+        let reverse = after.diff(&before);
+        assert_eq!(reverse.added, diff.removed);
+        assert_eq!(reverse.removed, diff.added);
+        assert_eq!(reverse.cost_delta, -10000.0);
+
+        assert!(before.diff(&before).is_same_selection());
+    }
+
+    #[test]
+    fn test_diff_disjoint_selections() {
+        let before = OptimizationSolution::new(
+            vec!["ASSET_A (Pilot)".to_string()],
+            10000.0,
+            50000.0,
+            9.0,
+            250000.0,
+        );
+        let after = OptimizationSolution::new(
+            vec!["ASSET_B (Full)".to_string(), "ASSET_C (Full)".to_string()],
+            35000.0,
+            160000.0,
+            10.0,
+            250000.0,
+        );
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, after.selected_alternatives);
+        assert_eq!(diff.removed, before.selected_alternatives);
+        assert_eq!(diff.cost_delta, 25000.0);
+        assert_eq!(diff.risk_reduction_delta, 110000.0);
+    }
+
     #[test]
     fn test_one_alternative_per_asset() {
         let optimizer = PortfolioOptimizer::new();
@@ -375,4 +1460,1084 @@ mod tests {
         // Should select the one with higher risk reduction
         assert!(solution.total_risk_reduction >= 50000.0);
     }
+
+    #[test]
+    fn test_solution_serializes_to_json() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![create_test_result(
+            "IT_SYSTEM_001",
+            "Pilot_Program",
+            10000.0,
+            50000.0,
+            5.0,
+        )];
+
+        let solution = optimizer
+            .optimize_by_risk_reduction(&results, 20000.0)
+            .unwrap();
+        let json = serde_json::to_string(&solution).unwrap();
+
+        assert!(json.contains("\"total_cost\":10000.0"));
+        assert!(json.contains("\"selected_alternatives\":[\"IT_SYSTEM_001 (Pilot_Program)\"]"));
+    }
+
+    #[test]
+    fn test_combined_weights_select_matching_strategy() {
+        let optimizer = PortfolioOptimizer::new();
+        // High risk reduction but low priority vs. low risk reduction but high priority
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Full", 10000.0, 900000.0, 0.1),
+            create_test_result("DATACENTER_002", "Full", 10000.0, 100000.0, 5.0),
+        ];
+
+        let risk_only = optimizer
+            .optimize_combined(&results, 10000.0, 1.0, 0.0)
+            .unwrap();
+        assert_eq!(
+            risk_only.selected_alternatives,
+            vec!["IT_SYSTEM_001 (Full)"]
+        );
+
+        let priority_only = optimizer
+            .optimize_combined(&results, 10000.0, 0.0, 1.0)
+            .unwrap();
+        assert_eq!(
+            priority_only.selected_alternatives,
+            vec!["DATACENTER_002 (Full)"]
+        );
+    }
+
+    #[test]
+    fn test_combined_rejects_negative_weights() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![create_test_result(
+            "IT_SYSTEM_001",
+            "Pilot_Program",
+            10000.0,
+            50000.0,
+            5.0,
+        )];
+
+        assert!(optimizer
+            .optimize_combined(&results, 10000.0, -0.5, 0.5)
+            .is_err());
+        assert!(optimizer
+            .optimize_combined(&results, 10000.0, 0.5, -0.5)
+            .is_err());
+    }
+
+    #[test]
+    fn test_combined_rejects_zero_and_non_finite_weights() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![create_test_result(
+            "IT_SYSTEM_001",
+            "Pilot_Program",
+            10000.0,
+            50000.0,
+            5.0,
+        )];
+
+        for (risk, priority) in [(0.0, 0.0), (f64::NAN, 0.5), (0.5, f64::INFINITY)] {
+            assert!(optimizer
+                .optimize_combined(&results, 10000.0, risk, priority)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_combined_weights_are_normalized() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Full", 40000.0, 900000.0, 0.1),
+            create_test_result("IT_SYSTEM_001", "Pilot", 10000.0, 300000.0, 0.6),
+            create_test_result("DATACENTER_002", "Full", 30000.0, 100000.0, 2.0),
+            create_test_result("CLOUD_MIGRATION_003", "Full", 20000.0, 500000.0, 0.9),
+            create_test_result("WAREHOUSE_004", "Full", 10000.0, 50000.0, 1.5),
+        ];
+
+        for budget in [20000.0, 50000.0, 80000.0] {
+            let unit = optimizer
+                .optimize_combined(&results, budget, 0.6, 0.4)
+                .unwrap();
+            let scaled = optimizer
+                .optimize_combined(&results, budget, 6.0, 4.0)
+                .unwrap();
+            assert_eq!(unit, scaled);
+        }
+    }
+
+    fn create_effectiveness_result(
+        asset_id: &str,
+        alternative: &str,
+        cost: f64,
+        risk_reduction: f64,
+        cost_effectiveness: f64,
+    ) -> RiskCalculationResult {
+        let mut result = create_test_result(asset_id, alternative, cost, risk_reduction, 1.0);
+        result.cost_effectiveness = cost_effectiveness;
+        result
+    }
+
+    #[test]
+    fn test_greedy_respects_budget_and_one_per_asset() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_effectiveness_result("IT_SYSTEM_001", "Pilot", 10000.0, 40000.0, 90.0),
+            create_effectiveness_result("IT_SYSTEM_001", "Full", 20000.0, 80000.0, 80.0),
+            create_effectiveness_result("DATACENTER_002", "Full", 25000.0, 60000.0, 70.0),
+            create_effectiveness_result("CLOUD_MIGRATION_003", "Pilot", 15000.0, 30000.0, 60.0),
+        ];
+
+        let solution = optimizer.optimize_greedy(&results, 30000.0).unwrap();
+
+        assert_eq!(
+            solution.selected_alternatives,
+            vec!["IT_SYSTEM_001 (Pilot)", "CLOUD_MIGRATION_003 (Pilot)"]
+        );
+        assert_eq!(solution.total_cost, 25000.0);
+        assert_eq!(solution.total_risk_reduction, 70000.0);
+    }
+
+    #[test]
+    fn test_lp_at_least_as_good_as_greedy() {
+        let optimizer = PortfolioOptimizer::new();
+        // Greedy funds the most cost-effective cheap pilot first, which
+        // crowds out the larger reduction the LP finds
+        let results = vec![
+            create_effectiveness_result("IT_SYSTEM_001", "Pilot", 10000.0, 20000.0, 95.0),
+            create_effectiveness_result("DATACENTER_002", "Full", 20000.0, 90000.0, 60.0),
+            create_effectiveness_result("CLOUD_MIGRATION_003", "Full", 10000.0, 50000.0, 70.0),
+        ];
+
+        let greedy = optimizer.optimize_greedy(&results, 30000.0).unwrap();
+        let lp = optimizer
+            .optimize_by_risk_reduction(&results, 30000.0)
+            .unwrap();
+
+        assert!(lp.total_cost <= 30000.0);
+        assert!(lp.total_risk_reduction >= greedy.total_risk_reduction);
+        assert_eq!(greedy.total_risk_reduction, 70000.0);
+        assert_eq!(lp.total_risk_reduction, 140000.0);
+    }
+
+    #[test]
+    fn test_achievable_ceiling_and_capture_ratio() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Pilot", 10000.0, 40000.0, 1.0),
+            create_test_result("IT_SYSTEM_001", "Full", 50000.0, 90000.0, 1.0),
+            create_test_result("DATACENTER_002", "Pilot", 5000.0, 20000.0, 1.0),
+            create_test_result("DATACENTER_002", "Full", 30000.0, 60000.0, 1.0),
+        ];
+
+        assert_eq!(optimizer.achievable_ceiling(&results), 150000.0);
+
+        for budget in [0.0, 15000.0, 60000.0, 1000000.0] {
+            let solution = optimizer
+                .optimize_by_risk_reduction(&results, budget)
+                .unwrap();
+            assert_eq!(solution.achievable_ceiling, 150000.0);
+            assert!((0.0..=1.0).contains(&solution.capture_ratio));
+        }
+
+        let unconstrained = optimizer
+            .optimize_by_risk_reduction(&results, 1000000.0)
+            .unwrap();
+        assert!((unconstrained.capture_ratio - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_budget_sweep_is_monotonic() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Full", 50000.0, 90000.0, 1.0),
+            create_test_result("DATACENTER_002", "Pilot", 5000.0, 20000.0, 1.0),
+            create_test_result("CLOUD_MIGRATION_003", "Full", 20000.0, 70000.0, 1.0),
+            create_test_result("WAREHOUSE_004", "Partial", 30000.0, 45000.0, 1.0),
+        ];
+        let budgets: Vec<f64> = (0..=10).map(|i| i as f64 * 10000.0).collect();
+
+        let sweep = optimizer.budget_sweep(&results, &budgets).unwrap();
+
+        assert_eq!(sweep.len(), budgets.len());
+        for (i, (budget, _)) in sweep.iter().enumerate() {
+            assert_eq!(*budget, budgets[i]);
+        }
+        for pair in sweep.windows(2) {
+            assert!(pair[1].1.total_risk_reduction >= pair[0].1.total_risk_reduction);
+        }
+    }
+
+    #[test]
+    fn test_parallel_budget_sweep_matches_sequential() {
+        let optimizer = PortfolioOptimizer::new();
+        // Distinct cost/reduction ratios so the LP optimum is unique at every budget
+        let results: Vec<RiskCalculationResult> = (1..=40)
+            .flat_map(|id| {
+                let asset_id = format!("ASSET_{:03}", id);
+                let scale = id as f64;
+                vec![
+                    create_test_result(
+                        &asset_id,
+                        "Pilot",
+                        1000.0 * scale,
+                        5000.0 * scale + 17.0 * scale * scale,
+                        1.0,
+                    ),
+                    create_test_result(
+                        &asset_id,
+                        "Full",
+                        4000.0 * scale,
+                        12000.0 * scale + 29.0 * scale * scale,
+                        2.0,
+                    ),
+                ]
+            })
+            .collect();
+        let budgets: Vec<f64> = (1..=50).map(|i| i as f64 * 50000.0).collect();
+
+        let parallel = optimizer.budget_sweep(&results, &budgets).unwrap();
+        let sequential: Vec<(f64, OptimizationSolution)> = budgets
+            .iter()
+            .map(|&budget| {
+                (
+                    budget,
+                    optimizer
+                        .optimize_by_risk_reduction(&results, budget)
+                        .unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_ties_resolve_deterministically() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("WAREHOUSE_004", "Full", 10000.0, 50000.0, 1.0),
+            create_test_result("DATACENTER_002", "Full", 10000.0, 50000.0, 1.0),
+            create_test_result("IT_SYSTEM_001", "Full", 10000.0, 50000.0, 1.0),
+            create_test_result("CLOUD_MIGRATION_003", "Full", 10000.0, 50000.0, 1.0),
+        ];
+        let mut reversed = results.clone();
+        reversed.reverse();
+
+        let expected = vec!["CLOUD_MIGRATION_003 (Full)", "DATACENTER_002 (Full)"];
+        for _ in 0..10 {
+            let solution = optimizer
+                .optimize_by_risk_reduction(&results, 20000.0)
+                .unwrap();
+            assert_eq!(solution.selected_alternatives, expected);
+
+            let solution = optimizer
+                .optimize_by_risk_reduction(&reversed, 20000.0)
+                .unwrap();
+            assert_eq!(solution.selected_alternatives, expected);
+        }
+    }
+
+    #[test]
+    fn test_ties_between_small_scores_resolve_to_smallest_ids() {
+        // Priority scores near 1 across many alternatives: a bonus relative to the raw
+        // scores would step by ~1e-12 per rank, far inside minilp's 1e-8 tolerance
+        let optimizer = PortfolioOptimizer::new();
+        let results: Vec<RiskCalculationResult> = (0..200)
+            .map(|i| create_test_result(&format!("ASSET_{:03}", i), "Full", 10000.0, 50000.0, 0.5))
+            .collect();
+        let mut reversed = results.clone();
+        reversed.reverse();
+
+        let expected = vec!["ASSET_000 (Full)", "ASSET_001 (Full)", "ASSET_002 (Full)"];
+        for results in [&results, &reversed] {
+            let solution = optimizer.optimize_by_priority(results, 30000.0).unwrap();
+            assert_eq!(solution.selected_alternatives, expected);
+            assert_eq!(solution.fractional_selections, 0);
+        }
+    }
+
+    #[test]
+    fn test_exclude_do_nothing_never_selects_defer() {
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Defer", 0.0, 5000.0, 0.9),
+            create_test_result("IT_SYSTEM_001", "Full", 40000.0, 100000.0, 0.5),
+            create_test_result("DATACENTER_002", "Defer", 0.0, 3000.0, 0.9),
+            create_test_result("DATACENTER_002", "Full", 80000.0, 150000.0, 0.5),
+        ];
+
+        // By default the free Defer rows are picked up alongside funded work
+        let default = PortfolioOptimizer::new()
+            .optimize_by_priority(&results, 50000.0)
+            .unwrap();
+        assert!(default
+            .selected_alternatives
+            .iter()
+            .any(|s| s.contains("Defer")));
+
+        let optimizer = PortfolioOptimizer::new().with_do_nothing_excluded(true);
+        for solution in [
+            optimizer
+                .optimize_by_risk_reduction(&results, 50000.0)
+                .unwrap(),
+            optimizer.optimize_by_priority(&results, 50000.0).unwrap(),
+            optimizer
+                .optimize_combined(&results, 50000.0, 0.6, 0.4)
+                .unwrap(),
+            optimizer.optimize_greedy(&results, 50000.0).unwrap(),
+        ] {
+            assert_eq!(solution.selected_alternatives, vec!["IT_SYSTEM_001 (Full)"]);
+            assert_eq!(solution.total_cost, 40000.0);
+        }
+    }
+
+    #[test]
+    fn test_exclude_do_nothing_with_only_defer_rows() {
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Defer", 0.0, 5000.0, 0.9),
+            create_test_result("DATACENTER_002", "Defer", 0.0, 3000.0, 0.9),
+        ];
+        let optimizer = PortfolioOptimizer::new().with_do_nothing_excluded(true);
+
+        let solution = optimizer
+            .optimize_by_risk_reduction(&results, 50000.0)
+            .unwrap();
+
+        assert!(solution.selected_alternatives.is_empty());
+        assert_eq!(solution.total_cost, 0.0);
+        // Nothing is a candidate, so nothing is achievable and nothing captured
+        assert_eq!(solution.achievable_ceiling, 0.0);
+        assert_eq!(solution.capture_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_multi_period_respects_each_budget() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Pilot", 30000.0, 60000.0, 0.5),
+            create_test_result("IT_SYSTEM_001", "Full", 90000.0, 200000.0, 0.9),
+            create_test_result("DATACENTER_002", "Full", 70000.0, 150000.0, 0.8),
+            create_test_result("CLOUD_MIGRATION_003", "Full", 50000.0, 90000.0, 0.6),
+            create_test_result("WAREHOUSE_004", "Full", 40000.0, 50000.0, 0.4),
+        ];
+        let budgets = [100000.0, 80000.0];
+
+        let periods = optimizer.optimize_multi_period(&results, &budgets).unwrap();
+
+        assert_eq!(periods.len(), 2);
+        for (solution, budget) in periods.iter().zip(budgets) {
+            assert!(solution.total_cost <= budget);
+        }
+        let total: f64 = periods.iter().map(|p| p.total_risk_reduction).sum();
+        assert!(total > 0.0);
+    }
+
+    #[test]
+    fn test_multi_period_funds_each_asset_once() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Pilot", 50000.0, 80000.0, 0.5),
+            create_test_result("IT_SYSTEM_001", "Full", 100000.0, 200000.0, 0.9),
+            create_test_result("DATACENTER_002", "Partial", 50000.0, 70000.0, 0.5),
+            create_test_result("DATACENTER_002", "Full", 100000.0, 180000.0, 0.8),
+        ];
+
+        // Enough money in each period to fund everything on its own
+        let periods = optimizer
+            .optimize_multi_period(&results, &[1000000.0, 1000000.0])
+            .unwrap();
+
+        let mut funded: Vec<&str> = periods
+            .iter()
+            .flat_map(|p| &p.selected_alternatives)
+            .map(|s| s.split(' ').next().unwrap())
+            .collect();
+        funded.sort();
+        assert_eq!(funded, vec!["DATACENTER_002", "IT_SYSTEM_001"]);
+
+        // Ties between periods resolve to the earliest one
+        assert_eq!(
+            periods[0].selected_alternatives,
+            vec!["DATACENTER_002 (Full)", "IT_SYSTEM_001 (Full)"]
+        );
+        assert!(periods[1].selected_alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_multi_period_spills_into_later_period() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Full", 100000.0, 200000.0, 0.9),
+            create_test_result("DATACENTER_002", "Full", 100000.0, 180000.0, 0.8),
+        ];
+
+        let periods = optimizer
+            .optimize_multi_period(&results, &[100000.0, 100000.0])
+            .unwrap();
+
+        assert_eq!(periods[0].selected_alternatives.len(), 1);
+        assert_eq!(periods[1].selected_alternatives.len(), 1);
+        assert_ne!(
+            periods[0].selected_alternatives,
+            periods[1].selected_alternatives
+        );
+        assert_eq!(
+            periods[0].total_risk_reduction + periods[1].total_risk_reduction,
+            380000.0
+        );
+    }
+
+    #[test]
+    fn test_multi_period_leaves_spare_budget_off_negative_reduction() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Full", 100000.0, 200000.0, 0.9),
+            create_test_result("DATACENTER_002", "Retrofit", 20000.0, -5000.0, 0.1),
+        ];
+
+        let periods = optimizer
+            .optimize_multi_period(&results, &[500000.0, 500000.0])
+            .unwrap();
+
+        let funded: Vec<&String> = periods
+            .iter()
+            .flat_map(|p| &p.selected_alternatives)
+            .collect();
+        assert_eq!(funded, vec!["IT_SYSTEM_001 (Full)"]);
+        assert_eq!(periods[0].total_cost + periods[1].total_cost, 100000.0);
+    }
+
+    #[test]
+    fn test_multi_period_requires_budgets() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![create_test_result("A", "Full", 1.0, 1.0, 1.0)];
+        assert!(optimizer.optimize_multi_period(&results, &[]).is_err());
+    }
+
+    #[test]
+    fn test_pareto_frontier_is_non_dominated() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Pilot", 20000.0, 60000.0, 0.5),
+            create_test_result("IT_SYSTEM_001", "Full", 90000.0, 200000.0, 0.9),
+            create_test_result("DATACENTER_002", "Full", 70000.0, 150000.0, 0.8),
+            create_test_result("CLOUD_MIGRATION_003", "Full", 50000.0, 90000.0, 0.6),
+            create_test_result("WAREHOUSE_004", "Full", 40000.0, 50000.0, 0.4),
+        ];
+
+        let frontier = optimizer.pareto_frontier(&results, 300000.0, 12).unwrap();
+
+        assert!(frontier.len() > 1);
+        for (i, a) in frontier.iter().enumerate() {
+            for (j, b) in frontier.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let dominates = a.total_cost <= b.total_cost
+                    && a.total_risk_reduction >= b.total_risk_reduction;
+                assert!(!dominates, "{:?} dominates {:?}", a, b);
+            }
+        }
+        for pair in frontier.windows(2) {
+            assert!(pair[0].total_cost < pair[1].total_cost);
+        }
+    }
+
+    #[test]
+    fn test_pareto_frontier_requires_steps() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![create_test_result("A", "Full", 1.0, 1.0, 1.0)];
+        assert!(optimizer.pareto_frontier(&results, 100.0, 0).is_err());
+    }
+
+    fn min_cost_results() -> Vec<RiskCalculationResult> {
+        vec![
+            create_test_result("ASSET_A", "Pilot", 10000.0, 50000.0, 5.0),
+            create_test_result("ASSET_A", "Full", 30000.0, 90000.0, 9.0),
+            create_test_result("ASSET_B", "Full", 15000.0, 80000.0, 8.0),
+            create_test_result("ASSET_C", "Partial", 20000.0, 60000.0, 6.0),
+        ]
+    }
+
+    #[test]
+    fn test_optimize_min_cost_reachable_target() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = min_cost_results();
+
+        // A Pilot + B (25k) is the cheapest way to reach 130k
+        let solution = optimizer.optimize_min_cost(&results, 130000.0).unwrap();
+        assert_eq!(
+            solution.selected_alternatives,
+            vec!["ASSET_A (Pilot)", "ASSET_B (Full)"]
+        );
+        assert_eq!(solution.total_cost, 25000.0);
+        assert_eq!(solution.total_risk_reduction, 130000.0);
+
+        // 200k needs A's full implementation alongside B and C
+        let solution = optimizer.optimize_min_cost(&results, 200000.0).unwrap();
+        assert_eq!(
+            solution.selected_alternatives,
+            vec!["ASSET_A (Full)", "ASSET_B (Full)", "ASSET_C (Partial)"]
+        );
+        assert_eq!(solution.total_cost, 65000.0);
+        assert!(solution.total_risk_reduction >= 200000.0);
+
+        // A target of zero costs nothing
+        let solution = optimizer.optimize_min_cost(&results, 0.0).unwrap();
+        assert!(solution.selected_alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_optimize_min_cost_unreachable_target() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = min_cost_results();
+
+        // Best per asset sums to 90k + 80k + 60k = 230k
+        let err = optimizer.optimize_min_cost(&results, 230001.0).unwrap_err();
+        assert!(matches!(err, CapAllocError::Infeasible(_)));
+        assert!(
+            err.to_string().contains("unreachable"),
+            "unexpected error: {}",
+            err
+        );
+        assert!(optimizer.optimize_min_cost(&results, 230000.0).is_ok());
+        assert!(matches!(
+            optimizer.optimize_min_cost(&[], 1.0),
+            Err(CapAllocError::Validation(_))
+        ));
+    }
+
+    fn soft_budget_results() -> Vec<RiskCalculationResult> {
+        // Within 30k the best is A Pilot + B (130k); spending 5k more buys A Full + B (170k)
+        vec![
+            create_test_result("ASSET_A", "Pilot", 10000.0, 50000.0, 5.0),
+            create_test_result("ASSET_A", "Full", 20000.0, 90000.0, 9.0),
+            create_test_result("ASSET_B", "Full", 15000.0, 80000.0, 8.0),
+        ]
+    }
+
+    #[test]
+    fn test_soft_budget_high_penalty_stays_within_budget() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = soft_budget_results();
+
+        // 40k of extra risk reduction is not worth 5k at 100 per dollar
+        let solution = optimizer
+            .optimize_soft_budget(&results, 30000.0, 100.0)
+            .unwrap();
+        let hard = optimizer
+            .optimize_by_risk_reduction(&results, 30000.0)
+            .unwrap();
+
+        assert_eq!(solution.overspend, Some(0.0));
+        assert!(solution.total_cost <= 30000.0);
+        assert_eq!(
+            solution.selected_alternatives,
+            vec!["ASSET_A (Pilot)", "ASSET_B (Full)"]
+        );
+        assert_eq!(solution.total_risk_reduction, 130000.0);
+        assert_eq!(hard.overspend, None);
+    }
+
+    #[test]
+    fn test_soft_budget_low_penalty_allows_controlled_overspend() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = soft_budget_results();
+
+        // 40k of extra risk reduction for 5k is worth it at 1 per dollar
+        let solution = optimizer
+            .optimize_soft_budget(&results, 30000.0, 1.0)
+            .unwrap();
+
+        assert_eq!(
+            solution.selected_alternatives,
+            vec!["ASSET_A (Full)", "ASSET_B (Full)"]
+        );
+        assert_eq!(solution.total_cost, 35000.0);
+        assert_eq!(solution.overspend, Some(5000.0));
+        assert_eq!(solution.budget_slack, Some(-5000.0));
+        assert_eq!(solution.total_risk_reduction, 170000.0);
+
+        // Overspending is never more than the one alternative that pays for it
+        let generous = optimizer
+            .optimize_soft_budget(&results, 30000.0, 0.0)
+            .unwrap();
+        assert_eq!(generous.overspend, Some(5000.0));
+    }
+
+    #[test]
+    fn test_soft_budget_rejects_invalid_penalty() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = soft_budget_results();
+
+        for penalty in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                optimizer.optimize_soft_budget(&results, 30000.0, penalty),
+                Err(CapAllocError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_budget_slack_and_utilization() {
+        let optimizer = PortfolioOptimizer::new();
+        // Only 25k of alternatives exist against a 100k budget
+        let results = vec![
+            create_test_result("ASSET_A", "Pilot", 10000.0, 50000.0, 5.0),
+            create_test_result("ASSET_B", "Full", 15000.0, 80000.0, 8.0),
+        ];
+
+        let solution = optimizer
+            .optimize_by_risk_reduction(&results, 100000.0)
+            .unwrap();
+
+        assert_eq!(solution.total_cost, 25000.0);
+        assert_eq!(solution.budget_slack, Some(75000.0));
+        assert_eq!(solution.budget_utilization, Some(0.25));
+        assert!(solution.is_underutilized(0.8));
+        assert!(!solution.is_underutilized(0.2));
+
+        // A tight budget is fully used
+        let solution = optimizer
+            .optimize_by_risk_reduction(&results, 25000.0)
+            .unwrap();
+        assert_eq!(solution.budget_slack, Some(0.0));
+        assert!(!solution.is_underutilized(0.8));
+
+        // Target-driven solutions have no budget to measure against
+        let solution = optimizer.optimize_min_cost(&results, 50000.0).unwrap();
+        assert_eq!(solution.budget_utilization, None);
+        assert!(!solution.is_underutilized(0.8));
+    }
+
+    fn category_spend(solution: &OptimizationSolution, results: &[RiskCalculationResult]) -> f64 {
+        solution
+            .ranked_selections(results, Contribution::RiskReduction)
+            .iter()
+            .filter(|r| r.asset.category() == "IT_SYSTEM")
+            .map(|r| r.asset.cost_usd)
+            .sum()
+    }
+
+    #[test]
+    fn test_category_caps_limit_spend() {
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Full", 60000.0, 200000.0, 1.0),
+            create_test_result("IT_SYSTEM_002", "Full", 50000.0, 150000.0, 1.0),
+            create_test_result("DATACENTER_001", "Full", 40000.0, 60000.0, 1.0),
+        ];
+        let optimizer = PortfolioOptimizer::new();
+        let uncapped = optimizer
+            .optimize_by_risk_reduction(&results, 150000.0)
+            .unwrap();
+        assert_eq!(category_spend(&uncapped, &results), 110000.0);
+
+        let optimizer = PortfolioOptimizer::new()
+            .with_category_caps(BTreeMap::from([("IT_SYSTEM".to_string(), 60000.0)]));
+        let capped = optimizer
+            .optimize_by_risk_reduction(&results, 150000.0)
+            .unwrap();
+        assert_eq!(category_spend(&capped, &results), 60000.0);
+        assert_eq!(capped.total_risk_reduction, 260000.0);
+
+        let greedy = optimizer.optimize_greedy(&results, 150000.0).unwrap();
+        assert!(category_spend(&greedy, &results) <= 60000.0);
+
+        let periods = optimizer
+            .optimize_multi_period(&results, &[150000.0, 150000.0])
+            .unwrap();
+        for period in &periods {
+            assert!(category_spend(period, &results) <= 60000.0);
+        }
+        assert_eq!(
+            periods[0].num_assets_optimized + periods[1].num_assets_optimized,
+            3
+        );
+
+        let min_cost = optimizer.optimize_min_cost(&results, 250000.0).unwrap();
+        assert!(category_spend(&min_cost, &results) <= 60000.0);
+        // Reachable without the cap, but not within it
+        assert!(matches!(
+            optimizer.optimize_min_cost(&results, 300000.0),
+            Err(CapAllocError::Infeasible(_))
+        ));
+    }
+
+    #[test]
+    fn test_negative_category_cap_errors() {
+        let results = vec![create_test_result(
+            "IT_SYSTEM_001",
+            "Full",
+            60000.0,
+            200000.0,
+            1.0,
+        )];
+        let optimizer = PortfolioOptimizer::new()
+            .with_category_caps(BTreeMap::from([("IT_SYSTEM".to_string(), -1.0)]));
+
+        let err = optimizer
+            .optimize_by_risk_reduction(&results, 100000.0)
+            .unwrap_err();
+        assert!(matches!(err, CapAllocError::Validation(_)));
+        assert!(
+            err.to_string().contains("IT_SYSTEM"),
+            "unexpected error: {}",
+            err
+        );
+        assert!(optimizer.optimize_greedy(&results, 100000.0).is_err());
+    }
+
+    #[test]
+    fn test_solver_error_falls_back_to_greedy() {
+        let results = vec![
+            create_test_result("IT_SYSTEM_001", "Pilot", 10000.0, 50000.0, 5.0),
+            create_test_result("DATACENTER_002", "Full", 15000.0, 80000.0, 8.0),
+        ];
+        let optimizer = PortfolioOptimizer::new().with_solver(|_| Err(minilp::Error::Infeasible));
+
+        let solution = optimizer
+            .optimize_by_risk_reduction(&results, 100000.0)
+            .unwrap();
+        let greedy = optimizer.optimize_greedy(&results, 100000.0).unwrap();
+
+        assert!(solution.was_fallback);
+        assert!(!solution.selected_alternatives.is_empty());
+        assert_eq!(solution.selected_alternatives, greedy.selected_alternatives);
+        assert!(!greedy.was_fallback);
+        assert!(
+            !PortfolioOptimizer::new()
+                .optimize_by_risk_reduction(&results, 100000.0)
+                .unwrap()
+                .was_fallback
+        );
+    }
+
+    #[test]
+    fn test_timeout_abandons_slow_solve() {
+        // A small problem behind a solver that sleeps, so the abandoned worker
+        // threads finish shortly after the test instead of solving a huge LP
+        fn slow_solve(problem: &Problem) -> Result<Solution, minilp::Error> {
+            thread::sleep(Duration::from_millis(200));
+            problem.solve()
+        }
+        let results = vec![
+            create_test_result("A", "Pilot", 1000.0, 4000.0, 1.0),
+            create_test_result("A", "Full", 3000.0, 9000.0, 1.0),
+            create_test_result("B", "Pilot", 2000.0, 5000.0, 1.0),
+        ];
+        let optimizer = PortfolioOptimizer::new()
+            .with_solver(slow_solve)
+            .with_timeout(Duration::from_millis(20));
+
+        let solution = optimizer
+            .optimize_by_risk_reduction(&results, 5000.0)
+            .unwrap();
+        assert!(solution.was_fallback);
+        assert!(!solution.selected_alternatives.is_empty());
+        assert!(matches!(
+            optimizer.optimize_min_cost(&results, 8000.0),
+            Err(CapAllocError::TimedOut(_))
+        ));
+        assert!(matches!(
+            optimizer.optimize_multi_period(&results, &[3000.0, 3000.0]),
+            Err(CapAllocError::TimedOut(_))
+        ));
+
+        // A generous timeout returns the LP solution as usual
+        let solution = PortfolioOptimizer::new()
+            .with_solver(slow_solve)
+            .with_timeout(Duration::from_secs(60))
+            .optimize_by_risk_reduction(&results, 5000.0)
+            .unwrap();
+        assert!(!solution.was_fallback);
+    }
+
+    fn selection(alternatives: &[&str]) -> HashSet<String> {
+        alternatives.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_selection_overlap_full() {
+        let all = selection(&["A (Full)", "B (Pilot)"]);
+
+        let overlap = selection_overlap(&all, &all, &all);
+
+        assert_eq!(
+            overlap,
+            SelectionOverlap {
+                all_three: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(overlap.exactly_two(), 0);
+        assert_eq!(overlap.exactly_one(), 0);
+    }
+
+    #[test]
+    fn test_selection_overlap_partial() {
+        let risk = selection(&["A (Full)", "B (Pilot)", "C (Full)", "D (Full)"]);
+        let priority = selection(&["A (Full)", "B (Pilot)", "E (Pilot)"]);
+        let combined = selection(&["A (Full)", "C (Full)", "E (Pilot)", "F (Full)"]);
+
+        let overlap = selection_overlap(&risk, &priority, &combined);
+
+        assert_eq!(
+            overlap,
+            SelectionOverlap {
+                all_three: 1,
+                first_second: 1,
+                first_third: 1,
+                second_third: 1,
+                first_only: 1,
+                second_only: 0,
+                third_only: 1,
+            }
+        );
+        assert_eq!(overlap.exactly_two(), 3);
+        assert_eq!(overlap.exactly_one(), 2);
+    }
+
+    #[test]
+    fn test_selection_overlap_disjoint() {
+        let overlap = selection_overlap(
+            &selection(&["A (Full)"]),
+            &selection(&["B (Pilot)", "C (Full)"]),
+            &selection(&[]),
+        );
+
+        assert_eq!(overlap.all_three, 0);
+        assert_eq!(overlap.exactly_two(), 0);
+        assert_eq!((overlap.first_only, overlap.second_only), (1, 2));
+        assert_eq!(overlap.exactly_one(), 3);
+    }
+
+    #[test]
+    fn test_heuristic_comparison_improvement() {
+        let solution = |selected: usize, cost: f64, risk_reduction: f64| {
+            OptimizationSolution::new(
+                (0..selected)
+                    .map(|i| format!("ASSET_{} (Full)", i))
+                    .collect(),
+                cost,
+                risk_reduction,
+                0.0,
+                1_000_000.0,
+            )
+        };
+
+        let comparison = HeuristicComparison::new(
+            &solution(3, 95000.0, 150000.0),
+            &solution(2, 60000.0, 120000.0),
+        );
+        assert_eq!((comparison.lp_selected, comparison.greedy_selected), (3, 2));
+        assert_eq!(
+            (comparison.lp_cost, comparison.greedy_cost),
+            (95000.0, 60000.0)
+        );
+        assert!((comparison.improvement_percent().unwrap() - 25.0).abs() < 1e-9);
+
+        // Identical portfolios, including two empty ones, are no improvement
+        let same = HeuristicComparison::new(
+            &solution(2, 60000.0, 120000.0),
+            &solution(2, 60000.0, 120000.0),
+        );
+        assert_eq!(same.improvement_percent(), Some(0.0));
+        let empty = HeuristicComparison::new(&solution(0, 0.0, 0.0), &solution(0, 0.0, 0.0));
+        assert_eq!(empty.improvement_percent(), Some(0.0));
+
+        // Greedy can win when the LP relaxation rounds badly; that is a negative improvement
+        let worse = HeuristicComparison::new(
+            &solution(1, 80000.0, 90000.0),
+            &solution(2, 60000.0, 120000.0),
+        );
+        assert!((worse.improvement_percent().unwrap() + 25.0).abs() < 1e-9);
+
+        // No percentage exists when greedy reduces no risk at all
+        let from_zero =
+            HeuristicComparison::new(&solution(1, 10000.0, 50000.0), &solution(0, 0.0, 0.0));
+        assert_eq!(from_zero.improvement_percent(), None);
+    }
+
+    #[test]
+    fn test_heuristic_comparison_from_optimizers() {
+        let optimizer = PortfolioOptimizer::new();
+        let results = vec![
+            create_test_result("ASSET_A", "Pilot", 10000.0, 50000.0, 5.0),
+            create_test_result("ASSET_A", "Full", 20000.0, 90000.0, 9.0),
+            create_test_result("ASSET_B", "Full", 15000.0, 80000.0, 8.0),
+        ];
+        // A generous budget lets both fund the best alternative of every asset
+        let lp = optimizer
+            .optimize_by_risk_reduction(&results, 100000.0)
+            .unwrap();
+        let greedy = optimizer.optimize_greedy(&results, 100000.0).unwrap();
+        let comparison = HeuristicComparison::new(&lp, &greedy);
+
+        assert!(comparison.lp_risk_reduction >= comparison.greedy_risk_reduction);
+        assert!(comparison.improvement_percent().unwrap() >= 0.0);
+    }
+
+    #[test]
+    fn test_explain_exclusion_reasons() {
+        let mut results = vec![
+            create_test_result("ASSET_A", "Pilot", 15000.0, 80000.0, 5.0),
+            create_test_result("ASSET_B", "Full", 20000.0, 60000.0, 5.0),
+        ];
+        let solution = OptimizationSolution::new(
+            results.iter().map(|r| r.selection_key()).collect(),
+            35000.0,
+            140000.0,
+            10.0,
+            200000.0,
+        );
+        let money = MoneyFormat::default();
+        let optimizer = PortfolioOptimizer::new();
+
+        let sibling = create_test_result("ASSET_A", "Full", 20000.0, 60000.0, 4.0);
+        let too_costly = create_test_result("ASSET_C", "Full", 8000.0, 100.0, 1.0);
+        // Fits in the 5000 left, so it lost out on value
+        let affordable = create_test_result("ASSET_D", "Full", 5000.0, 100.0, 1.0);
+        // An asset ID that merely starts with a selected one is not a sibling
+        let prefixed = create_test_result("ASSET_AB", "Pilot", 1000.0, 100.0, 1.0);
+        results.extend([
+            sibling.clone(),
+            too_costly.clone(),
+            affordable.clone(),
+            prefixed.clone(),
+        ]);
+        let explain = |optimizer: &PortfolioOptimizer, result: &RiskCalculationResult| {
+            optimizer.explain_exclusion(result, &results, &solution, 40000.0, &money)
+        };
+
+        assert_eq!(
+            explain(&optimizer, &sibling),
+            "excluded: another alternative of this asset (Pilot) was chosen"
+        );
+        assert_eq!(
+            explain(&optimizer, &too_costly),
+            "excluded: adding it would exceed budget by $3,000.00"
+        );
+        assert_eq!(
+            explain(&optimizer, &affordable),
+            "excluded: lower priority than selected alternatives within budget"
+        );
+        assert!(explain(&optimizer, &prefixed).contains("lower priority"));
+
+        // IDs without a trailing number are their own category
+        let capped = PortfolioOptimizer::new()
+            .with_category_caps(BTreeMap::from([("ASSET_D".to_string(), 4000.0)]));
+        assert_eq!(
+            explain(&capped, &affordable),
+            "excluded: adding it would exceed the ASSET_D cap of $4,000.00"
+        );
+        assert!(explain(&capped, &prefixed).contains("lower priority"));
+    }
+
+    #[test]
+    fn test_residual_risk_mixes_funded_and_unfunded_assets() {
+        let with_risks = |asset: &str, alternative: &str, cost: f64, baseline: f64, post: f64| {
+            let mut result = create_test_result(asset, alternative, cost, baseline - post, 1.0);
+            result.baseline_risk = baseline;
+            result.post_action_risk = post;
+            result
+        };
+        let results = vec![
+            with_risks("ASSET_A", "Pilot", 10000.0, 500000.0, 200000.0),
+            with_risks("ASSET_A", "Full", 20000.0, 500000.0, 50000.0),
+            with_risks("ASSET_B", "Full", 15000.0, 300000.0, 100000.0),
+            with_risks("ASSET_C", "Full", 50000.0, 400000.0, 20000.0),
+        ];
+
+        // A and B funded at their post-action risk, C left at its baseline
+        let funded = vec!["ASSET_A (Full)".to_string(), "ASSET_B (Full)".to_string()];
+        assert_eq!(
+            PortfolioOptimizer::residual_risk(&results, &funded),
+            50000.0 + 100000.0 + 400000.0
+        );
+        // Nothing funded leaves every asset's baseline exactly once
+        assert_eq!(
+            PortfolioOptimizer::residual_risk(&results, &[]),
+            500000.0 + 300000.0 + 400000.0
+        );
+
+        // Solutions carry it for their own selection
+        let optimizer = PortfolioOptimizer::new();
+        let greedy = optimizer.optimize_greedy(&results, 35000.0).unwrap();
+        assert_eq!(
+            greedy.residual_risk,
+            PortfolioOptimizer::residual_risk(&results, &greedy.selected_alternatives)
+        );
+        assert!(greedy.residual_risk < 1200000.0);
+        let nothing = optimizer.optimize_by_risk_reduction(&results, 0.0).unwrap();
+        assert_eq!(nothing.residual_risk, 1200000.0);
+
+        // Later periods count what earlier periods funded
+        let periods = optimizer
+            .optimize_multi_period(&results, &[20000.0, 15000.0])
+            .unwrap();
+        let all_funded: Vec<String> = periods
+            .iter()
+            .flat_map(|p| p.selected_alternatives.iter().cloned())
+            .collect();
+        assert!(periods[1].residual_risk <= periods[0].residual_risk);
+        assert_eq!(
+            periods[1].residual_risk,
+            PortfolioOptimizer::residual_risk(&results, &all_funded)
+        );
+    }
+
+    #[test]
+    fn test_round_selection_threshold_and_snapping() {
+        // Genuinely fractional values round at 0.5, with an exact tie left unselected
+        assert_eq!(round_selection(0.49), (false, true));
+        assert_eq!(round_selection(0.5), (false, true));
+        assert_eq!(round_selection(0.51), (true, true));
+        assert_eq!(round_selection(0.7), (true, true));
+
+        // Solver noise around 0 and 1 snaps without being flagged
+        assert_eq!(round_selection(0.0), (false, false));
+        assert_eq!(round_selection(1e-9), (false, false));
+        assert_eq!(round_selection(-1e-9), (false, false));
+        assert_eq!(round_selection(1.0 - 1e-9), (true, false));
+        assert_eq!(round_selection(1.0), (true, false));
+        assert_eq!(round_selection(1.0 + 1e-9), (true, false));
+    }
+
+    #[test]
+    fn test_fractional_selections_are_counted() {
+        let optimizer = PortfolioOptimizer::new();
+        // Only half of the 20k alternative fits in 10k, so its LP value is 0.5
+        let results = vec![create_test_result("ASSET_A", "Full", 20000.0, 90000.0, 9.0)];
+
+        let fractional = optimizer
+            .optimize_by_risk_reduction(&results, 10000.0)
+            .unwrap();
+        assert_eq!(fractional.fractional_selections, 1);
+        assert!(fractional.selected_alternatives.is_empty());
+        assert!(fractional.total_cost <= 10000.0);
+
+        // B fills 8k of 12k and A's 6k is two-thirds funded; rounding A up would
+        // spend 14k, so it is left out to stay within the hard budget
+        let results = vec![
+            create_test_result("ASSET_A", "Full", 6000.0, 6000.0, 1.0),
+            create_test_result("ASSET_B", "Full", 8000.0, 12000.0, 1.0),
+        ];
+        let within = optimizer
+            .optimize_by_risk_reduction(&results, 12000.0)
+            .unwrap();
+        assert_eq!(within.fractional_selections, 1);
+        assert_eq!(within.selected_alternatives, vec!["ASSET_B (Full)"]);
+        assert!(within.total_cost <= 12000.0);
+        let results = vec![create_test_result("ASSET_A", "Full", 20000.0, 90000.0, 9.0)];
+
+        let integral = optimizer
+            .optimize_by_risk_reduction(&results, 20000.0)
+            .unwrap();
+        assert_eq!(integral.fractional_selections, 0);
+        assert_eq!(integral.num_assets_optimized, 1);
+
+        // Cost minimization is made integral before extraction
+        let min_cost = optimizer.optimize_min_cost(&results, 90000.0).unwrap();
+        assert_eq!(min_cost.fractional_selections, 0);
+    }
 }
@@ -1,44 +1,178 @@
-use crate::domain::{Asset, RiskCalculationResult};
+use crate::domain::{Asset, RiskCalculationResult, RiskLevelConfig, SafetyWeights};
+use crate::error::CapAllocError;
 use crate::repository::FormulaRepository;
 use formcalc::{Engine, Value};
-use std::error::Error;
 use std::time::Instant;
 
+/// Annual discount rate applied when none is configured
+pub const DEFAULT_DISCOUNT_RATE: f64 = 0.08;
+
+/// Probability of failure assumed for doing nothing when none is configured
+pub const DEFAULT_BASELINE_POF: f64 = 1.0;
+
+/// Remaining life assumed when an asset has none; at or beyond it there is no urgency boost
+pub const DEFAULT_REMAINING_LIFE_YEARS: f64 = 10.0;
+
+/// Decimal places for money outputs (risks and adjusted cost) when none is configured
+pub const DEFAULT_MONEY_DP: u32 = 2;
+
+/// Decimal places for ROI, priority score and the degradation and time value factors
+/// when none is configured
+pub const DEFAULT_SCORE_DP: u32 = 4;
+
+/// Decimal places for the criticality, complexity and cost effectiveness ratings when
+/// none is configured; payback months use one place fewer
+pub const DEFAULT_RATING_DP: u32 = 2;
+
 /// Service responsible for calculating risk metrics and ROI
 pub struct RiskCalculationService {
     formula_repository: Box<dyn FormulaRepository>,
+    risk_levels: RiskLevelConfig,
+    safety_weights: SafetyWeights,
+    discount_rate: f64,
+    baseline_pof: f64,
+    /// Percentage by which `pof_post_action` is perturbed for the risk reduction band
+    pof_uncertainty: Option<f64>,
+    money_dp: u32,
+    score_dp: u32,
+    rating_dp: u32,
 }
 
 impl RiskCalculationService {
     pub fn new(formula_repository: Box<dyn FormulaRepository>) -> Self {
-        Self { formula_repository }
+        Self {
+            formula_repository,
+            risk_levels: RiskLevelConfig::default(),
+            safety_weights: SafetyWeights::default(),
+            discount_rate: DEFAULT_DISCOUNT_RATE,
+            baseline_pof: DEFAULT_BASELINE_POF,
+            pof_uncertainty: None,
+            money_dp: DEFAULT_MONEY_DP,
+            score_dp: DEFAULT_SCORE_DP,
+            rating_dp: DEFAULT_RATING_DP,
+        }
     }
 
-    /// Calculate risk metrics for a single asset alternative
-    pub fn calculate(&self, asset: &Asset) -> Result<RiskCalculationResult, Box<dyn Error>> {
-        let start = Instant::now();
+    /// Set which safety level labels drive the `is_high_risk` and `is_critical` formula variables
+    pub fn with_risk_levels(mut self, risk_levels: RiskLevelConfig) -> Self {
+        self.risk_levels = risk_levels;
+        self
+    }
 
-        let mut engine = Engine::new();
-        self.set_variables(&mut engine, asset);
+    /// Set the priority weight per safety level; formulas see the asset's as `safety_weight`
+    pub fn with_safety_weights(mut self, safety_weights: SafetyWeights) -> Self {
+        self.safety_weights = safety_weights;
+        self
+    }
 
-        let formulas = self.formula_repository.load_all()?;
-        engine.execute(formulas)?;
+    /// Set the annual discount rate; formulas see it as the `monthly_rate` variable
+    pub fn with_discount_rate(mut self, annual_rate: f64) -> Self {
+        self.discount_rate = annual_rate;
+        self
+    }
+
+    /// Set the do-nothing probability of failure; formulas see it as `baseline_pof`
+    pub fn with_baseline_pof(mut self, baseline_pof: f64) -> Self {
+        self.baseline_pof = baseline_pof;
+        self
+    }
+
+    /// Also calculate risk reduction with `pof_post_action` lowered and raised by
+    /// `percent` (clamped to 0..=1), stored as `risk_reduction_low`/`risk_reduction_high`
+    pub fn with_pof_uncertainty(mut self, percent: f64) -> Self {
+        self.pof_uncertainty = Some(percent);
+        self
+    }
+
+    /// Set the rounding precision; formulas see it as the `money_dp`, `score_dp` and
+    /// `rating_dp` variables
+    pub fn with_precision(mut self, money_dp: u32, score_dp: u32, rating_dp: u32) -> Self {
+        self.money_dp = money_dp;
+        self.score_dp = score_dp;
+        self.rating_dp = rating_dp;
+        self
+    }
 
-        let result = self.extract_results(&engine)?;
+    /// Calculate risk metrics for a single asset alternative
+    pub fn calculate(&self, asset: &Asset) -> Result<RiskCalculationResult, CapAllocError> {
+        let start = Instant::now();
+        let result = self.evaluate(asset)?;
+
+        // Re-run the formulas at both ends of the PoF range; which end gives the lower
+        // risk reduction is left to the formulas
+        let band = match self.pof_uncertainty {
+            Some(percent) => {
+                let perturbed = |factor: f64| -> Result<f64, CapAllocError> {
+                    let asset = Asset {
+                        pof_post_action: (asset.pof_post_action * factor).clamp(0.0, 1.0),
+                        ..asset.clone()
+                    };
+                    Ok(self.evaluate(&asset)?.risk_reduction)
+                };
+                let lowered = perturbed(1.0 - percent / 100.0)?;
+                let raised = perturbed(1.0 + percent / 100.0)?;
+                Some((lowered.min(raised), lowered.max(raised)))
+            }
+            None => None,
+        };
         let calculation_time_ms = start.elapsed().as_secs_f64() * 1000.0;
 
-        Ok(RiskCalculationResult::new(
+        // The formulas return capped placeholders (999.9999 / 999.9) where ROI and payback
+        // are undefined, which keeps the scores built on them bounded; results expose None
+        let roi = (result.adjusted_cost > 0.0).then_some(result.roi);
+        let payback_period = (result.risk_reduction > 0.0).then_some(result.payback_period);
+
+        let calculated = RiskCalculationResult::new(
             asset.clone(),
             result.baseline_risk,
             result.post_action_risk,
             result.risk_reduction,
-            result.roi,
+            roi,
             result.criticality_score,
             result.priority_score,
             result.cost_effectiveness,
-            result.payback_period,
+            payback_period,
             calculation_time_ms,
-        ))
+        );
+        Ok(match band {
+            Some((low, high)) => calculated.with_risk_reduction_band(low, high),
+            None => calculated,
+        })
+    }
+
+    /// Factor the built-in priority formula applies on top of its weighted sum:
+    /// 1.3x for critical assets, the safety weight, and the remaining-life urgency
+    /// boost. `--normalize-priority` applies it to the dataset-relative score
+    pub fn priority_multiplier(&self, asset: &Asset) -> f64 {
+        let critical = if asset.is_critical(&self.risk_levels) {
+            1.3
+        } else {
+            1.0
+        };
+        let remaining_life = asset
+            .remaining_life_years
+            .unwrap_or(DEFAULT_REMAINING_LIFE_YEARS);
+        let urgency = 1.0 + 0.5 * (1.0 - remaining_life / DEFAULT_REMAINING_LIFE_YEARS).max(0.0);
+        critical * self.safety_weights.weight(&asset.safety_risk_level) * urgency
+    }
+
+    /// Run every formula for `asset` and read back the outputs
+    fn evaluate(&self, asset: &Asset) -> Result<CalculationResults, CapAllocError> {
+        // A fresh engine per asset keeps calculations isolated. Creating one is only a
+        // few empty maps (~2% of a calculation); reusing a cleared engine was measured
+        // no faster, see `engine_creation_vs_reuse_timing`
+        let mut engine = Engine::new();
+        self.set_variables(&mut engine, asset);
+
+        let formulas = self.formula_repository.load_all()?;
+        engine.execute(formulas).map_err(|e| {
+            CapAllocError::FormulaEval(format!(
+                "{} ({}): formula execution failed: {}",
+                asset.asset_id, asset.alternative_id, e
+            ))
+        })?;
+
+        self.extract_results(&engine, asset)
     }
 
     /// Set asset data as variables in the formula engine
@@ -49,38 +183,127 @@ impl RiskCalculationService {
             Value::Number(asset.pof_post_action),
         );
         engine.set_variable("cof_total".to_string(), Value::Number(asset.cof_total_usd));
-        engine.set_variable("is_critical".to_string(), Value::Bool(asset.is_critical()));
+        engine.set_variable(
+            "is_critical".to_string(),
+            Value::Bool(asset.is_critical(&self.risk_levels)),
+        );
         engine.set_variable(
             "is_high_risk".to_string(),
-            Value::Bool(asset.is_high_risk()),
+            Value::Bool(asset.is_high_risk(&self.risk_levels)),
+        );
+        engine.set_variable(
+            "safety_weight".to_string(),
+            Value::Number(self.safety_weights.weight(&asset.safety_risk_level)),
+        );
+        engine.set_variable(
+            "monthly_rate".to_string(),
+            Value::Number(self.discount_rate / 12.0),
+        );
+        engine.set_variable("baseline_pof".to_string(), Value::Number(self.baseline_pof));
+        engine.set_variable(
+            "remaining_life_years".to_string(),
+            Value::Number(
+                asset
+                    .remaining_life_years
+                    .unwrap_or(DEFAULT_REMAINING_LIFE_YEARS),
+            ),
+        );
+        engine.set_variable("money_dp".to_string(), Value::Number(self.money_dp as f64));
+        engine.set_variable("score_dp".to_string(), Value::Number(self.score_dp as f64));
+        engine.set_variable(
+            "rating_dp".to_string(),
+            Value::Number(self.rating_dp as f64),
         );
     }
 
     /// Extract calculation results from the engine
-    fn extract_results(&self, engine: &Engine) -> Result<CalculationResults, Box<dyn Error>> {
+    /// Formulas absent from the repository are reported together, sorted by name
+    fn extract_results(
+        &self,
+        engine: &Engine,
+        asset: &Asset,
+    ) -> Result<CalculationResults, CapAllocError> {
+        let errors = engine.get_errors();
+        let mut missing: Vec<&str> = RESULT_FORMULAS
+            .into_iter()
+            .filter(|name| engine.get_result(name).is_none() && !errors.contains_key(*name))
+            .collect();
+        if !missing.is_empty() {
+            missing.sort_unstable();
+            return Err(CapAllocError::FormulaEval(format!(
+                "{} ({}): missing formulas: {}",
+                asset.asset_id,
+                asset.alternative_id,
+                missing.join(", ")
+            )));
+        }
+
+        let mut values = [0.0; RESULT_FORMULAS.len()];
+        for (value, name) in values.iter_mut().zip(RESULT_FORMULAS) {
+            *value = self.extract_number(engine, asset, name)?;
+        }
+        // Bound in `RESULT_FORMULAS` order
+        let [baseline_risk, post_action_risk, risk_reduction, roi, criticality_score, priority_score, cost_effectiveness, payback_period, adjusted_cost] =
+            values;
         Ok(CalculationResults {
-            baseline_risk: self.extract_number(engine, "baseline_risk")?,
-            post_action_risk: self.extract_number(engine, "post_action_risk")?,
-            risk_reduction: self.extract_number(engine, "risk_reduction")?,
-            roi: self.extract_number(engine, "roi")?,
-            criticality_score: self.extract_number(engine, "criticality_score")?,
-            priority_score: self.extract_number(engine, "priority_score")?,
-            cost_effectiveness: self.extract_number(engine, "cost_effectiveness")?,
-            payback_period: self.extract_number(engine, "payback_period")?,
+            baseline_risk,
+            post_action_risk,
+            risk_reduction,
+            roi,
+            criticality_score,
+            priority_score,
+            cost_effectiveness,
+            payback_period,
+            adjusted_cost,
         })
     }
 
-    fn extract_number(&self, engine: &Engine, name: &str) -> Result<f64, Box<dyn Error>> {
-        match engine
-            .get_result(name)
-            .ok_or(format!("{} not found", name))?
-        {
-            Value::Number(n) => Ok(n),
-            _ => Err(format!("{} is not a number", name).into()),
+    /// Extract a numeric formula output, reporting the asset and formula on failure
+    /// NaN and infinite outputs are rejected so they never reach sorting or the optimizer
+    fn extract_number(
+        &self,
+        engine: &Engine,
+        asset: &Asset,
+        name: &str,
+    ) -> Result<f64, CapAllocError> {
+        let context = format!("{} ({})", asset.asset_id, asset.alternative_id);
+        match engine.get_result(name) {
+            Some(Value::Number(n)) if n.is_finite() => Ok(n),
+            Some(Value::Number(n)) => Err(CapAllocError::FormulaEval(format!(
+                "{}: formula '{}' produced a non-finite value ({})",
+                context, name, n
+            ))),
+            Some(_) => Err(CapAllocError::FormulaEval(format!(
+                "{}: formula '{}' is not a number",
+                context, name
+            ))),
+            None => match engine.get_errors().get(name) {
+                Some(error) => Err(CapAllocError::FormulaEval(format!(
+                    "{}: formula '{}' failed: {}",
+                    context, name, error
+                ))),
+                None => Err(CapAllocError::FormulaEval(format!(
+                    "{}: formula '{}' not found",
+                    context, name
+                ))),
+            },
         }
     }
 }
 
+/// Formula outputs read by `extract_results`, in `CalculationResults` field order
+const RESULT_FORMULAS: [&str; 9] = [
+    "baseline_risk",
+    "post_action_risk",
+    "risk_reduction",
+    "roi",
+    "criticality_score",
+    "priority_score",
+    "cost_effectiveness",
+    "payback_period",
+    "adjusted_cost",
+];
+
 /// Internal struct for holding calculation results
 struct CalculationResults {
     baseline_risk: f64,
@@ -91,24 +314,41 @@ struct CalculationResults {
     priority_score: f64,
     cost_effectiveness: f64,
     payback_period: f64,
+    adjusted_cost: f64,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repository::InMemoryFormulaRepository;
+    use crate::repository::{InMemoryFormulaRepository, LayeredFormulaRepository};
+    use formcalc::{Formula, FormulaT};
 
-    fn create_test_asset() -> Asset {
-        Asset {
-            asset_id: "IT_SYSTEM_001".to_string(),
-            alternative_id: "Pilot_Program".to_string(),
-            cost_usd: 45000.0,
-            pof_post_action: 0.05,
-            cof_total_usd: 500000.0,
-            safety_risk_level: "Low".to_string(),
+    struct BrokenFormulaRepository;
+
+    impl FormulaRepository for BrokenFormulaRepository {
+        fn load_all(&self) -> Result<Vec<Formula>, CapAllocError> {
+            let mut formulas = InMemoryFormulaRepository::new().load_all()?;
+            formulas.retain(|f| f.name() != "roi");
+            formulas.push(Formula::new("roi", "return undefined_variable * 2"));
+            Ok(formulas)
         }
     }
 
+    /// Omits two formulas nothing else depends on
+    struct IncompleteFormulaRepository;
+
+    impl FormulaRepository for IncompleteFormulaRepository {
+        fn load_all(&self) -> Result<Vec<Formula>, CapAllocError> {
+            let mut formulas = InMemoryFormulaRepository::new().load_all()?;
+            formulas.retain(|f| f.name() != "payback_period" && f.name() != "cost_effectiveness");
+            Ok(formulas)
+        }
+    }
+
+    fn create_test_asset() -> Asset {
+        Asset::fixture("IT_SYSTEM_001", "Pilot_Program", 45000.0)
+    }
+
     #[test]
     fn test_calculate_risk_metrics() {
         let formula_repo = Box::new(InMemoryFormulaRepository::new());
@@ -155,6 +395,301 @@ mod tests {
         assert!(critical_result.post_action_risk > normal_result.post_action_risk);
     }
 
+    #[test]
+    fn test_safety_weight_scales_priority() {
+        let mut critical_asset = create_test_asset();
+        critical_asset.safety_risk_level = "Critical".to_string();
+
+        let default = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+            .calculate(&critical_asset)
+            .unwrap();
+        let weighted = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+            .with_safety_weights(SafetyWeights::default().with_weight("Critical", 2.0))
+            .calculate(&critical_asset)
+            .unwrap();
+
+        assert!(weighted.priority_score > default.priority_score);
+        assert!((weighted.priority_score - default.priority_score * 2.0).abs() < 0.001);
+        // Only the priority score is weighted
+        assert_eq!(weighted.risk_reduction, default.risk_reduction);
+        assert_eq!(weighted.cost_effectiveness, default.cost_effectiveness);
+    }
+
+    #[test]
+    fn test_priority_multiplier_matches_formula_factors() {
+        let service = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+            .with_safety_weights(SafetyWeights::default().with_weight("Critical", 2.0));
+        let plain = create_test_asset();
+        let critical = Asset {
+            safety_risk_level: "Critical".to_string(),
+            remaining_life_years: Some(5.0),
+            ..create_test_asset()
+        };
+
+        assert_eq!(service.priority_multiplier(&plain), 1.0);
+        // 1.3 critical boost, 2x safety weight, 1 + 0.5 * 0.5 urgency
+        assert!((service.priority_multiplier(&critical) - 3.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_lower_baseline_pof_reduces_risk_reduction() {
+        let asset = create_test_asset();
+
+        let default = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+            .calculate(&asset)
+            .unwrap();
+        let lower = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+            .with_baseline_pof(0.5)
+            .calculate(&asset)
+            .unwrap();
+
+        assert!((lower.baseline_risk - default.baseline_risk * 0.5).abs() < 0.01);
+        assert!(lower.risk_reduction < default.risk_reduction);
+        // Post-action risk does not depend on the do-nothing assumption
+        assert_eq!(lower.post_action_risk, default.post_action_risk);
+        assert!(
+            (default.risk_reduction - lower.risk_reduction - default.baseline_risk * 0.5).abs()
+                < 0.01
+        );
+    }
+
+    #[test]
+    fn test_non_finite_formula_output_is_rejected() {
+        let asset = create_test_asset();
+        let calculate = |name: &str, expression: &str| {
+            let formulas =
+                LayeredFormulaRepository::new(Box::new(InMemoryFormulaRepository::new()))
+                    .with_override(name, expression);
+            RiskCalculationService::new(Box::new(formulas)).calculate(&asset)
+        };
+
+        // exp(1000) overflows to infinity; infinity minus itself is NaN
+        for (name, expression) in [
+            ("roi", "return exp(1000)"),
+            ("criticality_score", "return exp(1000) - exp(1000)"),
+        ] {
+            let err = calculate(name, expression).unwrap_err();
+            assert!(matches!(err, CapAllocError::FormulaEval(_)));
+            let message = err.to_string();
+            assert!(
+                message.contains(&format!("formula '{}'", name)) && message.contains("non-finite"),
+                "unexpected error: {}",
+                message
+            );
+            assert!(message.contains("IT_SYSTEM_001"), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_short_remaining_life_raises_priority() {
+        let service = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()));
+        let with_life = |id: &str, cost: f64, years: Option<f64>| Asset {
+            remaining_life_years: years,
+            ..Asset::fixture(id, "Pilot_Program", cost)
+        };
+
+        // Missing life and life at or beyond the default horizon score the same
+        let missing = service.calculate(&with_life("A", 45000.0, None)).unwrap();
+        let long = service
+            .calculate(&with_life("A", 45000.0, Some(25.0)))
+            .unwrap();
+        let short = service
+            .calculate(&with_life("A", 45000.0, Some(2.0)))
+            .unwrap();
+        assert_eq!(long.priority_score, missing.priority_score);
+        assert!(short.priority_score > long.priority_score);
+        // Two years left of ten: 1 + 0.5 * 0.8 = 1.4x
+        assert!((short.priority_score / missing.priority_score - 1.4).abs() < 1e-3);
+        // Only priority changes; risk and ROI do not depend on remaining life
+        assert_eq!(short.risk_reduction, long.risk_reduction);
+        assert_eq!(short.roi, long.roi);
+
+        // The dearer alternative outranks the cheaper one once its asset is near end of life
+        let cheap = service
+            .calculate(&with_life("A", 45000.0, Some(20.0)))
+            .unwrap();
+        let dear_long = service
+            .calculate(&with_life("B", 90000.0, Some(20.0)))
+            .unwrap();
+        let dear_short = service
+            .calculate(&with_life("B", 90000.0, Some(1.0)))
+            .unwrap();
+        assert!(cheap.priority_score > dear_long.priority_score);
+        assert!(dear_short.priority_score > cheap.priority_score);
+    }
+
+    #[test]
+    fn test_pof_uncertainty_band_brackets_and_widens() {
+        let asset = create_test_asset();
+        let calculate = |percent: Option<f64>| {
+            let service = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()));
+            match percent {
+                Some(percent) => service.with_pof_uncertainty(percent),
+                None => service,
+            }
+            .calculate(&asset)
+            .unwrap()
+        };
+
+        let plain = calculate(None);
+        assert_eq!(plain.risk_reduction_low, None);
+        assert_eq!(plain.risk_reduction_high, None);
+
+        let narrow = calculate(Some(10.0));
+        let wide = calculate(Some(50.0));
+        assert_eq!(narrow.risk_reduction, plain.risk_reduction);
+        for result in [&narrow, &wide] {
+            let (low, high) = (
+                result.risk_reduction_low.unwrap(),
+                result.risk_reduction_high.unwrap(),
+            );
+            assert!(low < result.risk_reduction && result.risk_reduction < high);
+        }
+        assert!(wide.risk_reduction_low.unwrap() < narrow.risk_reduction_low.unwrap());
+        assert!(wide.risk_reduction_high.unwrap() > narrow.risk_reduction_high.unwrap());
+    }
+
+    #[test]
+    fn test_mixed_case_safety_level_matches_canonical() {
+        let formula_repo = Box::new(InMemoryFormulaRepository::new());
+        let service = RiskCalculationService::new(formula_repo);
+
+        let mut canonical = create_test_asset();
+        canonical.safety_risk_level = "Critical".to_string();
+        let mut messy = create_test_asset();
+        messy.safety_risk_level = " CRITICAL ".to_string();
+
+        let expected = service.calculate(&canonical).unwrap();
+        let actual = service.calculate(&messy).unwrap();
+        assert_eq!(actual.post_action_risk, expected.post_action_risk);
+        assert_eq!(actual.priority_score, expected.priority_score);
+    }
+
+    #[test]
+    fn test_custom_risk_labels_drive_formula_variables() {
+        let default_service =
+            RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()));
+        let custom_service =
+            RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+                .with_risk_levels(RiskLevelConfig::new(
+                    vec!["Alto".to_string()],
+                    vec!["Crítico".to_string()],
+                ));
+
+        for (english, local) in [("Critical", "Crítico"), ("High", "Alto"), ("Low", "Bajo")] {
+            let mut english_asset = create_test_asset();
+            english_asset.safety_risk_level = english.to_string();
+            let mut local_asset = create_test_asset();
+            local_asset.safety_risk_level = local.to_string();
+
+            let expected = default_service.calculate(&english_asset).unwrap();
+            let actual = custom_service.calculate(&local_asset).unwrap();
+
+            assert_eq!(actual.post_action_risk, expected.post_action_risk);
+            assert_eq!(actual.criticality_score, expected.criticality_score);
+            assert_eq!(actual.priority_score, expected.priority_score);
+        }
+    }
+
+    #[test]
+    fn test_higher_discount_rate_lowers_time_value_adjustment() {
+        let formulas = InMemoryFormulaRepository::new().load_all().unwrap();
+        let mut asset = create_test_asset();
+        asset.cost_usd = 450000.0;
+
+        let time_value_at = |annual_rate: f64| {
+            let service = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+                .with_discount_rate(annual_rate);
+            let mut engine = Engine::new();
+            service.set_variables(&mut engine, &asset);
+            engine.execute(formulas.clone()).unwrap();
+            match engine.get_result("time_value_adjustment") {
+                Some(Value::Number(n)) => n,
+                other => panic!("unexpected time_value_adjustment: {:?}", other),
+            }
+        };
+
+        let default = time_value_at(DEFAULT_DISCOUNT_RATE);
+        let higher = time_value_at(0.15);
+        assert!(higher < default, "{} !< {}", higher, default);
+        assert_eq!(time_value_at(0.0), 1.0);
+
+        // A cheaper present-value cost raises ROI for the same risk reduction
+        let default_roi = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+            .calculate(&asset)
+            .unwrap()
+            .roi
+            .unwrap();
+        let higher_roi = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+            .with_discount_rate(0.15)
+            .calculate(&asset)
+            .unwrap()
+            .roi
+            .unwrap();
+        assert!(higher_roi > default_roi);
+    }
+
+    #[test]
+    fn test_precision_changes_rounded_outputs() {
+        let mut asset = create_test_asset();
+        asset.cost_usd = 45678.9;
+        asset.pof_post_action = 0.0537;
+        let calculate = |money_dp: u32, score_dp: u32, rating_dp: u32| {
+            RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+                .with_precision(money_dp, score_dp, rating_dp)
+                .calculate(&asset)
+                .unwrap()
+        };
+
+        let default = calculate(DEFAULT_MONEY_DP, DEFAULT_SCORE_DP, DEFAULT_RATING_DP);
+        let whole = calculate(0, DEFAULT_SCORE_DP, DEFAULT_RATING_DP);
+        let precise = calculate(DEFAULT_MONEY_DP, 8, DEFAULT_RATING_DP);
+
+        assert_eq!(whole.post_action_risk, whole.post_action_risk.round());
+        assert_ne!(whole.post_action_risk, default.post_action_risk);
+        assert_ne!(precise.roi, default.roi);
+        assert_eq!(precise.risk_reduction, default.risk_reduction);
+        let roi = precise.roi.unwrap();
+        assert_eq!((roi * 1e4).round() / 1e4, default.roi.unwrap());
+
+        // Every rounded formula output follows its precision variable, including
+        // the intermediate factors and ratings that never reach the result
+        let service = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()))
+            .with_precision(0, 1, 0);
+        let mut engine = Engine::new();
+        service.set_variables(&mut engine, &asset);
+        engine
+            .execute(InMemoryFormulaRepository::new().load_all().unwrap())
+            .unwrap();
+        let places = [
+            ("baseline_risk", 0),
+            ("post_action_risk", 0),
+            ("risk_reduction", 0),
+            ("adjusted_cost", 0),
+            ("roi", 1),
+            ("priority_score", 1),
+            ("degradation_factor", 1),
+            ("time_value_adjustment", 1),
+            ("criticality_score", 0),
+            ("implementation_complexity", 0),
+            ("cost_effectiveness", 0),
+            ("payback_period", 0),
+        ];
+        for (name, dp) in places {
+            let value = match engine.get_result(name) {
+                Some(Value::Number(n)) => n,
+                other => panic!("unexpected {}: {:?}", name, other),
+            };
+            let scaled = value * 10f64.powi(dp);
+            assert!(
+                (scaled - scaled.round()).abs() < 1e-6,
+                "{}: {}",
+                name,
+                value
+            );
+        }
+    }
+
     #[test]
     fn test_roi_calculation() {
         let formula_repo = Box::new(InMemoryFormulaRepository::new());
@@ -165,8 +700,31 @@ mod tests {
 
         // ROI should be risk_reduction / adjusted_cost (which includes time value and complexity adjustments)
         // Just verify it's a positive number and reasonable
-        assert!(result.roi > 0.0);
-        assert!(result.roi < 1000.0); // Sanity check
+        let roi = result.roi.unwrap();
+        assert!(roi > 0.0);
+        assert!(roi < 1000.0); // Sanity check
+    }
+
+    #[test]
+    fn test_zero_cost_asset_has_undefined_roi() {
+        let service = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()));
+        let mut defer = create_test_asset();
+        defer.alternative_id = "Defer".to_string();
+        defer.cost_usd = 0.0;
+
+        let result = service.calculate(&defer).unwrap();
+
+        assert_eq!(result.roi, None);
+        assert_eq!(result.payback_period, Some(0.0));
+        assert!(result.risk_reduction > 0.0);
+
+        // No risk reduction at all leaves nothing to pay the cost back
+        let mut no_benefit = create_test_asset();
+        no_benefit.cof_total_usd = 0.0;
+        let result = service.calculate(&no_benefit).unwrap();
+        assert_eq!(result.risk_reduction, 0.0);
+        assert_eq!(result.payback_period, None);
+        assert!(result.roi.is_some());
     }
 
     #[test]
@@ -185,4 +743,157 @@ mod tests {
         assert!(low_result.post_action_risk < high_result.post_action_risk);
         assert!(low_result.risk_reduction > high_result.risk_reduction);
     }
+
+    #[test]
+    fn test_missing_formulas_are_reported_together() {
+        let service = RiskCalculationService::new(Box::new(IncompleteFormulaRepository));
+        let err = service.calculate(&create_test_asset()).unwrap_err();
+
+        assert!(matches!(err, CapAllocError::FormulaEval(_)));
+        assert!(
+            err.to_string()
+                .ends_with("missing formulas: cost_effectiveness, payback_period"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_error_reports_asset_and_formula() {
+        let service = RiskCalculationService::new(Box::new(BrokenFormulaRepository));
+        let asset = create_test_asset();
+
+        let err = service.calculate(&asset).unwrap_err();
+        assert!(matches!(err, CapAllocError::FormulaEval(_)));
+
+        let err = err.to_string();
+        assert!(err.contains("IT_SYSTEM_001"), "unexpected error: {}", err);
+        assert!(err.contains("Pilot_Program"), "unexpected error: {}", err);
+        assert!(err.contains("roi"), "unexpected error: {}", err);
+    }
+
+    /// Run every formula for `asset` and assert each output against `expected`
+    fn assert_golden_outputs(asset: &Asset, expected: &[(&str, f64)]) {
+        let service = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()));
+        let formulas = InMemoryFormulaRepository::new().load_all().unwrap();
+        assert_eq!(expected.len(), formulas.len());
+
+        let mut engine = Engine::new();
+        service.set_variables(&mut engine, asset);
+        engine.execute(formulas).unwrap();
+
+        for (name, value) in expected {
+            match engine.get_result(name) {
+                Some(Value::Number(n)) => assert!(
+                    (n - value).abs() < 1e-6,
+                    "{}: expected {}, got {}",
+                    name,
+                    value,
+                    n
+                ),
+                other => panic!("{}: unexpected output {:?}", name, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_golden_outputs_low_risk_asset() {
+        // cost 45,000, PoF 0.05, CoF 500,000, Low: no multipliers apply
+        assert_golden_outputs(
+            &create_test_asset(),
+            &[
+                ("baseline_risk", 500000.0),         // CoF <= 1M, unscaled
+                ("safety_multiplier", 1.0),          // neither high risk nor critical
+                ("criticality_score", 1.5),          // 0.05*10 + 500k/500k
+                ("degradation_factor", 0.9),         // 1 - 0.05*2
+                ("post_action_risk", 22500.0),       // 0.05 * 500k * 1.0 * 0.9
+                ("risk_reduction", 477500.0),        // 500k - 22.5k
+                ("implementation_complexity", 0.45), // 45k / 100k
+                ("time_value_adjustment", 0.9934),   // 1 / (1 + 0.08/12)^1
+                ("adjusted_cost", 45708.82),         // 45k * 1.0225 * 0.9934
+                ("roi", 10.4466),                    // 477.5k / 45,708.82
+                ("cost_effectiveness", 41.06),       // min(roi, 20)*3.5 + 1.5*3
+                ("priority_score", 0.5785),          // 0.4*0.4775 + 0.35*1 + 0.25*0.15
+                ("payback_period", 1.1),             // 45,708.82 / 477.5k * 12
+            ],
+        );
+    }
+
+    #[test]
+    fn test_golden_outputs_critical_asset() {
+        // cost 120,000, PoF 0.01, CoF 2,000,000, Critical: every critical branch applies
+        let asset = Asset {
+            pof_post_action: 0.01,
+            cof_total_usd: 2000000.0,
+            safety_risk_level: "Critical".to_string(),
+            ..Asset::fixture("SUBSTATION_003", "Full_Replacement", 120000.0)
+        };
+        assert_golden_outputs(
+            &asset,
+            &[
+                ("baseline_risk", 3297442.54),      // CoF > 1M, scaled by e^0.5
+                ("safety_multiplier", 1.502),       // 1.5 + 0.01*0.2
+                ("criticality_score", 6.15),        // (0.01*10 + 2M/500k) * 1.5
+                ("degradation_factor", 0.98),       // 1 - 0.01*2
+                ("post_action_risk", 29439.2),      // 0.01 * 2M * 1.502 * 0.98
+                ("risk_reduction", 3268003.34),     // 3,297,442.54 - 29,439.20
+                ("implementation_complexity", 2.4), // 120k / 100k * 2
+                ("time_value_adjustment", 0.9673),  // 1 / (1 + 0.08/12)^ceil(4.8)
+                ("adjusted_cost", 130005.12),       // 120k * 1.12 * 0.9673
+                ("roi", 25.1375),                   // 3,268,003.34 / 130,005.12
+                ("cost_effectiveness", 88.45),      // min(roi, 20)*3.5 + 6.15*3
+                ("priority_score", 2.3542),         // 1.3 * (0.4*3.268 + 0.35*1 + 0.25*0.615)
+                ("payback_period", 0.5),            // 130,005.12 / 3,268,003.34 * 12
+            ],
+        );
+    }
+
+    /// Timing comparison behind the per-asset `Engine::new()` in `calculate`
+    /// Run with `cargo test --release -- --ignored --nocapture engine_creation`
+    #[test]
+    #[ignore]
+    fn engine_creation_vs_reuse_timing() {
+        let formulas = InMemoryFormulaRepository::new().load_all().unwrap();
+        let service = RiskCalculationService::new(Box::new(InMemoryFormulaRepository::new()));
+        let assets: Vec<Asset> = (0..2000)
+            .map(|i| {
+                let mut asset = create_test_asset();
+                asset.cost_usd = 1000.0 + i as f64 * 37.0;
+                asset
+            })
+            .collect();
+
+        let start = Instant::now();
+        let fresh: Vec<Option<Value>> = assets
+            .iter()
+            .map(|asset| {
+                let mut engine = Engine::new();
+                service.set_variables(&mut engine, asset);
+                engine.execute(formulas.clone()).unwrap();
+                engine.get_result("priority_score")
+            })
+            .collect();
+        let fresh_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut engine = Engine::new();
+        let reused: Vec<Option<Value>> = assets
+            .iter()
+            .map(|asset| {
+                engine.clear();
+                service.set_variables(&mut engine, asset);
+                engine.execute(formulas.clone()).unwrap();
+                engine.get_result("priority_score")
+            })
+            .collect();
+        let reused_time = start.elapsed();
+
+        println!(
+            "fresh engine: {:?}, reused engine: {:?} ({} assets)",
+            fresh_time,
+            reused_time,
+            assets.len()
+        );
+        assert_eq!(fresh, reused);
+    }
 }
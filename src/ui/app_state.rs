@@ -1,18 +1,104 @@
-use crate::domain::RiskCalculationResult;
-use std::collections::HashSet;
-use std::time::Duration;
+use crate::domain::{RiskCalculationResult, RiskLevelConfig};
+use crate::error::CapAllocError;
+use crate::format::MoneyFormat;
+use crate::repository::CsvResultExporter;
+use crate::services::{
+    selection_overlap, OptimizationSolution, PortfolioOptimizer, SelectionOverlap,
+    DEFAULT_RATING_DP, DEFAULT_SCORE_DP,
+};
+use crate::ui::Theme;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long a status message stays in the list title
+const STATUS_DURATION: Duration = Duration::from_secs(3);
+
+/// Aggregate statistics over the whole dataset
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub total_assets: usize,
+    pub total_alternatives: usize,
+    pub safety_level_counts: BTreeMap<String, usize>,
+    pub mean_priority_score: f64,
+    pub median_priority_score: f64,
+    pub total_cost: f64,
+    /// Sum of the best risk reduction per asset (one alternative per asset)
+    pub achievable_risk_reduction: f64,
+}
+
+/// The three LP strategies the TUI shows side by side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strategy {
+    Risk,
+    Priority,
+    Combined,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Strategy::Risk, Strategy::Priority, Strategy::Combined];
+
+    /// Name shown in the TUI
+    pub fn label(self) -> &'static str {
+        match self {
+            Strategy::Risk => "Risk",
+            Strategy::Priority => "Priority",
+            Strategy::Combined => "Combined",
+        }
+    }
+
+    /// Key of this strategy's solution in the CLI output and `--export-solution`
+    pub fn key(self) -> &'static str {
+        match self {
+            Strategy::Risk => "risk_reduction",
+            Strategy::Priority => "priority",
+            Strategy::Combined => "combined",
+        }
+    }
+}
+
+/// Aggregate totals over the alternatives one strategy selected
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PortfolioTotals {
+    pub selected: usize,
+    pub total_cost: f64,
+    pub total_risk_reduction: f64,
+    pub total_priority_score: f64,
+}
 
 pub struct AppState {
     pub results: Vec<RiskCalculationResult>,
     pub display_order: Vec<usize>, // Indices into results for display order
+    pub base_order: Vec<usize>,    // Unfiltered display order
+    pub filter: String,
+    pub search_mode: bool,
     pub total_time: Duration,
     pub selected: usize, // Index into display_order
     pub expanded: bool,
+    pub show_summary: bool,
+    pub show_comparison: bool, // Compare all alternatives of the selected asset
+    pub show_histogram: bool,  // Priority score distribution instead of details
+    pub sort_by_cost_benefit: bool,
+    pub selected_only: bool, // Show only alternatives selected by any strategy
     pub optimization_budget: Option<f64>,
+    pub budget_input: Option<String>,
     // Three strategy results
     pub risk_selected: HashSet<String>, // Asset_ID (Alternative_ID)
     pub priority_selected: HashSet<String>,
     pub combined_selected: HashSet<String>,
+    // Solutions of the strategies that succeeded, to explain exclusions
+    pub solutions: BTreeMap<Strategy, OptimizationSolution>,
+    pub risk_weight: f64,
+    pub priority_weight: f64,
+    /// Do-nothing, category cap and timeout settings used when re-optimizing
+    pub optimizer: PortfolioOptimizer,
+    pub risk_levels: RiskLevelConfig,
+    pub money_format: MoneyFormat,
+    /// Decimal places for scores and ratings, as in `--score-dp` and `--rating-dp`
+    pub score_dp: u32,
+    pub rating_dp: u32,
+    pub theme: Theme,
+    pub status: Option<(String, Instant)>, // Message and when it was set
 }
 
 impl AppState {
@@ -20,17 +106,90 @@ impl AppState {
         let display_order: Vec<usize> = (0..results.len()).collect();
         Self {
             results,
+            base_order: display_order.clone(),
             display_order,
+            filter: String::new(),
+            search_mode: false,
             total_time,
             selected: 0,
             expanded: false,
+            show_summary: false,
+            show_comparison: false,
+            show_histogram: false,
+            sort_by_cost_benefit: false,
+            selected_only: false,
             optimization_budget: None,
+            budget_input: None,
             risk_selected: HashSet::new(),
             priority_selected: HashSet::new(),
             combined_selected: HashSet::new(),
+            solutions: BTreeMap::new(),
+            risk_weight: 0.6,
+            priority_weight: 0.4,
+            optimizer: PortfolioOptimizer::new(),
+            risk_levels: RiskLevelConfig::default(),
+            money_format: MoneyFormat::default(),
+            score_dp: DEFAULT_SCORE_DP,
+            rating_dp: DEFAULT_RATING_DP,
+            theme: Theme::default(),
+            status: None,
         }
     }
 
+    /// Set the separators and symbol used to render money amounts
+    pub fn with_money_format(mut self, money_format: MoneyFormat) -> Self {
+        self.money_format = money_format;
+        self
+    }
+
+    /// Set the decimal places scores and ratings are shown to; payback months
+    /// use one place fewer than ratings, as in the formulas
+    pub fn with_precision(mut self, score_dp: u32, rating_dp: u32) -> Self {
+        self.score_dp = score_dp;
+        self.rating_dp = rating_dp;
+        self
+    }
+
+    /// ROI, priority and other ratios to `score_dp` places
+    pub fn score_text(&self, value: f64) -> String {
+        format!("{:.*}", self.score_dp as usize, value)
+    }
+
+    /// Criticality and cost effectiveness to `rating_dp` places
+    pub fn rating_text(&self, value: f64) -> String {
+        format!("{:.*}", self.rating_dp as usize, value)
+    }
+
+    /// Payback months to one place fewer than ratings
+    pub fn months_text(&self, value: f64) -> String {
+        format!("{:.*}", self.rating_dp.saturating_sub(1) as usize, value)
+    }
+
+    /// Set the styles used to render the TUI
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set the weights used by the combined strategy when re-optimizing
+    pub fn with_combined_weights(mut self, risk_weight: f64, priority_weight: f64) -> Self {
+        self.risk_weight = risk_weight;
+        self.priority_weight = priority_weight;
+        self
+    }
+
+    /// Set which safety level labels are highlighted as high risk and critical
+    pub fn with_risk_levels(mut self, risk_levels: RiskLevelConfig) -> Self {
+        self.risk_levels = risk_levels;
+        self
+    }
+
+    /// Re-optimize with the same settings as the CLI strategies
+    pub fn with_optimizer(mut self, optimizer: PortfolioOptimizer) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+
     pub fn with_optimization(
         results: Vec<RiskCalculationResult>,
         total_time: Duration,
@@ -39,79 +198,256 @@ impl AppState {
         combined_alternatives: Vec<String>,
         budget: f64,
     ) -> Self {
-        let risk_set: HashSet<String> = risk_alternatives.into_iter().collect();
-        let priority_set: HashSet<String> = priority_alternatives.into_iter().collect();
-        let combined_set: HashSet<String> = combined_alternatives.into_iter().collect();
+        let mut state = Self::new(results, total_time);
+        state.optimization_budget = Some(budget);
+        state.apply_selections(
+            risk_alternatives,
+            priority_alternatives,
+            combined_alternatives,
+        );
+        state
+    }
+
+    /// Keep the strategy solutions behind the selections, so the expanded view
+    /// can explain why an alternative was left out
+    pub fn with_solutions(mut self, solutions: BTreeMap<Strategy, OptimizationSolution>) -> Self {
+        self.solutions = solutions;
+        self
+    }
 
-        // Sort display order: alternatives selected by any strategy first
+    /// Replace the three strategy selections and re-sort so that
+    /// alternatives selected by any strategy are listed first
+    fn apply_selections(
+        &mut self,
+        risk_alternatives: Vec<String>,
+        priority_alternatives: Vec<String>,
+        combined_alternatives: Vec<String>,
+    ) {
+        self.risk_selected = risk_alternatives.into_iter().collect();
+        self.priority_selected = priority_alternatives.into_iter().collect();
+        self.combined_selected = combined_alternatives.into_iter().collect();
+        self.rebuild_order();
+    }
+
+    /// Recompute the unfiltered order and reapply the current filter
+    /// Default order lists alternatives selected by any strategy first;
+    /// the cost-benefit sort ranks by raw risk reduction per dollar instead
+    fn rebuild_order(&mut self) {
+        let results = &self.results;
         let mut display_order: Vec<usize> = (0..results.len()).collect();
-        display_order.sort_by(|&a, &b| {
-            let key_a = format!(
-                "{} ({})",
-                results[a].asset.asset_id, results[a].asset.alternative_id
-            );
-            let key_b = format!(
-                "{} ({})",
-                results[b].asset.asset_id, results[b].asset.alternative_id
-            );
-            let a_selected = risk_set.contains(&key_a)
-                || priority_set.contains(&key_a)
-                || combined_set.contains(&key_a);
-            let b_selected = risk_set.contains(&key_b)
-                || priority_set.contains(&key_b)
-                || combined_set.contains(&key_b);
-
-            match (a_selected, b_selected) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => a.cmp(&b),
-            }
+        if self.sort_by_cost_benefit {
+            display_order.sort_by(|&a, &b| {
+                results[b]
+                    .cost_benefit_ratio()
+                    .total_cmp(&results[a].cost_benefit_ratio())
+            });
+        } else {
+            display_order.sort_by(|&a, &b| {
+                let a_selected = self.is_selected_by_any(&results[a]);
+                let b_selected = self.is_selected_by_any(&results[b]);
+
+                match (a_selected, b_selected) {
+                    (true, false) => std::cmp::Ordering::Less,
+                    (false, true) => std::cmp::Ordering::Greater,
+                    _ => a.cmp(&b),
+                }
+            });
+        }
+
+        self.base_order = display_order;
+
+        let filter = self.filter.clone();
+        self.apply_filter(&filter);
+    }
+
+    /// Switch between the default order and ranking by raw risk reduction per dollar
+    pub fn toggle_cost_benefit_sort(&mut self) {
+        self.sort_by_cost_benefit = !self.sort_by_cost_benefit;
+        self.rebuild_order();
+    }
+
+    /// Switch between all alternatives and only those selected by any strategy
+    pub fn toggle_selected_only(&mut self) {
+        self.selected_only = !self.selected_only;
+        let filter = self.filter.clone();
+        self.apply_filter(&filter);
+    }
+
+    /// Re-run all three optimization strategies against a new budget; strategies
+    /// that fail are named with their error in the status line
+    pub fn reoptimize(&mut self, budget: f64) {
+        let optimizer = &self.optimizer;
+        let mut failures = Vec::new();
+        self.solutions = Strategy::ALL
+            .into_iter()
+            .filter_map(|strategy| {
+                let solution = match strategy {
+                    Strategy::Risk => optimizer.optimize_by_risk_reduction(&self.results, budget),
+                    Strategy::Priority => optimizer.optimize_by_priority(&self.results, budget),
+                    Strategy::Combined => optimizer.optimize_combined(
+                        &self.results,
+                        budget,
+                        self.risk_weight,
+                        self.priority_weight,
+                    ),
+                };
+                match solution {
+                    Ok(solution) => Some((strategy, solution)),
+                    Err(e) => {
+                        failures.push(format!("{}: {}", strategy.label(), e));
+                        None
+                    }
+                }
+            })
+            .collect();
+        if !failures.is_empty() {
+            self.set_status(format!("Optimization failed: {}", failures.join("; ")));
+        }
+        let [risk, priority, combined] = Strategy::ALL.map(|strategy| {
+            self.solutions
+                .get(&strategy)
+                .map(|s| s.selected_alternatives.clone())
+                .unwrap_or_default()
         });
 
-        Self {
-            results,
-            display_order,
-            total_time,
-            selected: 0,
-            expanded: false,
-            optimization_budget: Some(budget),
-            risk_selected: risk_set,
-            priority_selected: priority_set,
-            combined_selected: combined_set,
+        self.optimization_budget = Some(budget);
+        self.apply_selections(risk, priority, combined);
+    }
+
+    /// Write every result to `capalloc_export_<timestamp>.csv` in `dir`, with a
+    /// `selected_by_*` column per strategy once optimized; returns the path written
+    /// An existing file of that name is left alone and reported as an error
+    pub fn export_csv(&self, dir: &Path, timestamp: u64) -> Result<PathBuf, CapAllocError> {
+        let path = dir.join(format!("capalloc_export_{}.csv", timestamp));
+        if path.exists() {
+            return Err(CapAllocError::Validation(format!(
+                "{} already exists",
+                path.display()
+            )));
+        }
+        let mut exporter = CsvResultExporter::new(path.to_string_lossy().into_owned());
+        if self.optimization_budget.is_some() {
+            exporter = exporter
+                .with_selection("risk", self.risk_selected.clone())
+                .with_selection("priority", self.priority_selected.clone())
+                .with_selection("combined", self.combined_selected.clone());
+        }
+        exporter.export(&self.results)?;
+        Ok(path)
+    }
+
+    /// Export to the working directory, stamped with the current Unix time in
+    /// milliseconds so repeated exports get their own files, and flash the
+    /// outcome in the status line
+    pub fn export_now(&mut self) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let message = match self.export_csv(Path::new(""), timestamp) {
+            Ok(path) => format!(
+                "Exported {} results to {}",
+                self.results.len(),
+                path.display()
+            ),
+            Err(e) => format!("Export failed: {}", e),
+        };
+        self.set_status(message);
+    }
+
+    /// Show `message` in the status line for `STATUS_DURATION`
+    fn set_status(&mut self, message: String) {
+        self.status = Some((message, Instant::now()));
+    }
+
+    /// The status message, until it has been shown for `STATUS_DURATION`
+    pub fn status_message(&self) -> Option<&str> {
+        self.status
+            .as_ref()
+            .filter(|(_, set_at)| set_at.elapsed() < STATUS_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    pub fn start_budget_input(&mut self) {
+        self.budget_input = Some(String::new());
+    }
+
+    pub fn push_budget_char(&mut self, ch: char) {
+        if let Some(input) = self.budget_input.as_mut() {
+            if ch.is_ascii_digit() || (ch == '.' && !input.contains('.')) {
+                input.push(ch);
+            }
+        }
+    }
+
+    pub fn pop_budget_char(&mut self) {
+        if let Some(input) = self.budget_input.as_mut() {
+            input.pop();
+        }
+    }
+
+    pub fn cancel_budget_input(&mut self) {
+        self.budget_input = None;
+    }
+
+    /// Apply the typed budget and re-optimize; input that is not a number is
+    /// discarded and reported in the status line
+    pub fn submit_budget_input(&mut self) {
+        if let Some(input) = self.budget_input.take() {
+            match input.parse::<f64>() {
+                Ok(budget) => self.reoptimize(budget),
+                Err(_) => self.set_status(format!("Invalid budget '{}'", input)),
+            }
         }
     }
 
     pub fn is_selected_by_risk(&self, result: &RiskCalculationResult) -> bool {
-        let key = format!(
-            "{} ({})",
-            result.asset.asset_id, result.asset.alternative_id
-        );
-        self.risk_selected.contains(&key)
+        self.risk_selected.contains(&result.selection_key())
     }
 
     pub fn is_selected_by_priority(&self, result: &RiskCalculationResult) -> bool {
-        let key = format!(
-            "{} ({})",
-            result.asset.asset_id, result.asset.alternative_id
-        );
-        self.priority_selected.contains(&key)
+        self.priority_selected.contains(&result.selection_key())
     }
 
     pub fn is_selected_by_combined(&self, result: &RiskCalculationResult) -> bool {
-        let key = format!(
-            "{} ({})",
-            result.asset.asset_id, result.asset.alternative_id
-        );
-        self.combined_selected.contains(&key)
+        self.combined_selected.contains(&result.selection_key())
+    }
+
+    pub fn is_selected_by(&self, strategy: Strategy, result: &RiskCalculationResult) -> bool {
+        match strategy {
+            Strategy::Risk => self.is_selected_by_risk(result),
+            Strategy::Priority => self.is_selected_by_priority(result),
+            Strategy::Combined => self.is_selected_by_combined(result),
+        }
     }
 
-    #[allow(dead_code)]
     pub fn is_selected_by_any(&self, result: &RiskCalculationResult) -> bool {
         self.is_selected_by_risk(result)
             || self.is_selected_by_priority(result)
             || self.is_selected_by_combined(result)
     }
 
+    /// Why each strategy with a known solution left `result` out, in strategy
+    /// order; empty before optimization
+    pub fn exclusion_reasons(&self, result: &RiskCalculationResult) -> Vec<(Strategy, String)> {
+        let Some(budget) = self.optimization_budget else {
+            return Vec::new();
+        };
+        self.solutions
+            .iter()
+            .filter(|(&strategy, _)| !self.is_selected_by(strategy, result))
+            .map(|(&strategy, solution)| {
+                let reason = self.optimizer.explain_exclusion(
+                    result,
+                    &self.results,
+                    solution,
+                    budget,
+                    &self.money_format,
+                );
+                (strategy, reason)
+            })
+            .collect()
+    }
+
     pub fn select_next(&mut self) {
         if self.selected < self.display_order.len().saturating_sub(1) {
             self.selected += 1;
@@ -124,6 +460,23 @@ impl AppState {
         }
     }
 
+    pub fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub fn select_last(&mut self) {
+        self.selected = self.display_order.len().saturating_sub(1);
+    }
+
+    /// 1-based position of the selection among the displayed rows, e.g. "120 / 4000"
+    pub fn position_label(&self) -> String {
+        if self.display_order.is_empty() {
+            "0 / 0".to_string()
+        } else {
+            format!("{} / {}", self.selected + 1, self.display_order.len())
+        }
+    }
+
     pub fn page_down(&mut self, page_size: usize) {
         self.selected = (self.selected + page_size).min(self.display_order.len().saturating_sub(1));
     }
@@ -136,9 +489,587 @@ impl AppState {
         self.expanded = !self.expanded;
     }
 
+    pub fn toggle_summary(&mut self) {
+        self.show_summary = !self.show_summary;
+    }
+
+    pub fn toggle_comparison(&mut self) {
+        self.show_comparison = !self.show_comparison;
+    }
+
+    pub fn toggle_histogram(&mut self) {
+        self.show_histogram = !self.show_histogram;
+    }
+
+    /// Every alternative of `asset_id`, cheapest first (ties by alternative ID)
+    /// Ignores the search filter, so the comparison is always complete
+    pub fn alternatives_for(&self, asset_id: &str) -> Vec<&RiskCalculationResult> {
+        let mut alternatives: Vec<&RiskCalculationResult> = self
+            .results
+            .iter()
+            .filter(|r| r.asset.asset_id == asset_id)
+            .collect();
+        alternatives.sort_by(|a, b| {
+            a.asset
+                .cost_usd
+                .total_cmp(&b.asset.cost_usd)
+                .then_with(|| a.asset.alternative_id.cmp(&b.asset.alternative_id))
+        });
+        alternatives
+    }
+
+    /// Totals for each strategy, in strategy order
+    pub fn portfolio_totals(&self) -> [(Strategy, PortfolioTotals); 3] {
+        Strategy::ALL.map(|strategy| {
+            (
+                strategy,
+                self.totals_where(|r| self.is_selected_by(strategy, r)),
+            )
+        })
+    }
+
+    /// How far the risk, priority and combined selections agree, in that order
+    pub fn selection_overlap(&self) -> SelectionOverlap {
+        selection_overlap(
+            &self.risk_selected,
+            &self.priority_selected,
+            &self.combined_selected,
+        )
+    }
+
+    fn totals_where(&self, selected: impl Fn(&RiskCalculationResult) -> bool) -> PortfolioTotals {
+        self.results
+            .iter()
+            .filter(|r| selected(r))
+            .fold(PortfolioTotals::default(), |totals, r| PortfolioTotals {
+                selected: totals.selected + 1,
+                total_cost: totals.total_cost + r.asset.cost_usd,
+                total_risk_reduction: totals.total_risk_reduction + r.risk_reduction,
+                total_priority_score: totals.total_priority_score + r.effective_priority(),
+            })
+    }
+
+    /// Compute aggregate statistics over all results
+    pub fn summary(&self) -> Summary {
+        let mut safety_level_counts: BTreeMap<String, usize> = BTreeMap::new();
+        let mut best_reduction: HashMap<&str, f64> = HashMap::new();
+        let mut priorities: Vec<f64> = Vec::with_capacity(self.results.len());
+        let mut total_cost = 0.0;
+
+        for result in &self.results {
+            *safety_level_counts
+                .entry(result.asset.safety_risk_level.clone())
+                .or_default() += 1;
+            let best = best_reduction
+                .entry(result.asset.asset_id.as_str())
+                .or_insert(0.0);
+            *best = best.max(result.risk_reduction);
+            priorities.push(result.effective_priority());
+            total_cost += result.asset.cost_usd;
+        }
+
+        priorities.sort_by(|a, b| a.total_cmp(b));
+        let mean_priority_score = if priorities.is_empty() {
+            0.0
+        } else {
+            priorities.iter().sum::<f64>() / priorities.len() as f64
+        };
+        let median_priority_score = match priorities.len() {
+            0 => 0.0,
+            n if n % 2 == 0 => (priorities[n / 2 - 1] + priorities[n / 2]) / 2.0,
+            n => priorities[n / 2],
+        };
+
+        Summary {
+            total_assets: best_reduction.len(),
+            total_alternatives: self.results.len(),
+            safety_level_counts,
+            mean_priority_score,
+            median_priority_score,
+            total_cost,
+            achievable_risk_reduction: best_reduction.values().sum(),
+        }
+    }
+
+    /// Restrict the display order to results whose asset or alternative ID
+    /// contains the query (case-insensitive). An empty query shows everything.
+    pub fn apply_filter(&mut self, query: &str) {
+        self.filter = query.to_string();
+        let query = query.to_lowercase();
+        self.display_order = self
+            .base_order
+            .iter()
+            .copied()
+            .filter(|&idx| !self.selected_only || self.is_selected_by_any(&self.results[idx]))
+            .filter(|&idx| {
+                let asset = &self.results[idx].asset;
+                query.is_empty()
+                    || asset.asset_id.to_lowercase().contains(&query)
+                    || asset.alternative_id.to_lowercase().contains(&query)
+            })
+            .collect();
+        self.selected = 0;
+    }
+
+    pub fn start_search(&mut self) {
+        self.search_mode = true;
+    }
+
+    pub fn push_search_char(&mut self, ch: char) {
+        let mut query = self.filter.clone();
+        query.push(ch);
+        self.apply_filter(&query);
+    }
+
+    pub fn pop_search_char(&mut self) {
+        let mut query = self.filter.clone();
+        query.pop();
+        self.apply_filter(&query);
+    }
+
+    /// Leave search mode, keeping the current filter applied
+    pub fn finish_search(&mut self) {
+        self.search_mode = false;
+    }
+
+    /// Leave search mode and clear the filter
+    pub fn clear_search(&mut self) {
+        self.search_mode = false;
+        self.apply_filter("");
+    }
+
     pub fn get_selected(&self) -> Option<&RiskCalculationResult> {
         self.display_order
             .get(self.selected)
             .and_then(|&idx| self.results.get(idx))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Asset;
+
+    fn create_test_result(asset_id: &str, alternative: &str) -> RiskCalculationResult {
+        create_scored_result(asset_id, alternative, "Low", 45000.0, 475000.0, 0.75)
+    }
+
+    fn create_scored_result(
+        asset_id: &str,
+        alternative: &str,
+        safety_level: &str,
+        cost: f64,
+        risk_reduction: f64,
+        priority: f64,
+    ) -> RiskCalculationResult {
+        RiskCalculationResult::new(
+            Asset {
+                safety_risk_level: safety_level.to_string(),
+                ..Asset::fixture(asset_id, alternative, cost)
+            },
+            500000.0,
+            25000.0,
+            risk_reduction,
+            Some(10.5),
+            1.0,
+            priority,
+            85.0,
+            Some(1.2),
+            0.5,
+        )
+    }
+
+    fn create_test_state() -> AppState {
+        AppState::new(
+            vec![
+                create_test_result("IT_SYSTEM_001", "Pilot_Program"),
+                create_test_result("IT_SYSTEM_001", "Full_Implementation"),
+                create_test_result("DATACENTER_002", "Pilot_Program"),
+                create_test_result("CLOUD_MIGRATION_003", "Defer"),
+            ],
+            Duration::from_millis(10),
+        )
+    }
+
+    #[test]
+    fn test_apply_filter_matches_asset_and_alternative() {
+        let mut state = create_test_state();
+
+        state.apply_filter("it_system");
+        assert_eq!(state.display_order, vec![0, 1]);
+
+        state.apply_filter("PILOT");
+        assert_eq!(state.display_order, vec![0, 2]);
+
+        state.apply_filter("");
+        assert_eq!(state.display_order, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_navigation_within_filtered_subset() {
+        let mut state = create_test_state();
+        state.select_next();
+        state.apply_filter("pilot");
+
+        assert_eq!(state.selected, 0);
+        state.select_next();
+        state.select_next();
+        assert_eq!(state.selected, 1);
+        assert_eq!(
+            state.get_selected().unwrap().asset.asset_id,
+            "DATACENTER_002"
+        );
+    }
+
+    #[test]
+    fn test_clear_search_restores_all_results() {
+        let mut state = create_test_state();
+        state.start_search();
+        for ch in "defer".chars() {
+            state.push_search_char(ch);
+        }
+        assert_eq!(state.display_order, vec![3]);
+
+        state.pop_search_char();
+        assert_eq!(state.filter, "defe");
+
+        state.clear_search();
+        assert!(!state.search_mode);
+        assert!(state.filter.is_empty());
+        assert_eq!(state.display_order.len(), 4);
+    }
+
+    #[test]
+    fn test_select_first_and_last() {
+        let mut state = create_test_state();
+        state.select_last();
+        assert_eq!(state.selected, 3);
+        state.select_first();
+        assert_eq!(state.selected, 0);
+
+        state.apply_filter("no such asset");
+        state.select_last();
+        assert_eq!(state.selected, 0);
+    }
+
+    #[test]
+    fn test_toggle_selected_only() {
+        let mut state = create_test_state();
+        state.apply_selections(
+            vec!["DATACENTER_002 (Pilot_Program)".to_string()],
+            vec!["IT_SYSTEM_001 (Full_Implementation)".to_string()],
+            vec![],
+        );
+        let all = state.display_order.clone();
+
+        state.toggle_selected_only();
+        let shown: Vec<String> = state
+            .display_order
+            .iter()
+            .map(|&idx| state.results[idx].asset.asset_id.clone())
+            .collect();
+        assert_eq!(shown, vec!["IT_SYSTEM_001", "DATACENTER_002"]);
+
+        // Combines with the search filter
+        state.apply_filter("datacenter");
+        assert_eq!(state.display_order.len(), 1);
+        state.apply_filter("");
+
+        state.toggle_selected_only();
+        assert_eq!(state.display_order, all);
+    }
+
+    #[test]
+    fn test_alternatives_for_groups_by_asset() {
+        let mut state = AppState::new(
+            vec![
+                create_scored_result("IT_SYSTEM_001", "Full", "Low", 120000.0, 450000.0, 0.9),
+                create_scored_result("DATACENTER_002", "Pilot", "Low", 20000.0, 90000.0, 0.4),
+                create_scored_result("IT_SYSTEM_001", "Defer", "Low", 0.0, 0.0, 0.1),
+                create_scored_result("IT_SYSTEM_001", "Pilot", "Low", 45000.0, 300000.0, 0.6),
+            ],
+            Duration::from_millis(10),
+        );
+        state.apply_filter("DATACENTER");
+
+        let alternatives: Vec<&str> = state
+            .alternatives_for("IT_SYSTEM_001")
+            .iter()
+            .map(|r| r.asset.alternative_id.as_str())
+            .collect();
+        assert_eq!(alternatives, vec!["Defer", "Pilot", "Full"]);
+        assert_eq!(state.alternatives_for("DATACENTER_002").len(), 1);
+        assert!(state.alternatives_for("UNKNOWN").is_empty());
+    }
+
+    #[test]
+    fn test_summary_aggregates() {
+        let state = AppState::new(
+            vec![
+                create_scored_result("IT_SYSTEM_001", "Pilot", "Low", 10000.0, 50000.0, 0.2),
+                create_scored_result("IT_SYSTEM_001", "Full", "Low", 40000.0, 90000.0, 0.6),
+                create_scored_result("DATACENTER_002", "Pilot", "Critical", 5000.0, 30000.0, 0.4),
+                create_scored_result("DATACENTER_002", "Full", "High", 20000.0, 70000.0, 1.0),
+            ],
+            Duration::from_millis(10),
+        );
+
+        let summary = state.summary();
+
+        assert_eq!(summary.total_assets, 2);
+        assert_eq!(summary.total_alternatives, 4);
+        assert_eq!(summary.safety_level_counts["Low"], 2);
+        assert_eq!(summary.safety_level_counts["Critical"], 1);
+        assert_eq!(summary.safety_level_counts["High"], 1);
+        assert!((summary.mean_priority_score - 0.55).abs() < 1e-9);
+        assert!((summary.median_priority_score - 0.5).abs() < 1e-9);
+        assert_eq!(summary.total_cost, 75000.0);
+        assert_eq!(summary.achievable_risk_reduction, 160000.0);
+    }
+
+    #[test]
+    fn test_portfolio_totals_per_strategy() {
+        let state = AppState::with_optimization(
+            vec![
+                create_scored_result("IT_SYSTEM_001", "Pilot", "Low", 10000.0, 50000.0, 0.2),
+                create_scored_result("IT_SYSTEM_001", "Full", "Low", 40000.0, 90000.0, 0.6),
+                create_scored_result("DATACENTER_002", "Full", "High", 20000.0, 70000.0, 1.0),
+            ],
+            Duration::from_millis(10),
+            vec![
+                "IT_SYSTEM_001 (Full)".to_string(),
+                "DATACENTER_002 (Full)".to_string(),
+            ],
+            vec!["DATACENTER_002 (Full)".to_string()],
+            vec![],
+            60000.0,
+        );
+
+        let [(risk_strategy, risk), (_, priority), (_, combined)] = state.portfolio_totals();
+
+        assert_eq!(risk_strategy, Strategy::Risk);
+        assert_eq!(risk_strategy.label(), "Risk");
+        assert_eq!(risk.selected, 2);
+        assert_eq!(risk.total_cost, 60000.0);
+        assert_eq!(risk.total_risk_reduction, 160000.0);
+        assert!((risk.total_priority_score - 1.6).abs() < 1e-9);
+        assert_eq!(priority.selected, 1);
+        assert_eq!(priority.total_cost, 20000.0);
+        assert_eq!(combined, PortfolioTotals::default());
+
+        let overlap = state.selection_overlap();
+        assert_eq!((overlap.first_second, overlap.first_only), (1, 1));
+        assert_eq!(overlap.all_three, 0);
+    }
+
+    #[test]
+    fn test_summary_empty() {
+        let state = AppState::new(Vec::new(), Duration::from_millis(0));
+        let summary = state.summary();
+
+        assert_eq!(summary.total_assets, 0);
+        assert_eq!(summary.mean_priority_score, 0.0);
+        assert_eq!(summary.median_priority_score, 0.0);
+    }
+
+    #[test]
+    fn test_reoptimize_recomputes_selection_and_order() {
+        let mut state = AppState::new(
+            vec![
+                create_scored_result("IT_SYSTEM_001", "Pilot", "Low", 10000.0, 50000.0, 0.2),
+                create_scored_result("DATACENTER_002", "Full", "High", 10000.0, 90000.0, 1.0),
+            ],
+            Duration::from_millis(10),
+        );
+
+        state.reoptimize(0.0);
+        assert_eq!(state.optimization_budget, Some(0.0));
+        assert!(state.risk_selected.is_empty());
+        assert_eq!(state.display_order, vec![0, 1]);
+
+        state.start_budget_input();
+        for ch in "10000".chars() {
+            state.push_budget_char(ch);
+        }
+        state.submit_budget_input();
+        assert_eq!(state.budget_input, None);
+        assert_eq!(state.optimization_budget, Some(10000.0));
+        assert!(!state.is_selected_by_risk(&state.results[0]));
+        assert!(state.is_selected_by_risk(&state.results[1]));
+        assert_eq!(state.display_order, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_exclusion_reasons_after_reoptimize() {
+        let mut state = AppState::new(
+            vec![
+                create_scored_result("IT_SYSTEM_001", "Pilot", "Low", 10000.0, 50000.0, 0.2),
+                create_scored_result("IT_SYSTEM_001", "Full", "Low", 15000.0, 60000.0, 0.3),
+                create_scored_result("DATACENTER_002", "Full", "High", 10000.0, 90000.0, 1.0),
+            ],
+            Duration::from_millis(10),
+        );
+        assert!(state.exclusion_reasons(&state.results[0]).is_empty());
+
+        state.reoptimize(20000.0);
+        assert!(state.exclusion_reasons(&state.results[2]).is_empty());
+
+        let reasons = state.exclusion_reasons(&state.results[1]);
+        let strategies: Vec<Strategy> = reasons.iter().map(|(strategy, _)| *strategy).collect();
+        assert_eq!(strategies, Strategy::ALL);
+        let labels: Vec<&str> = strategies.iter().map(|s| s.label()).collect();
+        assert_eq!(labels, vec!["Risk", "Priority", "Combined"]);
+        for (_, reason) in &reasons {
+            assert!(reason.contains("another alternative of this asset (Pilot)"));
+        }
+    }
+
+    #[test]
+    fn test_budget_input_ignores_non_numeric() {
+        let mut state = create_test_state();
+        state.start_budget_input();
+        for ch in "1a2.5.0".chars() {
+            state.push_budget_char(ch);
+        }
+        assert_eq!(state.budget_input.as_deref(), Some("12.50"));
+
+        state.cancel_budget_input();
+        assert_eq!(state.budget_input, None);
+        assert_eq!(state.optimization_budget, None);
+
+        state.start_budget_input();
+        state.push_budget_char('.');
+        state.submit_budget_input();
+        assert_eq!(state.budget_input, None);
+        assert_eq!(state.optimization_budget, None);
+        assert_eq!(state.status_message(), Some("Invalid budget '.'"));
+    }
+
+    #[test]
+    fn test_precision_sets_displayed_places() {
+        let state = create_test_state();
+        assert_eq!(state.score_text(0.123456), "0.1235");
+        assert_eq!(state.rating_text(7.456), "7.46");
+        assert_eq!(state.months_text(12.44), "12.4");
+
+        let state = create_test_state().with_precision(2, 0);
+        assert_eq!(state.score_text(0.123456), "0.12");
+        assert_eq!(state.rating_text(7.6), "8");
+        assert_eq!(state.months_text(12.4), "12");
+    }
+
+    #[test]
+    fn test_reoptimize_reports_failed_strategies() {
+        let mut state = create_test_state();
+        state.risk_weight = 0.0;
+        state.priority_weight = 0.0;
+
+        state.reoptimize(100000.0);
+
+        assert!(state.solutions.contains_key(&Strategy::Risk));
+        assert!(!state.solutions.contains_key(&Strategy::Combined));
+        let status = state.status_message().unwrap();
+        assert!(
+            status.starts_with("Optimization failed: Combined: "),
+            "{}",
+            status
+        );
+    }
+
+    #[test]
+    fn test_toggle_cost_benefit_sort() {
+        let mut state = AppState::new(
+            vec![
+                create_scored_result("IT_SYSTEM_001", "Defer", "High", 0.0, 0.0, 0.1),
+                create_scored_result("IT_SYSTEM_001", "Pilot", "Low", 10000.0, 50000.0, 0.5),
+                create_scored_result("DATACENTER_002", "Full", "Low", 10000.0, 200000.0, 0.5),
+                create_scored_result("WAREHOUSE_004", "Full", "Low", 50000.0, 100000.0, 0.5),
+            ],
+            Duration::from_millis(10),
+        );
+
+        state.toggle_cost_benefit_sort();
+        assert_eq!(state.display_order, vec![2, 1, 3, 0]);
+
+        state.apply_filter("full");
+        assert_eq!(state.display_order, vec![2, 3]);
+
+        state.toggle_cost_benefit_sort();
+        assert_eq!(state.display_order, vec![2, 3]);
+        state.apply_filter("");
+        assert_eq!(state.display_order, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_export_csv_uses_timestamped_name_and_selection_columns() {
+        let dir = std::env::temp_dir().join(format!("capalloc_tui_export_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let read = |path: &Path| {
+            let mut reader = csv::Reader::from_path(path).unwrap();
+            let headers = reader.headers().unwrap().clone();
+            let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+            (headers, rows)
+        };
+
+        // Without a budget only the result columns are written
+        let unoptimized = create_test_state();
+        let plain = unoptimized.export_csv(&dir, 1700000000).unwrap();
+        assert_eq!(plain, dir.join("capalloc_export_1700000000.csv"));
+        assert!(matches!(
+            unoptimized.export_csv(&dir, 1700000000),
+            Err(CapAllocError::Validation(_))
+        ));
+        let (headers, rows) = read(&plain);
+        assert_eq!(headers.len(), RiskCalculationResult::ROW_LABELS.len());
+        assert_eq!(rows.len(), unoptimized.results.len());
+
+        let state = AppState::with_optimization(
+            vec![
+                create_test_result("IT_SYSTEM_001", "Pilot_Program"),
+                create_test_result("DATACENTER_002", "Full_Implementation"),
+            ],
+            Duration::ZERO,
+            vec!["IT_SYSTEM_001 (Pilot_Program)".to_string()],
+            vec![],
+            vec!["DATACENTER_002 (Full_Implementation)".to_string()],
+            100000.0,
+        );
+        let optimized = state.export_csv(&dir, 1700000001).unwrap();
+        assert_eq!(optimized, dir.join("capalloc_export_1700000001.csv"));
+        let (headers, rows) = read(&optimized);
+        std::fs::remove_dir_all(&dir).ok();
+
+        let labels = RiskCalculationResult::ROW_LABELS.len();
+        assert_eq!(&headers[labels], "selected_by_risk");
+        assert_eq!(&headers[labels + 2], "selected_by_combined");
+        assert_eq!(&rows[0][0], "IT_SYSTEM_001");
+        assert_eq!(
+            (&rows[0][labels], &rows[0][labels + 1], &rows[0][labels + 2]),
+            ("true", "false", "false")
+        );
+        assert_eq!(&rows[1][labels + 2], "true");
+
+        // A directory that does not exist surfaces as an error
+        assert!(state.export_csv(&dir.join("missing"), 1).is_err());
+    }
+
+    #[test]
+    fn test_position_label_follows_navigation_and_filter() {
+        let mut state = create_test_state();
+        let total = state.results.len();
+        assert_eq!(state.position_label(), format!("1 / {}", total));
+
+        state.select_next();
+        assert_eq!(state.position_label(), format!("2 / {}", total));
+        state.page_down(100);
+        assert_eq!(state.position_label(), format!("{} / {}", total, total));
+        state.page_up(1);
+        assert_eq!(state.position_label(), format!("{} / {}", total - 1, total));
+
+        // Filtering counts only the matching rows and restarts at the top
+        state.apply_filter("IT_SYSTEM");
+        assert_eq!(state.position_label(), "1 / 2");
+        state.apply_filter("no such asset");
+        assert_eq!(state.position_label(), "0 / 0");
+    }
+}
@@ -1,6 +1,8 @@
 mod app_state;
 pub mod renderer;
+mod theme;
 pub mod ui_renderer;
 
-pub use app_state::AppState;
+pub use app_state::{AppState, Strategy};
 pub use renderer::render;
+pub use theme::{Theme, THEME_NAMES};
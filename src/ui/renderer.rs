@@ -1,15 +1,54 @@
 use crate::ui::AppState;
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     Frame,
 };
 
 pub fn render(f: &mut Frame, state: &AppState) {
+    // With a budget, a footer below both panels shows each strategy's totals
+    // and how far the strategies agree
+    let area = if state.optimization_budget.is_some() {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(6)])
+            .split(f.area());
+        let footer = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(36)])
+            .split(rows[1]);
+        crate::ui::ui_renderer::render_portfolio_totals(f, state, footer[0]);
+        crate::ui::ui_renderer::render_selection_overlap(f, state, footer[1]);
+        rows[0]
+    } else {
+        f.area()
+    };
+
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-        .split(f.area());
+        .split(area);
 
     crate::ui::ui_renderer::render_list(f, state, chunks[0]);
-    crate::ui::ui_renderer::render_details(f, state, chunks[1]);
+
+    if state.show_summary {
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(14), Constraint::Min(0)])
+            .split(chunks[1]);
+
+        crate::ui::ui_renderer::render_summary(f, state, right[0]);
+        render_right_panel(f, state, right[1]);
+    } else {
+        render_right_panel(f, state, chunks[1]);
+    }
+}
+
+fn render_right_panel(f: &mut Frame, state: &AppState, area: Rect) {
+    if state.show_histogram {
+        crate::ui::ui_renderer::render_histogram(f, state, area);
+    } else if state.show_comparison {
+        crate::ui::ui_renderer::render_comparison(f, state, area);
+    } else {
+        crate::ui::ui_renderer::render_details(f, state, area);
+    }
 }
@@ -0,0 +1,182 @@
+use crate::ui::Strategy;
+use ratatui::style::{Color, Modifier, Style};
+
+/// Named styles used by the TUI, so the palette can be swapped as a whole
+/// Headings add `Modifier::BOLD` on top of these at the call site
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Currently selected row
+    pub highlight: Style,
+    /// Alternative IDs, ROI and ratios
+    pub accent: Style,
+    /// Costs and post-action risk
+    pub cost: Style,
+    /// Risk reduction and cost effectiveness
+    pub benefit: Style,
+    /// Priority and criticality scores
+    pub score: Style,
+    /// Consequence of failure and baseline risk
+    pub hazard: Style,
+    /// Plain values such as IDs, PoF and payback
+    pub value: Style,
+    /// De-emphasized text
+    pub muted: Style,
+    /// Risk, priority and combined strategy markers (bold in the list)
+    pub risk_strategy: Style,
+    pub priority_strategy: Style,
+    pub combined_strategy: Style,
+    /// Safety levels classified as critical, high risk, and neither
+    pub critical: Style,
+    pub high_risk: Style,
+    pub normal_risk: Style,
+}
+
+/// Theme names accepted by `Theme::from_name`
+pub const THEME_NAMES: [&str; 3] = ["default", "color-blind", "mono"];
+
+impl Theme {
+    /// Marker style of `strategy`
+    pub fn strategy(&self, strategy: Strategy) -> Style {
+        match strategy {
+            Strategy::Risk => self.risk_strategy,
+            Strategy::Priority => self.priority_strategy,
+            Strategy::Combined => self.combined_strategy,
+        }
+    }
+
+    /// Theme for a `--theme` name, see `THEME_NAMES`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "default" => Some(Self::default()),
+            "color-blind" | "colorblind" => Some(Self::color_blind()),
+            "mono" | "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Okabe-Ito palette: strategies and safety levels never rely on telling
+    /// red from green, and critical levels are also underlined
+    pub fn color_blind() -> Self {
+        let blue = Color::Rgb(0, 114, 178);
+        let sky_blue = Color::Rgb(86, 180, 233);
+        let orange = Color::Rgb(230, 159, 0);
+        let vermillion = Color::Rgb(213, 94, 0);
+        let yellow = Color::Rgb(240, 228, 66);
+        let purple = Color::Rgb(204, 121, 167);
+
+        Self {
+            highlight: Style::default()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            accent: Style::default().fg(sky_blue),
+            cost: Style::default().fg(yellow),
+            benefit: Style::default().fg(blue),
+            score: Style::default().fg(purple),
+            hazard: Style::default().fg(vermillion),
+            value: Style::default().fg(Color::White),
+            muted: Style::default().fg(Color::Gray),
+            risk_strategy: Style::default().fg(blue),
+            priority_strategy: Style::default().fg(orange),
+            combined_strategy: Style::default().fg(purple),
+            critical: Style::default()
+                .fg(vermillion)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            high_risk: Style::default().fg(orange),
+            normal_risk: Style::default().fg(sky_blue),
+        }
+    }
+
+    /// No colors, for terminals without color support: emphasis uses
+    /// reverse video, bold, underline and dim only
+    pub fn monochrome() -> Self {
+        Self {
+            highlight: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            accent: Style::default(),
+            cost: Style::default(),
+            benefit: Style::default().add_modifier(Modifier::BOLD),
+            score: Style::default().add_modifier(Modifier::ITALIC),
+            hazard: Style::default().add_modifier(Modifier::UNDERLINED),
+            value: Style::default(),
+            muted: Style::default().add_modifier(Modifier::DIM),
+            risk_strategy: Style::default(),
+            priority_strategy: Style::default(),
+            combined_strategy: Style::default(),
+            critical: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            high_risk: Style::default().add_modifier(Modifier::BOLD),
+            normal_risk: Style::default(),
+        }
+    }
+}
+
+impl Default for Theme {
+    /// Red/yellow/green palette
+    fn default() -> Self {
+        Self {
+            highlight: Style::default()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            accent: Style::default().fg(Color::Cyan),
+            cost: Style::default().fg(Color::Yellow),
+            benefit: Style::default().fg(Color::Green),
+            score: Style::default().fg(Color::Magenta),
+            hazard: Style::default().fg(Color::Red),
+            value: Style::default().fg(Color::White),
+            muted: Style::default().fg(Color::Gray),
+            risk_strategy: Style::default().fg(Color::Red),
+            priority_strategy: Style::default().fg(Color::Yellow),
+            combined_strategy: Style::default().fg(Color::Green),
+            critical: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            high_risk: Style::default().fg(Color::Yellow),
+            normal_risk: Style::default().fg(Color::Green),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_themes_differ() {
+        let default = Theme::default();
+        let color_blind = Theme::color_blind();
+        let mono = Theme::monochrome();
+
+        assert_ne!(default, color_blind);
+        assert_ne!(default, mono);
+        assert_ne!(color_blind, mono);
+
+        // The color-blind palette separates risk and combined without red vs green
+        assert_ne!(default.risk_strategy, color_blind.risk_strategy);
+        assert_ne!(color_blind.risk_strategy.fg, Some(Color::Red));
+        assert_ne!(color_blind.combined_strategy.fg, Some(Color::Green));
+
+        // Monochrome sets no colors at all
+        for style in [
+            mono.highlight,
+            mono.accent,
+            mono.cost,
+            mono.benefit,
+            mono.score,
+            mono.hazard,
+            mono.risk_strategy,
+            mono.critical,
+            mono.high_risk,
+        ] {
+            assert_eq!(style.fg, None);
+            assert_eq!(style.bg, None);
+        }
+        assert_ne!(mono.critical, mono.normal_risk);
+    }
+
+    #[test]
+    fn test_theme_from_name() {
+        for name in THEME_NAMES {
+            assert!(Theme::from_name(name).is_some(), "{}", name);
+        }
+        assert_eq!(Theme::from_name(" MONO "), Some(Theme::monochrome()));
+        assert_eq!(Theme::from_name("neon"), None);
+    }
+}
@@ -1,35 +1,20 @@
-use crate::domain::RiskCalculationResult;
+use crate::domain::{
+    average_time_ms, histogram, RiskCalculationResult, RowKind, HISTOGRAM_BUCKETS,
+};
 use crate::ui::AppState;
 use ratatui::{
-    layout::Rect,
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Margin, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Table, Wrap,
+    },
     Frame,
 };
 
-fn format_money(value: f64) -> String {
-    let abs_value = value.abs();
-    let formatted = format!("{:.2}", abs_value);
-
-    let parts: Vec<&str> = formatted.split('.').collect();
-    let integer_part = parts[0];
-    let decimal_part = if parts.len() > 1 { parts[1] } else { "00" };
-
-    let mut result = String::new();
-    for (i, ch) in integer_part.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
-        }
-        result.push(ch);
-    }
-
-    let formatted_integer: String = result.chars().rev().collect();
-    let sign = if value < 0.0 { "-" } else { "" };
-    format!("{}{}.{}", sign, formatted_integer, decimal_part)
-}
-
 pub fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
+    let theme = &state.theme;
     let items: Vec<ListItem> = state
         .display_order
         .iter()
@@ -42,10 +27,7 @@ pub fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
 
             let style = if display_idx == state.selected {
                 // Currently selected row - white background
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::White)
-                    .add_modifier(Modifier::BOLD)
+                theme.highlight
             } else {
                 Style::default()
             };
@@ -54,10 +36,7 @@ pub fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
             // Asset(20) Alternative(18) R(2) P(2) C(2)
             let mut spans = vec![
                 Span::raw(format!("{:<20} ", result.asset.asset_id)),
-                Span::styled(
-                    format!("{:<18}", result.asset.alternative_id),
-                    Style::default().fg(Color::Cyan),
-                ),
+                Span::styled(format!("{:<18}", result.asset.alternative_id), theme.accent),
             ];
 
             // Add strategy indicators
@@ -65,21 +44,17 @@ pub fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
                 // Risk strategy column
                 spans.push(Span::styled(
                     if is_risk { " R" } else { "  " },
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    theme.risk_strategy.add_modifier(Modifier::BOLD),
                 ));
                 // Priority strategy column
                 spans.push(Span::styled(
                     if is_priority { " P" } else { "  " },
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
+                    theme.priority_strategy.add_modifier(Modifier::BOLD),
                 ));
                 // Combined strategy column
                 spans.push(Span::styled(
                     if is_combined { " C" } else { "  " },
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD),
+                    theme.combined_strategy.add_modifier(Modifier::BOLD),
                 ));
             }
 
@@ -88,34 +63,79 @@ pub fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
         })
         .collect();
 
-    let title = if let Some(budget) = state.optimization_budget {
+    let count = if state.display_order.len() == state.results.len() {
+        state.results.len().to_string()
+    } else {
+        format!("{}/{}", state.display_order.len(), state.results.len())
+    };
+
+    let mut title = if let Some(budget) = state.optimization_budget {
         format!(
-            " Asset Alternatives ({}) | Budget: ${} | R=Risk P=Priority C=Combined ",
-            state.results.len(),
-            format_money(budget).trim_end_matches(".00"),
+            " Asset Alternatives ({}) | Budget: {} | R=Risk P=Priority C=Combined ",
+            count,
+            state
+                .money_format
+                .currency(budget)
+                .replace(&format!("{}00", state.money_format.decimal_separator), ""),
         )
     } else {
         format!(
             " Asset Alternatives ({}) | Total Time: {:.2}ms ",
-            state.results.len(),
+            count,
             state.total_time.as_secs_f64() * 1000.0
         )
     };
 
+    if let Some(input) = &state.budget_input {
+        title = format!(
+            " Asset Alternatives ({}) | New Budget: {}{}_ (Enter to optimize, Esc to cancel) ",
+            count, state.money_format.symbol, input
+        );
+    }
+
+    if state.sort_by_cost_benefit {
+        title.push_str("| Sort: Risk/Cost ");
+    }
+
+    if state.selected_only {
+        title.push_str("| Selected Only ");
+    }
+
+    if state.search_mode || !state.filter.is_empty() {
+        title.push_str(&format!(
+            "| Search: {}{} ",
+            state.filter,
+            if state.search_mode { "_" } else { "" }
+        ));
+    }
+
+    title.push_str(&format!("| Row {} ", state.position_label()));
+
+    if let Some(message) = state.status_message() {
+        title.push_str(&format!("| {} ", message));
+    }
+
     let list = List::new(items)
         .block(Block::default().borders(Borders::ALL).title(title))
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        );
+        .highlight_style(theme.highlight);
 
     // Create a ListState to enable scrolling
     let mut list_state = ListState::default();
     list_state.select(Some(state.selected));
 
     f.render_stateful_widget(list, area, &mut list_state);
+
+    // Scrollbar on the right border, between the corners
+    let mut scrollbar_state =
+        ScrollbarState::new(state.display_order.len()).position(state.selected);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut scrollbar_state,
+    );
 }
 
 pub fn render_details(f: &mut Frame, state: &AppState, area: Rect) {
@@ -123,7 +143,7 @@ pub fn render_details(f: &mut Frame, state: &AppState, area: Rect) {
         let content = if state.expanded {
             render_expanded_view(result, state)
         } else {
-            render_summary_view(result)
+            render_summary_view(result, state)
         };
 
         let paragraph = Paragraph::new(content)
@@ -142,7 +162,293 @@ pub fn render_details(f: &mut Frame, state: &AppState, area: Rect) {
     }
 }
 
-fn render_summary_view(result: &RiskCalculationResult) -> Vec<Line<'_>> {
+/// Side-by-side table of every alternative of the selected asset,
+/// with the selected alternative highlighted
+pub fn render_comparison(f: &mut Frame, state: &AppState, area: Rect) {
+    let Some(current) = state.get_selected() else {
+        return;
+    };
+    let money = &state.money_format;
+    let theme = &state.theme;
+
+    // `to_row` fields shown, in row order, with their styles
+    let columns = [
+        ("alternative_id", theme.accent),
+        ("cost", theme.cost),
+        ("risk_reduction", theme.benefit),
+        ("roi", Style::default()),
+        ("priority_score", theme.score),
+    ];
+    let header = Row::new(
+        columns
+            .iter()
+            .map(|(label, _)| RiskCalculationResult::row_column(label).0)
+            .chain(["Picked"]),
+    )
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = state
+        .alternatives_for(&current.asset.asset_id)
+        .into_iter()
+        .map(|result| {
+            let picked: String = [
+                (state.is_selected_by_risk(result), 'R'),
+                (state.is_selected_by_priority(result), 'P'),
+                (state.is_selected_by_combined(result), 'C'),
+            ]
+            .iter()
+            .filter(|(selected, _)| *selected)
+            .map(|(_, mark)| *mark)
+            .collect();
+            let style = if result.asset.alternative_id == current.asset.alternative_id {
+                theme.highlight
+            } else {
+                Style::default()
+            };
+
+            let cells = result.to_row().into_iter().filter_map(|(label, value)| {
+                let (_, style) = columns.iter().find(|(column, _)| *column == label)?;
+                Some(Cell::from(row_text(state, label, &value)).style(*style))
+            });
+            Row::new(cells.chain([Cell::from(picked)])).style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(24),
+            Constraint::Length(16),
+            Constraint::Length(18),
+            Constraint::Length(10),
+            Constraint::Length(14),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header)
+    .block(Block::default().borders(Borders::ALL).title(format!(
+        " Alternatives for {} (c to close) ",
+        current.asset.asset_id
+    )));
+
+    f.render_widget(table, area);
+}
+
+/// Display text of the `to_row` value labelled `label`: money in the display
+/// currency, numbers to the configured places for their kind, and N/A when undefined
+fn row_text(state: &AppState, label: &'static str, value: &str) -> String {
+    let Ok(number) = value.parse::<f64>() else {
+        return if value.is_empty() {
+            "N/A".to_string()
+        } else {
+            value.to_string()
+        };
+    };
+    match RiskCalculationResult::row_column(label).1 {
+        RowKind::Text => value.to_string(),
+        RowKind::Money => state.money_format.currency(number),
+        RowKind::Score => state.score_text(number),
+        RowKind::Rating => state.rating_text(number),
+        RowKind::Months => state.months_text(number),
+    }
+}
+
+/// Selected count, cost, risk reduction and priority for each strategy
+pub fn render_portfolio_totals(f: &mut Frame, state: &AppState, area: Rect) {
+    let money = &state.money_format;
+    let theme = &state.theme;
+    let header = Row::new(vec![
+        "Strategy",
+        "Selected",
+        "Total Cost",
+        "Risk Reduction",
+        "Priority",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = state
+        .portfolio_totals()
+        .into_iter()
+        .map(|(strategy, totals)| {
+            Row::new(vec![
+                Cell::from(strategy.label())
+                    .style(theme.strategy(strategy).add_modifier(Modifier::BOLD)),
+                Cell::from(totals.selected.to_string()),
+                Cell::from(money.currency(totals.total_cost)).style(theme.cost),
+                Cell::from(money.currency(totals.total_risk_reduction)).style(theme.benefit),
+                Cell::from(state.score_text(totals.total_priority_score)).style(theme.score),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(20),
+            Constraint::Length(20),
+            Constraint::Length(12),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Portfolio Totals "),
+    );
+
+    f.render_widget(table, area);
+}
+
+/// Venn-style counts of alternatives picked by all three, two or one strategies
+pub fn render_selection_overlap(f: &mut Frame, state: &AppState, area: Rect) {
+    let theme = &state.theme;
+    let overlap = state.selection_overlap();
+    let label = Style::default().add_modifier(Modifier::BOLD);
+    let risk = theme.risk_strategy.add_modifier(Modifier::BOLD);
+    let priority = theme.priority_strategy.add_modifier(Modifier::BOLD);
+    let combined = theme.combined_strategy.add_modifier(Modifier::BOLD);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("All three:   ", label),
+            Span::styled(overlap.all_three.to_string(), theme.benefit),
+        ]),
+        Line::from(vec![
+            Span::styled("Exactly two: ", label),
+            Span::raw(format!("{:<4}", overlap.exactly_two())),
+            Span::styled("RP ", risk),
+            Span::raw(format!("{} ", overlap.first_second)),
+            Span::styled("RC ", combined),
+            Span::raw(format!("{} ", overlap.first_third)),
+            Span::styled("PC ", priority),
+            Span::raw(overlap.second_third.to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled("One only:    ", label),
+            Span::raw(format!("{:<4}", overlap.exactly_one())),
+            Span::styled("R ", risk),
+            Span::raw(format!("{} ", overlap.first_only)),
+            Span::styled("P ", priority),
+            Span::raw(format!("{} ", overlap.second_only)),
+            Span::styled("C ", combined),
+            Span::raw(overlap.third_only.to_string()),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Strategy Overlap "),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+/// Bucketed counts of priority scores across all results, as horizontal bars
+pub fn render_histogram(f: &mut Frame, state: &AppState, area: Rect) {
+    let theme = &state.theme;
+    let scores: Vec<f64> = state
+        .results
+        .iter()
+        .map(|r| r.effective_priority())
+        .collect();
+    let buckets = histogram(&scores, HISTOGRAM_BUCKETS);
+    let max_count = buckets
+        .iter()
+        .map(|(_, _, count)| *count)
+        .max()
+        .unwrap_or(0);
+    // Range and count take 29 columns, the borders 2
+    let bar_width = (area.width as usize).saturating_sub(31).max(1);
+
+    let lines: Vec<Line> = if buckets.is_empty() {
+        vec![Line::from("No results")]
+    } else {
+        buckets
+            .into_iter()
+            .map(|(start, end, count)| {
+                let bar_len = (count * bar_width).div_ceil(max_count.max(1));
+                Line::from(vec![
+                    Span::styled(format!("{:>8.4} - {:<8.4}", start, end), theme.value),
+                    Span::raw(format!(" {:>7} ", count)),
+                    Span::styled("█".repeat(bar_len), theme.score),
+                ])
+            })
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Priority Score Distribution (h to hide) "),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+pub fn render_summary(f: &mut Frame, state: &AppState, area: Rect) {
+    let theme = &state.theme;
+    let summary = state.summary();
+    let label = Style::default().add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Assets:             ", label),
+            Span::raw(format!(
+                "{} ({} alternatives)",
+                summary.total_assets, summary.total_alternatives
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Priority (mean):    ", label),
+            Span::styled(state.score_text(summary.mean_priority_score), theme.score),
+        ]),
+        Line::from(vec![
+            Span::styled("Priority (median):  ", label),
+            Span::styled(state.score_text(summary.median_priority_score), theme.score),
+        ]),
+        Line::from(vec![
+            Span::styled("Total Cost:         ", label),
+            Span::styled(state.money_format.currency(summary.total_cost), theme.cost),
+        ]),
+        Line::from(vec![
+            Span::styled("Achievable Risk Red:", label),
+            Span::styled(
+                format!(
+                    " {}",
+                    state
+                        .money_format
+                        .currency(summary.achievable_risk_reduction)
+                ),
+                theme.benefit,
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("By Safety Level:", label)),
+    ];
+
+    for (level, count) in &summary.safety_level_counts {
+        lines.push(Line::from(format!("  {:<12} {}", level, count)));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Dataset Summary (t to hide) "),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_summary_view<'a>(
+    result: &'a RiskCalculationResult,
+    state: &'a AppState,
+) -> Vec<Line<'a>> {
+    let money = &state.money_format;
+    let risk_levels = &state.risk_levels;
+    let theme = &state.theme;
     vec![
         Line::from(""),
         Line::from(vec![
@@ -154,10 +460,7 @@ fn render_summary_view(result: &RiskCalculationResult) -> Vec<Line<'_>> {
                 "Alternative: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::styled(
-                result.asset.alternative_id.clone(),
-                Style::default().fg(Color::Cyan),
-            ),
+            Span::styled(result.asset.alternative_id.clone(), theme.accent),
         ]),
         Line::from(""),
         Line::from(vec![
@@ -166,10 +469,8 @@ fn render_summary_view(result: &RiskCalculationResult) -> Vec<Line<'_>> {
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!("{:.4}", result.priority_score),
-                Style::default()
-                    .fg(Color::Magenta)
-                    .add_modifier(Modifier::BOLD),
+                state.score_text(result.effective_priority()),
+                theme.score.add_modifier(Modifier::BOLD),
             ),
         ]),
         Line::from(vec![
@@ -178,8 +479,8 @@ fn render_summary_view(result: &RiskCalculationResult) -> Vec<Line<'_>> {
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!("{:.2}/100", result.cost_effectiveness),
-                Style::default().fg(Color::Green),
+                format!("{}/100", state.rating_text(result.cost_effectiveness)),
+                theme.benefit,
             ),
         ]),
         Line::from(""),
@@ -188,26 +489,22 @@ fn render_summary_view(result: &RiskCalculationResult) -> Vec<Line<'_>> {
                 "Investment Cost: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::styled(
-                format!("${}", format_money(result.asset.cost_usd)),
-                Style::default().fg(Color::Yellow),
-            ),
+            Span::styled(money.currency(result.asset.cost_usd), theme.cost),
         ]),
         Line::from(vec![
             Span::styled(
                 "Risk Reduction: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::styled(
-                format!("${}", format_money(result.risk_reduction)),
-                Style::default().fg(Color::Green),
-            ),
+            Span::styled(money.currency(result.risk_reduction), theme.benefit),
         ]),
         Line::from(vec![
             Span::styled("ROI: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::styled(
-                format!("{:.4}", result.roi),
-                Style::default().fg(Color::Cyan),
+                result
+                    .roi
+                    .map_or("N/A".to_string(), |roi| state.score_text(roi)),
+                theme.accent,
             ),
         ]),
         Line::from(vec![
@@ -216,8 +513,10 @@ fn render_summary_view(result: &RiskCalculationResult) -> Vec<Line<'_>> {
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!("{:.1} months", result.payback_period),
-                Style::default().fg(Color::White),
+                result.payback_period.map_or("N/A".to_string(), |months| {
+                    format!("{} months", state.months_text(months))
+                }),
+                theme.value,
             ),
         ]),
         Line::from(""),
@@ -228,12 +527,12 @@ fn render_summary_view(result: &RiskCalculationResult) -> Vec<Line<'_>> {
             ),
             Span::styled(
                 result.asset.safety_risk_level.clone(),
-                if result.asset.is_critical() {
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
-                } else if result.asset.is_high_risk() {
-                    Style::default().fg(Color::Yellow)
+                if result.asset.is_critical(risk_levels) {
+                    theme.critical
+                } else if result.asset.is_high_risk(risk_levels) {
+                    theme.high_risk
                 } else {
-                    Style::default().fg(Color::Green)
+                    theme.normal_risk
                 },
             ),
         ]),
@@ -242,7 +541,7 @@ fn render_summary_view(result: &RiskCalculationResult) -> Vec<Line<'_>> {
                 "Criticality Score: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(format!("{:.2}", result.criticality_score)),
+            Span::raw(state.rating_text(result.criticality_score)),
         ]),
     ]
 }
@@ -251,7 +550,9 @@ fn render_expanded_view<'a>(
     result: &'a RiskCalculationResult,
     state: &'a AppState,
 ) -> Vec<Line<'a>> {
-    let avg_time = state.total_time.as_secs_f64() * 1000.0 / state.results.len() as f64;
+    let money = &state.money_format;
+    let theme = &state.theme;
+    let avg_time = average_time_ms(state.total_time, state.results.len());
     let is_risk = state.is_selected_by_risk(result);
     let is_priority = state.is_selected_by_priority(result);
     let is_combined = state.is_selected_by_combined(result);
@@ -264,193 +565,214 @@ fn render_expanded_view<'a>(
         if is_any {
             lines.push(Line::from(vec![Span::styled(
                 "SELECTED BY: ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
+                theme.value.add_modifier(Modifier::BOLD),
             )]));
 
             if is_risk {
                 lines.push(Line::from(vec![
-                    Span::styled("  R ", Style::default().fg(Color::Red)),
-                    Span::styled("Risk Reduction Strategy", Style::default().fg(Color::Red)),
+                    Span::styled("  R ", theme.risk_strategy),
+                    Span::styled("Risk Reduction Strategy", theme.risk_strategy),
                 ]));
             }
             if is_priority {
                 lines.push(Line::from(vec![
-                    Span::styled("  P ", Style::default().fg(Color::Yellow)),
-                    Span::styled(
-                        "Priority Score Strategy",
-                        Style::default().fg(Color::Yellow),
-                    ),
+                    Span::styled("  P ", theme.priority_strategy),
+                    Span::styled("Priority Score Strategy", theme.priority_strategy),
                 ]));
             }
             if is_combined {
                 lines.push(Line::from(vec![
-                    Span::styled("  C ", Style::default().fg(Color::Green)),
+                    Span::styled("  C ", theme.combined_strategy),
                     Span::styled(
-                        "Combined Strategy (60% Risk, 40% Priority)",
-                        Style::default().fg(Color::Green),
+                        format!(
+                            "Combined Strategy ({:.0}% Risk, {:.0}% Priority)",
+                            state.risk_weight * 100.0,
+                            state.priority_weight * 100.0
+                        ),
+                        theme.combined_strategy,
                     ),
                 ]));
             }
         } else {
             lines.push(Line::from(vec![Span::styled(
                 "○ Not Selected by Any Strategy",
-                Style::default().fg(Color::Gray),
+                theme.muted,
             )]));
         }
+
+        // Best-effort reason for each strategy that left it out
+        for (strategy, reason) in state.exclusion_reasons(result) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<9}", strategy.label()),
+                    theme.strategy(strategy),
+                ),
+                Span::styled(reason, theme.muted),
+            ]));
+        }
         lines.push(Line::from(""));
     }
 
     lines.extend(vec![
         Line::from(vec![Span::styled(
             "═══ ASSET INFORMATION ═══",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+            theme.accent.add_modifier(Modifier::BOLD),
         )]),
         Line::from(vec![
             Span::raw("Asset ID:        "),
-            Span::styled(
-                result.asset.asset_id.clone(),
-                Style::default().fg(Color::White),
-            ),
+            Span::styled(result.asset.asset_id.clone(), theme.value),
         ]),
         Line::from(vec![
             Span::raw("Alternative:     "),
-            Span::styled(
-                result.asset.alternative_id.clone(),
-                Style::default().fg(Color::Cyan),
-            ),
+            Span::styled(result.asset.alternative_id.clone(), theme.accent),
         ]),
         Line::from(vec![
             Span::raw("Safety Risk:     "),
             Span::styled(
                 result.asset.safety_risk_level.clone(),
-                if result.asset.is_critical() {
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
-                } else if result.asset.is_high_risk() {
-                    Style::default().fg(Color::Yellow)
+                if result.asset.is_critical(&state.risk_levels) {
+                    theme.critical
+                } else if result.asset.is_high_risk(&state.risk_levels) {
+                    theme.high_risk
                 } else {
-                    Style::default().fg(Color::Green)
+                    theme.normal_risk
                 },
             ),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
             "═══ FINANCIAL ANALYSIS ═══",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+            theme.cost.add_modifier(Modifier::BOLD),
         )]),
         Line::from(vec![
             Span::raw("Investment Cost: "),
             Span::styled(
-                format!("${:>15}", format_money(result.asset.cost_usd)),
-                Style::default().fg(Color::Yellow),
+                format!("{:>16}", money.currency(result.asset.cost_usd)),
+                theme.cost,
             ),
         ]),
         Line::from(vec![
             Span::raw("CoF (Total):     "),
             Span::styled(
-                format!("${:>15}", format_money(result.asset.cof_total_usd)),
-                Style::default().fg(Color::Red),
+                format!("{:>16}", money.currency(result.asset.cof_total_usd)),
+                theme.hazard,
             ),
         ]),
         Line::from(vec![
             Span::raw("Payback Period:  "),
             Span::styled(
-                format!("{:>12.1} months", result.payback_period),
-                Style::default().fg(Color::White),
+                result
+                    .payback_period
+                    .map_or(format!("{:>12}", "N/A"), |months| {
+                        format!("{:>12} months", state.months_text(months))
+                    }),
+                theme.value,
             ),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
             "═══ RISK METRICS ═══",
-            Style::default()
-                .fg(Color::Magenta)
-                .add_modifier(Modifier::BOLD),
+            theme.score.add_modifier(Modifier::BOLD),
         )]),
         Line::from(vec![
             Span::raw("PoF (Post):      "),
             Span::styled(
                 format!("{:>12.4}", result.asset.pof_post_action),
-                Style::default().fg(Color::White),
+                theme.value,
             ),
         ]),
         Line::from(vec![
             Span::raw("Baseline Risk:   "),
             Span::styled(
-                format!("${:>15}", format_money(result.baseline_risk)),
-                Style::default().fg(Color::Red),
+                format!("{:>16}", money.currency(result.baseline_risk)),
+                theme.hazard,
             ),
         ]),
         Line::from(vec![
             Span::raw("Post-Action Risk:"),
             Span::styled(
-                format!("${:>15}", format_money(result.post_action_risk)),
-                Style::default().fg(Color::Yellow),
+                format!("{:>16}", money.currency(result.post_action_risk)),
+                theme.cost,
             ),
         ]),
         Line::from(vec![
             Span::raw("Risk Reduction:  "),
             Span::styled(
-                format!("${:>15}", format_money(result.risk_reduction)),
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
+                format!("{:>16}", money.currency(result.risk_reduction)),
+                theme.benefit.add_modifier(Modifier::BOLD),
+            ),
+        ]),
+    ]);
+    if let (Some(low), Some(high)) = (result.risk_reduction_low, result.risk_reduction_high) {
+        lines.push(Line::from(vec![
+            Span::raw("  PoF Band:      "),
+            Span::styled(
+                format!("{} - {}", money.currency(low), money.currency(high)),
+                theme.benefit,
+            ),
+        ]));
+    }
+    lines.extend(vec![
+        Line::from(vec![
+            Span::raw("vs. Do-Nothing:  "),
+            Span::styled(
+                format!("{:>16}", money.currency(result.incremental_risk_reduction)),
+                theme.benefit,
             ),
         ]),
         Line::from(vec![
             Span::raw("Criticality:     "),
             Span::styled(
-                format!("{:>12.2}", result.criticality_score),
-                Style::default().fg(Color::Magenta),
+                format!("{:>12}", state.rating_text(result.criticality_score)),
+                theme.score,
             ),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
             "═══ OPTIMIZATION METRICS ═══",
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD),
+            theme.benefit.add_modifier(Modifier::BOLD),
         )]),
         Line::from(vec![
             Span::raw("ROI (Risk/Cost): "),
             Span::styled(
-                format!("{:>12.4}", result.roi),
-                Style::default().fg(Color::Cyan),
+                result.roi.map_or(format!("{:>12}", "N/A"), |roi| {
+                    format!("{:>12}", state.score_text(roi))
+                }),
+                theme.accent,
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Raw Risk/Cost:   "),
+            Span::styled(
+                format!("{:>12}", state.score_text(result.cost_benefit_ratio())),
+                theme.accent,
             ),
         ]),
         Line::from(vec![
             Span::raw("Cost Effectiveness:"),
             Span::styled(
-                format!("{:>12.2}/100", result.cost_effectiveness),
-                Style::default().fg(Color::Green),
+                format!("{:>12}/100", state.rating_text(result.cost_effectiveness)),
+                theme.benefit,
             ),
         ]),
         Line::from(vec![
             Span::raw("Priority Score:  "),
             Span::styled(
-                format!("{:>12.4}", result.priority_score),
-                Style::default()
-                    .fg(Color::Magenta)
-                    .add_modifier(Modifier::BOLD),
+                format!("{:>12}", state.score_text(result.effective_priority())),
+                theme.score.add_modifier(Modifier::BOLD),
             ),
         ]),
         Line::from(vec![
             Span::raw("Cost/Benefit:    "),
             Span::styled(
-                format!("{:>12.4}", result.cost_benefit_ratio()),
-                Style::default().fg(Color::Cyan),
+                format!("{:>12}", state.score_text(result.cost_benefit_ratio())),
+                theme.accent,
             ),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
             "═══ PERFORMANCE ═══",
-            Style::default()
-                .fg(Color::Gray)
-                .add_modifier(Modifier::BOLD),
+            theme.muted.add_modifier(Modifier::BOLD),
         )]),
         Line::from(vec![
             Span::raw("Calc Time:       "),